@@ -1,6 +1,6 @@
 use clap::{Parser, Subcommand};
 
-use crate::models::common::Priority;
+use crate::models::common::{BumpLevel, GraphFormat, Priority, TaskStatus};
 
 #[derive(Parser)]
 #[command(name = "tally")]
@@ -24,6 +24,24 @@ pub enum Commands {
         /// Comma-separated tags to attach.
         #[arg(short, long, value_delimiter = ',')]
         tags: Option<Vec<String>>,
+        /// Add as a subtask of the task with this ID.
+        #[arg(long)]
+        parent: Option<String>,
+        /// Comma-separated IDs of tasks that must be finished first.
+        #[arg(long, value_delimiter = ',', value_name = "IDS")]
+        depends: Vec<String>,
+        /// Due date: YYYY-MM-DD, today, tomorrow, a weekday, or an offset like 3d.
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Date to start work, in the same formats as --due.
+        #[arg(long, value_name = "DATE")]
+        scheduled: Option<String>,
+        /// Re-create the task when finished: daily, weekly, monthly, "every 2w" or every-release.
+        #[arg(long, value_name = "RULE")]
+        recur: Option<String>,
+        /// Notes to keep below the task; without a value, opens the editor.
+        #[arg(long, value_name = "TEXT", num_args = 0..=1, default_missing_value = "")]
+        note: Option<String>,
         /// Show what would be added without writing TODO.md.
         #[arg(long, default_value_t = false)]
         dry_run: bool,
@@ -35,11 +53,14 @@ pub enum Commands {
         json: bool,
     },
 
-    /// Mark a task as completed using fuzzy description matching.
+    /// Mark a task as completed using fuzzy description matching or its ID.
     Done {
-        /// Task text to match.
-        #[arg(required = true, num_args = 1..)]
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
         description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
         /// Commit hash to associate with completion.
         #[arg(short, long)]
         commit: Option<String>,
@@ -57,6 +78,124 @@ pub enum Commands {
         json: bool,
     },
 
+    /// Mark a task as in progress.
+    Start {
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
+        description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
+        /// Show what would be changed without writing TODO.md.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Auto-commit updated files after starting.
+        #[arg(long, default_value_t = false)]
+        auto: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Mark a task as blocked, optionally saying why.
+    Block {
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
+        description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
+        /// What the task is waiting on.
+        #[arg(short, long)]
+        reason: Option<String>,
+        /// Show what would be changed without writing TODO.md.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Auto-commit updated files after blocking.
+        #[arg(long, default_value_t = false)]
+        auto: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Cancel a task that will not be done.
+    Cancel {
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
+        description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
+        /// Show what would be changed without writing TODO.md.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Auto-commit updated files after cancelling.
+        #[arg(long, default_value_t = false)]
+        auto: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Edit a task's text, priority or tags, in TODO.md or a released entry.
+    Edit {
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
+        description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
+        /// Replace the task description.
+        #[arg(long = "description", value_name = "TEXT")]
+        new_description: Option<String>,
+        /// Set a new priority.
+        #[arg(short, long, value_enum)]
+        priority: Option<Priority>,
+        /// Comma-separated tags to add.
+        #[arg(long, value_delimiter = ',')]
+        add_tags: Vec<String>,
+        /// Comma-separated tags to remove.
+        #[arg(long, value_delimiter = ',')]
+        remove_tags: Vec<String>,
+        /// Comma-separated IDs of tasks to depend on.
+        #[arg(long, value_delimiter = ',', value_name = "IDS")]
+        add_depends: Vec<String>,
+        /// Comma-separated IDs of tasks to stop depending on.
+        #[arg(long, value_delimiter = ',', value_name = "IDS")]
+        remove_depends: Vec<String>,
+        /// Set the recurrence rule, or `none` to stop recurring.
+        #[arg(long, value_name = "RULE")]
+        recur: Option<String>,
+        /// Edit the task block in your editor instead.
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with_all = [
+                "new_description",
+                "priority",
+                "add_tags",
+                "remove_tags",
+                "add_depends",
+                "remove_depends",
+                "recur",
+            ]
+        )]
+        editor: bool,
+        /// Edit a released entry in CHANGELOG.md for this version instead of TODO.md.
+        #[arg(short = 'r', long, value_name = "VERSION")]
+        released: Option<String>,
+        /// Show the edited task without writing files.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Auto-commit updated files after editing.
+        #[arg(long, default_value_t = false)]
+        auto: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
     /// List tasks with optional filters.
     List {
         /// Filter by one or more comma-separated tags.
@@ -68,8 +207,31 @@ pub enum Commands {
         /// Show only completed tasks.
         #[arg(long, default_value_t = false)]
         done: bool,
+        /// Show only tasks in these comma-separated states.
+        #[arg(short, long, value_enum, value_delimiter = ',')]
+        status: Vec<TaskStatus>,
+        /// Show only open tasks whose dependencies are all finished.
+        #[arg(long, default_value_t = false)]
+        ready: bool,
+        /// Show only open tasks past their due date.
+        #[arg(long, default_value_t = false)]
+        overdue: bool,
+        /// Show only tasks due before this date.
+        #[arg(long, value_name = "DATE")]
+        due_before: Option<String>,
+        /// Show only tasks due within a span such as 7d, 2w or 1m, overdue ones included.
+        #[arg(long, value_name = "SPAN")]
+        due_within: Option<String>,
+        /// Show only tasks with custom metadata `KEY=VALUE`, or with `KEY` set; repeatable.
+        #[arg(long, value_name = "KEY[=VALUE]")]
+        meta: Vec<String>,
         /// List released tasks from CHANGELOG.md for a specific version.
-        #[arg(short = 'r', long, value_name = "VERSION")]
+        #[arg(
+            short = 'r',
+            long,
+            value_name = "VERSION",
+            conflicts_with_all = ["overdue", "due_before", "due_within"]
+        )]
         released: Option<String>,
         /// Output results as JSON.
         #[arg(long, default_value_t = false)]
@@ -79,7 +241,14 @@ pub enum Commands {
     /// Move completed unversioned tasks into CHANGELOG.md under a version.
     Semver {
         /// Version to assign (for example: 1.2.3 or v1.2.3).
-        version: String,
+        #[arg(required_unless_present_any = ["bump", "infer"])]
+        version: Option<String>,
+        /// Bump the latest released version instead of passing one explicitly.
+        #[arg(long, value_enum, conflicts_with_all = ["version", "infer"])]
+        bump: Option<BumpLevel>,
+        /// Infer the bump level from completed tasks (conventional prefixes and tags).
+        #[arg(long, default_value_t = false, conflicts_with = "version")]
+        infer: bool,
         /// Show what would be moved without writing files.
         #[arg(long, default_value_t = false)]
         dry_run: bool,
@@ -94,11 +263,14 @@ pub enum Commands {
         json: bool,
     },
 
-    /// Remove a task by fuzzy description match from TODO.md or a released entry.
+    /// Remove a task by fuzzy description match or ID from TODO.md or a released entry.
     Remove {
-        /// Task text to match.
-        #[arg(required = true, num_args = 1..)]
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
         description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
         /// Remove from CHANGELOG.md in a specific version instead of TODO.md.
         #[arg(short = 'r', long, value_name = "VERSION")]
         released: Option<String>,
@@ -118,9 +290,12 @@ pub enum Commands {
 
     /// Yank a changelog entry back into TODO as completed and unversioned.
     Yank {
-        /// Released task text to match.
-        #[arg(required = true, num_args = 1..)]
+        /// Released task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
         description: Vec<String>,
+        /// Select the released task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
         /// Optional tag filter to narrow released-task matching.
         #[arg(short, long, value_delimiter = ',')]
         tags: Option<Vec<String>>,
@@ -135,6 +310,99 @@ pub enum Commands {
         json: bool,
     },
 
+    /// Show a single task from TODO.md or CHANGELOG.md with all of its metadata.
+    Show {
+        /// Task text to match, or `#id`.
+        #[arg(required_unless_present = "id", num_args = 1..)]
+        description: Vec<String>,
+        /// Select the task by ID instead of fuzzy matching.
+        #[arg(long)]
+        id: Option<String>,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Print the task dependency graph as Graphviz DOT or Mermaid.
+    Graph {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+        /// Include tasks without dependencies or dependents.
+        #[arg(long, default_value_t = false)]
+        all: bool,
+    },
+
+    /// Revert the most recent tally command's changes to TODO.md and CHANGELOG.md.
+    Undo {
+        /// Show what would be reverted without writing files.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Reapply the most recently undone command.
+    Redo {
+        /// Show what would be reapplied without writing files.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// List recorded commands that can be undone or redone.
+    History {
+        /// Show at most this many entries, newest first.
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Output results as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Merge TODO.md or CHANGELOG.md task by task; run by git as a merge driver.
+    MergeDriver {
+        /// Common ancestor version (%O).
+        base: String,
+        /// Current branch version, overwritten with the result (%A).
+        ours: String,
+        /// Other branch version (%B).
+        theirs: String,
+        /// Path of the file being merged (%P).
+        path: Option<String>,
+    },
+
+    /// Register the tally merge driver in .git/config and .gitattributes.
+    InstallMergeDriver {
+        /// Show what would be configured without changing anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Install or remove the git hooks that mark tasks done from commits.
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommand,
+    },
+
+    /// Run one of tally's git hooks; called by the scripts `hooks install` writes.
+    Hook {
+        #[command(subcommand)]
+        hook: HookCommand,
+    },
+
+    /// Inspect tally configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
     /// Scan for task updates from git commits and/or source TODO markers.
     Scan {
         /// Auto-accept git-based done matches without prompting.
@@ -143,7 +411,9 @@ pub enum Commands {
         /// Show what would change without writing files.
         #[arg(long, default_value_t = false)]
         dry_run: bool,
-        /// Include git commit scanning.
+        /// Include git commit scanning. Without --since, --range,
+        /// --all-branches or --since-last-release, this is incremental: it
+        /// reads only the commits after the one recorded in `.tally/scan-head`.
         #[arg(long, default_value_t = false)]
         git: bool,
         /// Include source TODO scanning.
@@ -152,6 +422,114 @@ pub enum Commands {
         /// Include source DONE scanning.
         #[arg(long, default_value_t = false)]
         done: bool,
+        /// Scan commits after a revision, tag or date (implies --git).
+        #[arg(long, conflicts_with_all = ["range", "since_last_release"])]
+        since: Option<String>,
+        /// Scan an explicit `A..B` commit range (implies --git).
+        #[arg(long, conflicts_with_all = ["since_last_release", "all_branches"])]
+        range: Option<String>,
+        /// Scan commits on all branches (implies --git).
+        #[arg(long, default_value_t = false)]
+        all_branches: bool,
+        /// Scan commits after the newest release's git tag (implies --git).
+        #[arg(long, default_value_t = false)]
+        since_last_release: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HooksCommand {
+    /// Write commit-msg and post-commit hooks into the repository.
+    Install {
+        /// Also pre-fill a `done:` block from in-progress tasks.
+        #[arg(long, default_value_t = false)]
+        prepare_commit_msg: bool,
+        /// Show which hooks would be written without writing them.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Remove the hooks tally wrote, keeping any others.
+    Uninstall {
+        /// Show which hooks would be removed without removing them.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HookCommand {
+    /// Mark tasks done that the latest commit's `done:` block names.
+    PostCommit,
+    /// Warn about `done:` items that match no open task.
+    CommitMsg {
+        /// File holding the commit message.
+        file: String,
+    },
+    /// Pre-fill a `done:` block from in-progress tasks.
+    PrepareCommitMsg {
+        /// File holding the commit message.
+        file: String,
+        /// Where the message came from, as passed by git.
+        source: Option<String>,
+        /// Commit being amended, as passed by git.
+        sha: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Show the effective configuration after layering `.tally.toml` over the global config.
+    Show {
+        /// Show which file each value came from.
+        #[arg(long, default_value_t = false)]
+        origin: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Print the effective value of one key.
+    Get {
+        /// Dotted key, e.g. `matching.task_min_score`.
+        key: String,
+        /// Read the value from `.tally.toml` only.
+        #[arg(long, default_value_t = false)]
+        project: bool,
+        /// Output result as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Set a key in the global config, or in `.tally.toml` with `--project`.
+    Set {
+        /// Dotted key, e.g. `matching.task_min_score`.
+        key: String,
+        /// New value; lists may be given comma-separated.
+        value: String,
+        /// Write to the project's `.tally.toml`.
+        #[arg(long, default_value_t = false)]
+        project: bool,
+    },
+    /// Remove a key so the default or global value applies again.
+    Unset {
+        /// Dotted key, e.g. `matching.task_min_score`.
+        key: String,
+        /// Remove from the project's `.tally.toml`.
+        #[arg(long, default_value_t = false)]
+        project: bool,
+    },
+    /// List the keys set in the global config, or in `.tally.toml` with `--project`.
+    List {
+        /// List the project's `.tally.toml`.
+        #[arg(long, default_value_t = false)]
+        project: bool,
         /// Output result as JSON.
         #[arg(long, default_value_t = false)]
         json: bool,
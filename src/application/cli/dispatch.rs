@@ -1,22 +1,109 @@
 use anyhow::Result;
+use std::env;
 
-use crate::application::cli::arguments::{Cli, Commands};
+use crate::application::cli::arguments::{Cli, Commands, ConfigCommand, HookCommand, HooksCommand};
 use crate::application::commands;
+use crate::models::common::TaskStatus;
+use crate::services::storage::history_storage::{self, HistoryStorage};
+use crate::services::storage::lock;
+use crate::utils::project_paths::ProjectPaths;
 
 impl Cli {
     pub fn run(self) -> Result<()> {
-        match self.command {
+        if !self.command.is_journaled() {
+            return self.command.dispatch();
+        }
+
+        // Outside a project nothing is locked or journaled, so no `.tally/`
+        // is left behind; only commands that create a project fall back to
+        // the current directory.
+        let paths = match ProjectPaths::get_paths() {
+            Ok(paths) => paths,
+            Err(_) if self.command.creates_project() => match ProjectPaths::for_current_dir() {
+                Ok(paths) => paths,
+                Err(_) => return self.command.dispatch(),
+            },
+            Err(_) => return self.command.dispatch(),
+        };
+
+        // The TODO and CHANGELOG files are journaled for `undo` and `redo`;
+        // the lock keeps other tally processes out of the snapshot.
+        let _lock = lock::acquire(&paths.state_dir)?;
+        let before = history_storage::snapshot(&paths.root, &paths.tally_files());
+        let result = self.command.dispatch();
+        let after = history_storage::snapshot(&paths.root, &paths.tally_files());
+
+        let changes = history_storage::file_changes(before, after);
+        if changes.iter().any(|file| file.before != file.after) {
+            let label = env::args().skip(1).collect::<Vec<_>>().join(" ");
+            HistoryStorage::new(&paths.state_dir)?.record(&label, changes)?;
+        }
+
+        result
+    }
+}
+
+impl Commands {
+    /// Whether the command may write TODO.md or CHANGELOG.md.
+    fn is_journaled(&self) -> bool {
+        !matches!(
+            self,
+            Commands::List { .. }
+                | Commands::Show { .. }
+                | Commands::Graph { .. }
+                | Commands::Undo { .. }
+                | Commands::Redo { .. }
+                | Commands::History { .. }
+                | Commands::MergeDriver { .. }
+                | Commands::InstallMergeDriver { .. }
+                | Commands::Hooks { .. }
+                | Commands::Hook {
+                    hook: HookCommand::CommitMsg { .. } | HookCommand::PrepareCommitMsg { .. }
+                }
+                | Commands::Config { .. }
+        )
+    }
+
+    /// Whether the command writes TODO.md when there is no project yet.
+    fn creates_project(&self) -> bool {
+        matches!(self, Commands::Add { .. } | Commands::Scan { .. })
+    }
+
+    fn dispatch(self) -> Result<()> {
+        match self {
             Commands::Add {
                 description,
                 priority,
                 tags,
+                parent,
+                depends,
+                due,
+                scheduled,
+                recur,
+                note,
+                dry_run,
+                auto,
+                json,
+            } => commands::cmd_add(
+                join_words(description),
+                commands::add::NewTask {
+                    priority,
+                    tags,
+                    parent,
+                    depends,
+                    due,
+                    scheduled,
+                    recur,
+                    note,
+                },
                 dry_run,
                 auto,
                 json,
-            } => commands::cmd_add(join_words(description), priority, tags, dry_run, auto, json),
+            ),
 
             Commands::Done {
                 description,
+                id,
                 commit,
                 version,
                 dry_run,
@@ -24,6 +111,7 @@ impl Cli {
                 json,
             } => commands::cmd_done(
                 join_words(description),
+                id,
                 commit,
                 version,
                 dry_run,
@@ -31,38 +119,161 @@ impl Cli {
                 json,
             ),
 
+            Commands::Start {
+                description,
+                id,
+                dry_run,
+                auto,
+                json,
+            } => commands::cmd_set_status(
+                join_words(description),
+                id,
+                TaskStatus::InProgress,
+                None,
+                dry_run,
+                auto,
+                json,
+            ),
+
+            Commands::Block {
+                description,
+                id,
+                reason,
+                dry_run,
+                auto,
+                json,
+            } => commands::cmd_set_status(
+                join_words(description),
+                id,
+                TaskStatus::Blocked,
+                reason,
+                dry_run,
+                auto,
+                json,
+            ),
+
+            Commands::Cancel {
+                description,
+                id,
+                dry_run,
+                auto,
+                json,
+            } => commands::cmd_set_status(
+                join_words(description),
+                id,
+                TaskStatus::Cancelled,
+                None,
+                dry_run,
+                auto,
+                json,
+            ),
+
+            Commands::Edit {
+                description,
+                id,
+                new_description,
+                priority,
+                add_tags,
+                remove_tags,
+                add_depends,
+                remove_depends,
+                recur,
+                editor,
+                released,
+                dry_run,
+                auto,
+                json,
+            } => commands::cmd_edit(
+                join_words(description),
+                id,
+                commands::edit::TaskEdits {
+                    description: new_description,
+                    priority,
+                    add_tags,
+                    remove_tags,
+                    add_depends,
+                    remove_depends,
+                    recur,
+                    editor,
+                },
+                released,
+                dry_run,
+                auto,
+                json,
+            ),
+
             Commands::List {
                 tags,
                 priority,
                 done,
+                status,
+                ready,
+                overdue,
+                due_before,
+                due_within,
+                meta,
+                released,
+                json,
+            } => commands::cmd_list(
+                commands::list::TaskFilter {
+                    tags,
+                    priority,
+                    done,
+                    status,
+                    ready,
+                    overdue,
+                    due_before,
+                    due_within,
+                    meta,
+                },
                 released,
                 json,
-            } => commands::cmd_list(tags, priority, done, released, json),
+            ),
 
             Commands::Semver {
                 version,
+                bump,
+                infer,
                 dry_run,
                 summary,
                 auto,
                 json,
-            } => commands::cmd_semver(version, dry_run, summary, auto, json),
+            } => commands::cmd_semver(version, bump, infer, dry_run, summary, auto, json),
 
             Commands::Remove {
                 description,
+                id,
                 released,
                 tags,
                 dry_run,
                 auto,
                 json,
-            } => commands::cmd_remove(join_words(description), released, tags, dry_run, auto, json),
+            } => commands::cmd_remove(
+                join_words(description),
+                id,
+                released,
+                tags,
+                dry_run,
+                auto,
+                json,
+            ),
 
             Commands::Yank {
                 description,
+                id,
                 tags,
                 dry_run,
                 auto,
                 json,
-            } => commands::cmd_yank(join_words(description), tags, dry_run, auto, json),
+            } => commands::cmd_yank(join_words(description), id, tags, dry_run, auto, json),
+
+            Commands::Show {
+                description,
+                id,
+                json,
+            } => commands::cmd_show(join_words(description), id, json),
+
+            Commands::Graph { format, all } => commands::cmd_graph(format, all),
 
             Commands::Scan {
                 auto,
@@ -70,8 +281,75 @@ impl Cli {
                 git,
                 todo,
                 done,
+                since,
+                range,
+                all_branches,
+                since_last_release,
+                json,
+            } => commands::cmd_scan(
+                auto,
+                dry_run,
+                git,
+                todo,
+                done,
+                commands::scan::CommitRange {
+                    since,
+                    range,
+                    all_branches,
+                    since_last_release,
+                },
                 json,
-            } => commands::cmd_scan(auto, dry_run, git, todo, done, json),
+            ),
+
+            Commands::Undo { dry_run, json } => commands::cmd_undo(dry_run, json),
+
+            Commands::Redo { dry_run, json } => commands::cmd_redo(dry_run, json),
+
+            Commands::History { limit, json } => commands::cmd_history(limit, json),
+
+            Commands::MergeDriver {
+                base,
+                ours,
+                theirs,
+                path,
+            } => commands::cmd_merge_driver(base, ours, theirs, path),
+
+            Commands::InstallMergeDriver { dry_run, json } => {
+                commands::cmd_install_merge_driver(dry_run, json)
+            }
+
+            Commands::Hooks { command } => match command {
+                HooksCommand::Install {
+                    prepare_commit_msg,
+                    dry_run,
+                    json,
+                } => commands::cmd_hooks_install(prepare_commit_msg, dry_run, json),
+                HooksCommand::Uninstall { dry_run, json } => {
+                    commands::cmd_hooks_uninstall(dry_run, json)
+                }
+            },
+
+            Commands::Hook { hook } => match hook {
+                HookCommand::PostCommit => commands::cmd_hook_post_commit(),
+                HookCommand::CommitMsg { file } => commands::cmd_hook_commit_msg(file),
+                HookCommand::PrepareCommitMsg { file, source, .. } => {
+                    commands::cmd_hook_prepare_commit_msg(file, source)
+                }
+            },
+
+            Commands::Config { command } => match command {
+                ConfigCommand::Show { origin, json } => commands::cmd_config_show(origin, json),
+                ConfigCommand::Get { key, project, json } => {
+                    commands::cmd_config_get(key, project, json)
+                }
+                ConfigCommand::Set {
+                    key,
+                    value,
+                    project,
+                } => commands::cmd_config_set(key, value, project),
+                ConfigCommand::Unset { key, project } => commands::cmd_config_unset(key, project),
+                ConfigCommand::List { project, json } => commands::cmd_config_list(project, json),
+            },
         }
     }
 }
@@ -1,32 +1,79 @@
-use crate::models::common::Priority;
-use crate::models::tasks::Task;
+use crate::models::AppConfig;
+use crate::models::common::{Priority, Version};
+use crate::models::tasks::{Task, dependencies, task};
 use crate::output;
-use crate::services::git;
+use crate::services::storage::changelog_storage::ChangelogStorage;
 use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
+use crate::services::{git, manifest};
+use crate::utils::dates;
 use crate::utils::project_paths::ProjectPaths;
 use crate::utils::task_input::parse_task_input;
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
+
+/// Settings for a new task. Each one overrides the matching inline marker.
+#[derive(Debug, Default)]
+pub struct NewTask {
+    pub priority: Option<Priority>,
+    pub tags: Option<Vec<String>>,
+    pub parent: Option<String>,
+    /// IDs of tasks the new task waits on.
+    pub depends: Vec<String>,
+    pub due: Option<String>,
+    pub scheduled: Option<String>,
+    /// Recurrence rule such as `weekly` or `every-release`.
+    pub recur: Option<String>,
+    /// Notes for the task; an empty string opens the editor.
+    pub note: Option<String>,
+}
 
 pub fn cmd_add(
     description: String,
-    priority: Option<Priority>,
-    tags: Option<Vec<String>>,
+    new: NewTask,
     dry_run: bool,
     auto: bool,
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
 
-    let input = parse_task_input(description, priority, tags)?;
-    let task = Task::new(
+    let input = parse_task_input(description, new.priority, new.tags)?;
+    let mut task = Task::new(
         input.description.clone(),
         input.priority,
         input.tags.clone(),
     );
+    task.due = date_flag("--due", new.due.as_deref())?.or(input.due);
+    task.scheduled = date_flag("--scheduled", new.scheduled.as_deref())?.or(input.scheduled);
+    task.recur = new.recur.as_deref().map(str::parse).transpose()?;
+    if config.preferences.record_origin {
+        task.created_at_commit = git::head_hash(&paths.root);
+        task.created_at_version = current_version(&paths, &storage, config);
+        task.created_by = git::user_name(&paths.root);
+    }
+    if let Some(parent) = new.parent {
+        let index = storage
+            .find_task_by_id(&parent)
+            .ok_or_else(|| anyhow!("No task found with ID '{}'", parent))?;
+        task.parent = Some(storage.tasks()[index].id.clone());
+    }
+    for id in &new.depends {
+        let id = task::normalize_id(id);
+        if !id.is_empty() && !task.depends.contains(&id) {
+            task.depends.push(id);
+        }
+    }
+    dependencies::check(storage.tasks(), None, &task)?;
+    task.notes = match new.note.as_deref() {
+        Some("") => note_text(&output::edit_text(
+            config.preferences.editor.as_deref(),
+            "",
+        )?),
+        Some(text) => note_text(text),
+        None => None,
+    };
 
     if dry_run {
         if json {
@@ -58,13 +105,46 @@ pub fn cmd_add(
             "task": task,
         }))?;
     } else {
-        println!("✓ Added task:");
-        print_task_simple(&input.description, &input.priority, &input.tags);
+        match &task.parent {
+            Some(parent) => println!("✓ Added subtask {} under {}:", task.id, parent),
+            None => println!("✓ Added task {}:", task.id),
+        }
+        print_task(&task);
     }
 
     Ok(())
 }
 
+/// The version new tasks are created at: the newest changelog release, or
+/// the version in `Cargo.toml` or `package.json` before the first release.
+/// `None` if the changelog cannot be read, so a broken changelog never
+/// stops a task from being added.
+pub(crate) fn current_version(
+    paths: &ProjectPaths,
+    storage: &ListStorage,
+    config: &AppConfig,
+) -> Option<Version> {
+    let changelog =
+        ChangelogStorage::for_project(paths, storage.project_name(), &config.changelog).ok()?;
+    let released = changelog.log().releases.iter().map(|r| &r.version).max();
+    released
+        .cloned()
+        .or_else(|| manifest::package_version(&paths.root))
+}
+
+fn date_flag(flag: &str, value: Option<&str>) -> Result<Option<chrono::NaiveDate>> {
+    value
+        .map(|value| {
+            dates::parse_date(value, dates::today()).with_context(|| format!("Invalid {flag}"))
+        })
+        .transpose()
+}
+
+fn note_text(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
 fn print_task(task: &Task) {
     let priority_str = match task.priority {
         Priority::High => " (high)",
@@ -86,26 +166,22 @@ fn print_task(task: &Task) {
     };
 
     println!("  [ ] {}{}{}", task.description, priority_str, tags_str);
-}
-
-fn print_task_simple(description: &str, priority: &Priority, tags: &[String]) {
-    let priority_str = match priority {
-        Priority::High => " (high)",
-        Priority::Medium => "",
-        Priority::Low => " (low)",
-    };
-
-    let tags_str = if tags.is_empty() {
-        String::new()
-    } else {
-        format!(
-            " {}",
-            tags.iter()
-                .map(|t| format!("#{}", t))
-                .collect::<Vec<_>>()
-                .join(" ")
-        )
-    };
-
-    println!("  [ ] {}{}{}", description, priority_str, tags_str);
+    if let Some(due) = task.due {
+        println!("      @due {}", due);
+    }
+    if let Some(scheduled) = task.scheduled {
+        println!("      @scheduled {}", scheduled);
+    }
+    if let Some(recur) = &task.recur {
+        println!("      @recur {}", recur);
+    }
+    if let Some(notes) = &task.notes {
+        for line in notes.lines() {
+            if line.trim().is_empty() {
+                println!();
+            } else {
+                println!("      {}", line);
+            }
+        }
+    }
 }
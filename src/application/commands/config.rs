@@ -0,0 +1,164 @@
+use crate::output;
+use crate::services::storage::config_storage::{
+    ConfigOrigin, ConfigStorage, ConfigTarget, known_keys,
+};
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::{Result, anyhow, bail};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+pub fn cmd_config_show(origin: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+
+    if !origin {
+        if json {
+            return output::print_json(config_storage.get_config());
+        }
+        let text = toml::to_string_pretty(config_storage.get_config())?;
+        return output::page_text(None, &text);
+    }
+
+    let values = config_storage.effective_values()?;
+    if json {
+        let entries: BTreeMap<_, _> = values
+            .iter()
+            .map(|(key, (value, origin))| {
+                (
+                    key,
+                    serde_json::json!({
+                        "value": value,
+                        "origin": origin,
+                        "file": config_storage.origin_file(*origin),
+                    }),
+                )
+            })
+            .collect();
+        return output::print_json(&entries);
+    }
+
+    let lines: Vec<(String, ConfigOrigin)> = values
+        .iter()
+        .map(|(key, (value, origin))| (format!("{} = {}", key, value), *origin))
+        .collect();
+    let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+
+    let mut text = String::new();
+    for (line, origin) in lines {
+        match config_storage.origin_file(origin) {
+            Some(file) => writeln!(text, "{line:<width$}  # {origin} ({})", file.display())?,
+            None => writeln!(text, "{line:<width$}  # {origin}")?,
+        }
+    }
+    output::page_text(None, &text)
+}
+
+pub fn cmd_config_get(key: String, project: bool, json: bool) -> Result<()> {
+    let config_storage = load()?;
+
+    let value = if project {
+        if !known_keys().contains(&key) {
+            bail!("Key path not found: {}", key);
+        }
+        config_storage
+            .target_values(ConfigTarget::Project)
+            .map_err(|e| anyhow!(e))?
+            .remove(&key)
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} is not set in {}",
+                    key,
+                    target_label(&config_storage, ConfigTarget::Project)
+                )
+            })?
+    } else {
+        config_storage.get_value(&key).map_err(|e| anyhow!(e))?
+    };
+
+    if json {
+        return output::print_json(&serde_json::json!({ "key": key, "value": value }));
+    }
+    match value.as_str() {
+        Some(text) => println!("{}", text),
+        None => println!("{}", value),
+    }
+    Ok(())
+}
+
+pub fn cmd_config_set(key: String, value: String, project: bool) -> Result<()> {
+    let (mut config_storage, target) = (load()?, target(project));
+    config_storage
+        .set_value(&key, &value, target)
+        .map_err(|e| anyhow!(e))?;
+
+    let value = config_storage.get_value(&key).map_err(|e| anyhow!(e))?;
+    println!(
+        "Set {} = {} in {}",
+        key,
+        value,
+        target_label(&config_storage, target)
+    );
+    Ok(())
+}
+
+pub fn cmd_config_unset(key: String, project: bool) -> Result<()> {
+    let (mut config_storage, target) = (load()?, target(project));
+    let removed = config_storage
+        .unset_value(&key, target)
+        .map_err(|e| anyhow!(e))?;
+    if !removed {
+        bail!(
+            "{} is not set in {}",
+            key,
+            target_label(&config_storage, target)
+        );
+    }
+
+    println!("Unset {} in {}", key, target_label(&config_storage, target));
+    Ok(())
+}
+
+pub fn cmd_config_list(project: bool, json: bool) -> Result<()> {
+    let (config_storage, target) = (load()?, target(project));
+    let values = config_storage
+        .target_values(target)
+        .map_err(|e| anyhow!(e))?;
+
+    if json {
+        return output::print_json(&values);
+    }
+    if values.is_empty() {
+        println!("Nothing set in {}", target_label(&config_storage, target));
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    for (key, value) in values {
+        writeln!(text, "{} = {}", key, value)?;
+    }
+    output::page_text(None, &text)
+}
+
+fn load() -> Result<ConfigStorage> {
+    let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
+    ConfigStorage::for_project(&paths)
+}
+
+fn target(project: bool) -> ConfigTarget {
+    if project {
+        ConfigTarget::Project
+    } else {
+        ConfigTarget::Global
+    }
+}
+
+fn target_label(config_storage: &ConfigStorage, target: ConfigTarget) -> String {
+    let origin = match target {
+        ConfigTarget::Global => ConfigOrigin::Global,
+        ConfigTarget::Project => ConfigOrigin::Project,
+    };
+    config_storage
+        .origin_file(origin)
+        .map(|file| file.display().to_string())
+        .unwrap_or_else(|| origin.to_string())
+}
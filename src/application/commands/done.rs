@@ -1,16 +1,20 @@
 use crate::models::common::Version;
+use crate::models::tasks::{Task, dependencies, tree};
 use crate::output;
 use crate::services::git;
 use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
-use crate::utils::matching::{MatchCandidate, score_percent, select_unambiguous};
+use crate::utils::matching::{
+    MatchCandidate, id_match, requested_id, score_percent, select_unambiguous, tag_query_score,
+};
 use crate::utils::project_paths::ProjectPaths;
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 
 pub fn cmd_done(
     description: String,
+    id: Option<String>,
     commit: Option<String>,
     version: Option<String>,
     dry_run: bool,
@@ -18,40 +22,71 @@ pub fn cmd_done(
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
 
-    // Fuzzy match the description
-    let matcher = SkimMatcherV2::default();
     let tasks = storage.tasks();
-    let mut candidates = Vec::new();
-
-    for (i, task) in tasks.iter().enumerate() {
-        if task.completed {
-            continue;
+    let selection = match requested_id(&description, id.as_deref(), |id| {
+        storage.find_task_by_id(id).is_some()
+    }) {
+        Some(id) => {
+            let index = storage
+                .find_task_by_id(&id)
+                .ok_or_else(|| anyhow!("No task found with ID '{}'", id))?;
+            if tasks[index].is_done() {
+                bail!("Task {} is already completed", id);
+            }
+            Some(id_match(index, tasks[index].description.clone()))
         }
+        None => {
+            // Fuzzy match the description
+            let matcher = SkimMatcherV2::default();
+            let mut candidates = Vec::new();
 
-        let exact = task.description.eq_ignore_ascii_case(&description);
-        if let Some(score) = matcher
-            .fuzzy_match(&task.description, &description)
-            .or(exact.then_some(i64::MAX))
-        {
-            candidates.push(MatchCandidate {
-                value: i,
-                score,
-                label: task.description.clone(),
-                exact,
-            });
+            for (i, task) in tasks.iter().enumerate() {
+                if task.is_closed() {
+                    continue;
+                }
+
+                let exact = task.description.eq_ignore_ascii_case(&description);
+                if let Some(score) = matcher
+                    .fuzzy_match(&task.description, &description)
+                    .or(exact.then_some(i64::MAX))
+                    .or_else(|| {
+                        tag_query_score(&description, &task.tags, |word| {
+                            matcher.fuzzy_match(&task.description, word)
+                        })
+                    })
+                {
+                    candidates.push(MatchCandidate {
+                        value: i,
+                        score,
+                        label: task.description.clone(),
+                        exact,
+                    });
+                }
+            }
+
+            select_unambiguous(candidates, config.matching.task_min_score, &description)?
         }
-    }
+    };
 
-    match select_unambiguous(candidates, config.matching.task_min_score, &description)? {
+    match selection {
         Some(best_match) => {
             let index = best_match.value;
             let score = best_match.score;
             let task = &tasks[index];
             let score_pct = score_percent(score);
+            let open_subtasks: Vec<&Task> = tree::descendants(tasks, index)
+                .into_iter()
+                .map(|i| &tasks[i])
+                .filter(|subtask| !subtask.is_closed())
+                .collect();
+            let open_dependencies: Vec<&Task> = dependencies::open_dependencies(tasks, index)
+                .into_iter()
+                .map(|i| &tasks[i])
+                .collect();
 
             if dry_run {
                 if json {
@@ -62,16 +97,23 @@ pub fn cmd_done(
                         "task": task,
                         "completed_commit": commit,
                         "completed_version": version,
+                        "open_subtasks": open_subtasks,
+                        "open_dependencies": open_dependencies,
                     }));
                 }
                 println!("Would mark as done (score: {:.0}%):", score_pct);
-                println!("  [x] {}", task.description);
+                println!("  [x] {} {}", task.id, task.description);
+                warn_open_subtasks(&open_subtasks);
+                warn_open_dependencies(&open_dependencies);
                 if let Some(ref commit_hash) = commit {
                     println!("      @completed_commit {}", commit_hash);
                 }
                 if let Some(ref v) = version {
                     println!("      @completed_version {}", v);
                 }
+                if let Some(recur) = task.recur.as_ref().filter(|recur| recur.is_time_based()) {
+                    println!("Would add the next occurrence ({})", recur);
+                }
                 return Ok(());
             }
 
@@ -82,17 +124,13 @@ pub fn cmd_done(
             };
 
             let description = task.description.clone();
+            let open_subtasks: Vec<Task> = open_subtasks.into_iter().cloned().collect();
+            let open_dependencies: Vec<Task> = open_dependencies.into_iter().cloned().collect();
 
-            // Add commit hash if provided
-            if let Some(commit_hash) = commit {
-                if let Some(task) = storage.tasks_mut().get_mut(index) {
-                    task.completed_at_commit = Some(commit_hash);
-                }
-                storage.save_list()?;
-            }
-
-            storage.complete_task(index, version_obj)?;
+            let next_task = storage.mark_done(index, version_obj, commit)?;
             let completed_task = storage.tasks()[index].clone();
+            let parents = complete_finished_parents(&mut storage, index, json)?;
+            storage.save_list()?;
 
             if auto || config.auto_commit_done() {
                 if json {
@@ -108,9 +146,35 @@ pub fn cmd_done(
                     "dry_run": false,
                     "match_score": score_pct,
                     "task": completed_task,
+                    "open_subtasks": open_subtasks,
+                    "open_dependencies": open_dependencies,
+                    "completed_parents": parents.completed,
+                    "ready_parent": parents.ready,
+                    "next_task": next_task,
                 }))?;
             } else {
-                println!("Marked as done: {}", description);
+                println!("Marked as done: {} {}", completed_task.id, description);
+                if let Some(next) = &next_task {
+                    println!(
+                        "Next occurrence: {} {}{}",
+                        next.id,
+                        next.description,
+                        next.due
+                            .map(|due| format!(" @due {}", due))
+                            .unwrap_or_default()
+                    );
+                }
+                warn_open_subtasks(&open_subtasks.iter().collect::<Vec<_>>());
+                warn_open_dependencies(&open_dependencies.iter().collect::<Vec<_>>());
+                for parent in &parents.completed {
+                    println!("Marked as done: {} {}", parent.id, parent.description);
+                }
+                if let Some(parent) = &parents.ready {
+                    println!(
+                        "All subtasks of {} {} are done; complete it with `tally done --id {}`.",
+                        parent.id, parent.description, parent.id
+                    );
+                }
             }
 
             Ok(())
@@ -121,3 +185,94 @@ pub fn cmd_done(
         )),
     }
 }
+
+fn warn_open_subtasks(open_subtasks: &[&Task]) {
+    if open_subtasks.is_empty() {
+        return;
+    }
+    println!(
+        "Warning: {} open subtask(s) remain; it is not released until they are closed:",
+        open_subtasks.len()
+    );
+    for subtask in open_subtasks {
+        println!(
+            "  [{}] {} {}",
+            subtask.status.checkbox(),
+            subtask.id,
+            subtask.description
+        );
+    }
+}
+
+fn warn_open_dependencies(open_dependencies: &[&Task]) {
+    if open_dependencies.is_empty() {
+        return;
+    }
+    println!(
+        "Warning: {} unfinished dependenc{} this task waits on:",
+        open_dependencies.len(),
+        if open_dependencies.len() == 1 {
+            "y"
+        } else {
+            "ies"
+        }
+    );
+    for dependency in open_dependencies {
+        println!(
+            "  [{}] {} {}",
+            dependency.status.checkbox(),
+            dependency.id,
+            dependency.description
+        );
+    }
+}
+
+#[derive(Default)]
+struct FinishedParents {
+    /// Parents completed after confirming.
+    completed: Vec<Task>,
+    /// A parent whose subtasks are all done but that was left open.
+    ready: Option<Task>,
+}
+
+/// Offer to complete each parent whose last open subtask was just completed.
+fn complete_finished_parents(
+    storage: &mut ListStorage,
+    index: usize,
+    json: bool,
+) -> Result<FinishedParents> {
+    let mut parents = FinishedParents::default();
+    let mut current = index;
+
+    while let Some(parent) = tree::parent_index(storage.tasks(), current) {
+        let tasks = storage.tasks();
+        let finished = !tasks[parent].is_closed()
+            && tree::children(tasks, parent)
+                .into_iter()
+                .all(|child| tasks[child].is_closed());
+        if !finished {
+            break;
+        }
+
+        let task = tasks[parent].clone();
+        let confirmed = !json
+            && output::is_interactive()
+            && output::confirm(
+                format!(
+                    "All subtasks of {} {} are done. Mark it as done too?",
+                    task.id, task.description
+                ),
+                true,
+            )?;
+        if !confirmed {
+            parents.ready = Some(task);
+            break;
+        }
+
+        storage.mark_done(parent, None, None)?;
+        parents.completed.push(storage.tasks()[parent].clone());
+        current = parent;
+    }
+
+    Ok(parents)
+}
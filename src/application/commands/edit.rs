@@ -0,0 +1,461 @@
+use super::yank::task_from_change;
+use crate::models::changes::Change;
+use crate::models::common::{Priority, Version};
+use crate::models::tasks::{Task, dependencies, task};
+use crate::output;
+use crate::services::git;
+use crate::services::serializers::todo_serializer;
+use crate::services::storage::changelog_storage::ChangelogStorage;
+use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::matching::{
+    MatchCandidate, id_match, requested_id, score_percent, select_unambiguous, tag_query_score,
+};
+use crate::utils::project_paths::ProjectPaths;
+use crate::utils::task_input::parse_task_input;
+use anyhow::{Result, anyhow, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+/// Changes requested for a task.
+#[derive(Debug, Default)]
+pub struct TaskEdits {
+    pub description: Option<String>,
+    pub priority: Option<Priority>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// IDs of tasks to start depending on.
+    pub add_depends: Vec<String>,
+    /// IDs of tasks to stop depending on.
+    pub remove_depends: Vec<String>,
+    /// New recurrence rule, or `none` to stop recurring.
+    pub recur: Option<String>,
+    /// Edit the whole task block in an editor instead.
+    pub editor: bool,
+}
+
+impl TaskEdits {
+    fn is_empty(&self) -> bool {
+        self.description.is_none()
+            && self.priority.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.add_depends.is_empty()
+            && self.remove_depends.is_empty()
+            && self.recur.is_none()
+            && !self.editor
+    }
+
+    fn apply(
+        &self,
+        description: &mut String,
+        priority: &mut Priority,
+        tags: &mut Vec<String>,
+    ) -> Result<()> {
+        if let Some(text) = &self.description {
+            // Inline `#tags` in the new text are added like with `tally add`.
+            let input = parse_task_input(text, None, None)?;
+            *description = input.description;
+            add_tags(tags, &input.tags);
+        }
+        if let Some(new_priority) = self.priority {
+            *priority = new_priority;
+        }
+        add_tags(tags, &self.add_tags);
+        tags.retain(|tag| {
+            !self
+                .remove_tags
+                .iter()
+                .any(|removed| removed.trim_start_matches('#') == tag)
+        });
+        Ok(())
+    }
+
+    /// Inline `due:` and `scheduled:` dates in a new description, dependency
+    /// changes and the recurrence rule; only tasks carry these.
+    fn apply_task_fields(&self, task: &mut Task) -> Result<()> {
+        if let Some(text) = &self.description {
+            let input = parse_task_input(text, None, None)?;
+            task.due = input.due.or(task.due);
+            task.scheduled = input.scheduled.or(task.scheduled);
+        }
+        for id in &self.add_depends {
+            let id = task::normalize_id(id);
+            if !id.is_empty() && !task.depends.contains(&id) {
+                task.depends.push(id);
+            }
+        }
+        task.depends.retain(|dependency| {
+            !self
+                .remove_depends
+                .iter()
+                .any(|removed| &task::normalize_id(removed) == dependency)
+        });
+        match self.recur.as_deref().map(str::trim) {
+            Some(rule) if rule.eq_ignore_ascii_case("none") => task.recur = None,
+            Some(rule) => task.recur = Some(rule.parse()?),
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+fn add_tags(tags: &mut Vec<String>, new_tags: &[String]) {
+    for tag in new_tags {
+        let tag = tag.trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+}
+
+pub fn cmd_edit(
+    description: String,
+    id: Option<String>,
+    edits: TaskEdits,
+    released: Option<String>,
+    dry_run: bool,
+    auto: bool,
+    json: bool,
+) -> Result<()> {
+    if edits.is_empty() {
+        bail!(
+            "Nothing to edit: pass --description, --priority, --add-tags, --remove-tags, --add-depends, --remove-depends, --recur or --editor"
+        );
+    }
+
+    if let Some(version) = released {
+        let version = Version::parse(&version)?;
+        return cmd_edit_released(description, id, edits, version, dry_run, auto, json);
+    }
+
+    let paths = ProjectPaths::get_paths()?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let tasks = storage.tasks();
+    let selection = match requested_id(&description, id.as_deref(), |id| {
+        storage.find_task_by_id(id).is_some()
+    }) {
+        Some(id) => {
+            let index = storage
+                .find_task_by_id(&id)
+                .ok_or_else(|| anyhow!("No task found with ID '{}'", id))?;
+            Some(id_match(index, tasks[index].description.clone()))
+        }
+        None => {
+            let matcher = SkimMatcherV2::default();
+            let mut candidates = Vec::new();
+
+            for (i, task) in tasks.iter().enumerate() {
+                let exact = task.description.eq_ignore_ascii_case(&description);
+                if let Some(score) = matcher
+                    .fuzzy_match(&task.description, &description)
+                    .or(exact.then_some(i64::MAX))
+                    .or_else(|| {
+                        tag_query_score(&description, &task.tags, |word| {
+                            matcher.fuzzy_match(&task.description, word)
+                        })
+                    })
+                {
+                    candidates.push(MatchCandidate {
+                        value: i,
+                        score,
+                        label: task.description.clone(),
+                        exact,
+                    });
+                }
+            }
+
+            select_unambiguous(candidates, config.matching.task_min_score, &description)?
+        }
+    };
+
+    let Some(best_match) = selection else {
+        bail!("No matching task found for: '{}'", description);
+    };
+    let index = best_match.value;
+    let score_pct = score_percent(best_match.score);
+    let original = tasks[index].clone();
+
+    let edited = if edits.editor {
+        edit_task_in_editor(&original, config.preferences.editor.as_deref())?
+    } else {
+        let mut task = original.clone();
+        edits.apply(&mut task.description, &mut task.priority, &mut task.tags)?;
+        edits.apply_task_fields(&mut task)?;
+        task
+    };
+    dependencies::check(tasks, Some(index), &edited)?;
+
+    if dry_run {
+        if json {
+            return output::print_json(&serde_json::json!({
+                "status": "would_edit",
+                "dry_run": true,
+                "match_score": score_pct,
+                "previous": original,
+                "task": edited,
+            }));
+        }
+        println!(
+            "Would edit task {} (score: {:.0}%):",
+            original.id, score_pct
+        );
+        print_edit(&original, &edited);
+        return Ok(());
+    }
+
+    storage.tasks_mut()[index] = edited.clone();
+    storage.save_list()?;
+
+    if auto || config.auto_commit_edit() {
+        if json {
+            git::commit_tally_files_quiet("update TODO: edit task")?;
+        } else {
+            git::commit_tally_files("update TODO: edit task")?;
+        }
+    }
+
+    if json {
+        output::print_json(&serde_json::json!({
+            "status": "edited",
+            "dry_run": false,
+            "match_score": score_pct,
+            "previous": original,
+            "task": edited,
+        }))?;
+    } else {
+        println!("Edited task {}:", edited.id);
+        print_edit(&original, &edited);
+    }
+
+    Ok(())
+}
+
+fn cmd_edit_released(
+    description: String,
+    id: Option<String>,
+    edits: TaskEdits,
+    released_version: Version,
+    dry_run: bool,
+    auto: bool,
+    json: bool,
+) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+    let mut changelog =
+        ChangelogStorage::for_project(&paths, storage.project_name(), &config.changelog)?;
+
+    let requested = requested_id(&description, id.as_deref(), |id| {
+        changelog
+            .find_change_by_id(id)
+            .is_some_and(|(version, _)| version == released_version)
+    });
+    let found = match requested {
+        Some(ref id) => changelog
+            .find_change_by_id(id)
+            .filter(|(version, _)| version == &released_version),
+        None => changelog.find_change(
+            &description,
+            Some(&released_version),
+            None,
+            config.matching.released_min_score,
+        )?,
+    };
+
+    let Some((version, original)) = found else {
+        match requested {
+            Some(id) => bail!(
+                "No released task found with ID '{}' in {}",
+                id,
+                released_version
+            ),
+            None => bail!("No matching released task found."),
+        }
+    };
+
+    let edited = if edits.editor {
+        let task = edit_task_in_editor(
+            &task_from_change(&original),
+            config.preferences.editor.as_deref(),
+        )?;
+        Change {
+            description: task.description,
+            priority: task.priority,
+            tags: task.tags,
+            commit: task.completed_at_commit,
+            notes: task.notes,
+            meta: task.meta,
+            ..original.clone()
+        }
+    } else {
+        if let Some(text) = &edits.description {
+            let input = parse_task_input(text, None, None)?;
+            if input.due.is_some() || input.scheduled.is_some() {
+                bail!(
+                    "Released entries have no due or scheduled date; remove due: and scheduled: from the description"
+                );
+            }
+        }
+        let mut change = original.clone();
+        edits.apply(
+            &mut change.description,
+            &mut change.priority,
+            &mut change.tags,
+        )?;
+        change
+    };
+
+    if dry_run {
+        if json {
+            return output::print_json(&serde_json::json!({
+                "status": "would_edit_released",
+                "dry_run": true,
+                "version": version,
+                "previous": original,
+                "change": edited,
+            }));
+        }
+        println!("Would edit in {}:", version);
+        println!("  - {}", original.description);
+        println!("  + {}", edited.description);
+        return Ok(());
+    }
+
+    if !changelog.replace_change(&version, &original, edited.clone()) {
+        bail!("Released task changed while editing; nothing was written");
+    }
+    changelog.save()?;
+
+    if auto || config.auto_commit_edit() {
+        if json {
+            git::commit_tally_files_quiet("update CHANGELOG: edit released task")?;
+        } else {
+            git::commit_tally_files("update CHANGELOG: edit released task")?;
+        }
+    }
+
+    if json {
+        output::print_json(&serde_json::json!({
+            "status": "edited_released",
+            "dry_run": false,
+            "version": version,
+            "previous": original,
+            "change": edited,
+        }))?;
+    } else {
+        println!("Edited in {}: {}", version, edited.description);
+    }
+
+    Ok(())
+}
+
+/// Open a task block in the editor and parse it back, keeping the task ID
+/// and its place under a parent, which the block does not show.
+fn edit_task_in_editor(task: &Task, editor: Option<&str>) -> Result<Task> {
+    let text = output::edit_text(editor, &todo_serializer::render_task(task))?;
+    let mut edited = parse_edited_task(&text)?;
+    edited.id = task.id.clone();
+    edited.parent = task.parent.clone();
+    Ok(edited)
+}
+
+fn parse_edited_task(text: &str) -> Result<Task> {
+    // Blank lines inside the block belong to the task's notes.
+    let lines: Vec<String> = text
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if !lines.first().is_some_and(|line| line.starts_with("- [")) {
+        bail!("Edited task must start with a '- [ ]' line");
+    }
+    if lines[1..].iter().any(|line| line.starts_with("- [")) {
+        bail!("Edited text must contain exactly one task");
+    }
+
+    todo_serializer::parse_task(&lines)
+}
+
+fn print_edit(before: &Task, after: &Task) {
+    println!("  - {}", task_line(before));
+    println!("  + {}", task_line(after));
+}
+
+fn task_line(task: &Task) -> String {
+    todo_serializer::render_task(task)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches("- ")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_updates_fields_and_tags() {
+        let edits = TaskEdits {
+            description: Some("Rename parser #api".to_string()),
+            priority: Some(Priority::High),
+            add_tags: vec!["#docs".to_string(), "parser".to_string()],
+            remove_tags: vec!["old".to_string()],
+            add_depends: Vec::new(),
+            remove_depends: Vec::new(),
+            recur: None,
+            editor: false,
+        };
+        let mut description = "Parser".to_string();
+        let mut priority = Priority::Low;
+        let mut tags = vec!["parser".to_string(), "old".to_string()];
+
+        edits
+            .apply(&mut description, &mut priority, &mut tags)
+            .unwrap();
+
+        assert_eq!(description, "Rename parser");
+        assert_eq!(priority, Priority::High);
+        assert_eq!(tags, vec!["parser", "api", "docs"]);
+    }
+
+    #[test]
+    fn parse_edited_task_reads_block_and_rejects_extra_tasks() {
+        let task = parse_edited_task(
+            "- [ ] Write docs (high) #docs\n      @id c0ffee\n      @created 2026-02-20 08:15\n",
+        )
+        .unwrap();
+        assert_eq!(task.description, "Write docs");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, vec!["docs"]);
+
+        assert!(parse_edited_task("").is_err());
+        assert!(
+            parse_edited_task(
+                "- [ ] One\n      @created 2026-02-20 08:15\n- [ ] Two\n      @created 2026-02-20 08:15\n"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn editing_a_subtask_keeps_its_parent() {
+        let parent = Task::new("Parser", Priority::Medium, vec![]);
+        let mut subtask = Task::new("Tokenizer", Priority::Low, vec!["api".to_string()]);
+        subtask.parent = Some(parent.id.clone());
+
+        // An editor that saves the block unchanged.
+        let edited = edit_task_in_editor(&subtask, Some("true")).unwrap();
+
+        assert_eq!(edited.id, subtask.id);
+        assert_eq!(edited.parent, Some(parent.id));
+        assert_eq!(edited.description, "Tokenizer");
+        assert_eq!(edited.tags, vec!["api"]);
+    }
+}
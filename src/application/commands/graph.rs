@@ -0,0 +1,118 @@
+use crate::models::common::GraphFormat;
+use crate::models::tasks::{Task, dependencies};
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::Result;
+use std::fmt::Write as _;
+
+pub fn cmd_graph(format: GraphFormat, all: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let storage = ListStorage::for_reading(&paths)?;
+
+    print!("{}", render_graph(storage.tasks(), format, all));
+    Ok(())
+}
+
+/// Render the dependency graph with an edge from each dependency to the task
+/// waiting on it. Unless `all` is set, only tasks with an edge are drawn.
+fn render_graph(tasks: &[Task], format: GraphFormat, all: bool) -> String {
+    let edges: Vec<(usize, usize)> = (0..tasks.len())
+        .flat_map(|task| {
+            dependencies::dependency_indices(tasks, task)
+                .into_iter()
+                .map(move |dependency| (dependency, task))
+        })
+        .collect();
+    let nodes =
+        (0..tasks.len()).filter(|&i| all || edges.iter().any(|&(from, to)| from == i || to == i));
+
+    let mut output = String::new();
+    match format {
+        GraphFormat::Dot => {
+            output.push_str("digraph tally {\n  rankdir=LR;\n  node [shape=box];\n");
+            for i in nodes {
+                let task = &tasks[i];
+                let style = if task.is_closed() {
+                    ", style=dashed"
+                } else {
+                    ""
+                };
+                writeln!(
+                    output,
+                    "  \"{}\" [label=\"{}\"{}];",
+                    task.id,
+                    node_label(task).replace('\\', "\\\\").replace('"', "\\\""),
+                    style
+                )
+                .unwrap();
+            }
+            for (from, to) in edges {
+                writeln!(output, "  \"{}\" -> \"{}\";", tasks[from].id, tasks[to].id).unwrap();
+            }
+            output.push_str("}\n");
+        }
+        GraphFormat::Mermaid => {
+            output.push_str("flowchart LR\n");
+            for i in nodes {
+                let task = &tasks[i];
+                writeln!(
+                    output,
+                    "  t{}[\"{}\"]",
+                    task.id,
+                    node_label(task).replace('"', "#quot;")
+                )
+                .unwrap();
+            }
+            for (from, to) in edges {
+                writeln!(output, "  t{} --> t{}", tasks[from].id, tasks[to].id).unwrap();
+            }
+        }
+    }
+    output
+}
+
+fn node_label(task: &Task) -> String {
+    format!(
+        "[{}] {} {}",
+        task.status.checkbox(),
+        task.id,
+        task.description
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::{Priority, TaskStatus};
+
+    fn task(id: &str, description: &str, depends: &[&str]) -> Task {
+        let mut task = Task::new(description, Priority::Medium, vec![]);
+        task.id = id.to_string();
+        task.depends = depends.iter().map(|id| id.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn renders_dot_and_mermaid_edges_from_dependency_to_dependent() {
+        let mut tasks = vec![
+            task("aaaaaa", "Design \"schema\"", &[]),
+            task("bbbbbb", "Write migration", &["aaaaaa"]),
+            task("cccccc", "Unrelated", &[]),
+        ];
+        tasks[0].status = TaskStatus::Done;
+
+        let dot = render_graph(&tasks, GraphFormat::Dot, false);
+        assert!(dot.starts_with("digraph tally {\n"));
+        assert!(dot.contains(
+            "  \"aaaaaa\" [label=\"[x] aaaaaa Design \\\"schema\\\"\", style=dashed];\n"
+        ));
+        assert!(dot.contains("  \"aaaaaa\" -> \"bbbbbb\";\n"));
+        assert!(!dot.contains("cccccc"));
+
+        let mermaid = render_graph(&tasks, GraphFormat::Mermaid, true);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("  taaaaaa[\"[x] aaaaaa Design #quot;schema#quot;\"]\n"));
+        assert!(mermaid.contains("  taaaaaa --> tbbbbbb\n"));
+        assert!(mermaid.contains("tcccccc"));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::models::history::JournalEntry;
+use crate::output;
+use crate::services::storage::history_storage::HistoryStorage;
+use crate::services::storage::lock;
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::Result;
+use std::fmt::Write as _;
+
+pub fn cmd_undo(dry_run: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let _lock = lock::acquire(&paths.state_dir)?;
+    let mut history = HistoryStorage::new(&paths.state_dir)?;
+
+    let Some(entry) = history.undo(&paths.root, dry_run)? else {
+        return report_nothing("undo", dry_run, json);
+    };
+    report("undo", &entry, dry_run, json)
+}
+
+pub fn cmd_redo(dry_run: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let _lock = lock::acquire(&paths.state_dir)?;
+    let mut history = HistoryStorage::new(&paths.state_dir)?;
+
+    let Some(entry) = history.redo(&paths.root, dry_run)? else {
+        return report_nothing("redo", dry_run, json);
+    };
+    report("redo", &entry, dry_run, json)
+}
+
+pub fn cmd_history(limit: Option<usize>, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let history = HistoryStorage::new(&paths.state_dir)?;
+    let journal = history.journal();
+
+    let entries: Vec<(bool, &JournalEntry)> = journal
+        .entries
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, entry)| (i >= journal.position, entry))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    if json {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(undone, entry)| {
+                serde_json::json!({
+                    "id": entry.id,
+                    "command": entry.command,
+                    "recorded_at": entry.recorded_at,
+                    "undone": undone,
+                    "files": entry.files.iter().map(|file| &file.path).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        return output::print_json(&entries);
+    }
+
+    if entries.is_empty() {
+        println!("No history recorded.");
+        return Ok(());
+    }
+
+    let mut output = String::new();
+    for (undone, entry) in entries {
+        writeln!(
+            output,
+            "#{:<4} {}  {}{}",
+            entry.id,
+            entry.recorded_at.format("%Y-%m-%d %H:%M"),
+            entry.command,
+            if undone { "  (undone)" } else { "" }
+        )?;
+    }
+    output::page_text(None, &output)
+}
+
+fn report(action: &str, entry: &JournalEntry, dry_run: bool, json: bool) -> Result<()> {
+    let files: Vec<&str> = entry.files.iter().map(|file| file.path.as_str()).collect();
+
+    if json {
+        let status = match (action, dry_run) {
+            ("undo", true) => "would_undo",
+            ("undo", false) => "undone",
+            (_, true) => "would_redo",
+            (_, false) => "redone",
+        };
+        return output::print_json(&serde_json::json!({
+            "status": status,
+            "dry_run": dry_run,
+            "id": entry.id,
+            "command": entry.command,
+            "files": files,
+        }));
+    }
+
+    let verb = match (action, dry_run) {
+        ("undo", true) => "Would undo",
+        ("undo", false) => "Undid",
+        (_, true) => "Would redo",
+        (_, false) => "Redid",
+    };
+    println!(
+        "{} #{}: {} ({})",
+        verb,
+        entry.id,
+        entry.command,
+        files.join(", ")
+    );
+    Ok(())
+}
+
+fn report_nothing(action: &str, dry_run: bool, json: bool) -> Result<()> {
+    if json {
+        return output::print_json(&serde_json::json!({
+            "status": "nothing_to_".to_string() + action,
+            "dry_run": dry_run,
+        }));
+    }
+    println!("Nothing to {}.", action);
+    Ok(())
+}
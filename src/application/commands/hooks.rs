@@ -0,0 +1,346 @@
+use crate::application::commands::scan::best_commit_match;
+use crate::models::common::TaskStatus;
+use crate::output;
+use crate::services::git;
+use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::matching::score_passes;
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::Result;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use std::fs;
+use std::path::Path;
+
+/// Line that marks a hook script as written by tally.
+const MARKER: &str = "# Installed by `tally hooks install`; `tally hooks uninstall` removes it.";
+
+/// Git hooks tally can install, with the command each one runs.
+const HOOKS: &[(&str, &str)] = &[
+    ("commit-msg", "tally hook commit-msg \"$1\""),
+    ("post-commit", "tally hook post-commit"),
+    (
+        "prepare-commit-msg",
+        "tally hook prepare-commit-msg \"$1\" \"$2\"",
+    ),
+];
+
+fn script(command: &str) -> String {
+    // A missing or failing tally must not block commits; its error is still
+    // printed.
+    format!(
+        "#!/bin/sh\n{}\ncommand -v tally >/dev/null 2>&1 || exit 0\n{} || true\n",
+        MARKER, command
+    )
+}
+
+fn written_by_tally(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.lines().any(|line| line == MARKER))
+}
+
+/// Write tally's `commit-msg` and `post-commit` hooks, plus
+/// `prepare-commit-msg` if asked. Hooks written by something else are left
+/// alone.
+pub fn cmd_hooks_install(prepare_commit_msg: bool, dry_run: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
+    let hooks_dir = git::hooks_dir(&paths.root)?;
+    let results = install(&hooks_dir, prepare_commit_msg, dry_run)?;
+    report("install", &results, dry_run, json)
+}
+
+fn install(
+    hooks_dir: &Path,
+    prepare_commit_msg: bool,
+    dry_run: bool,
+) -> Result<Vec<(&'static str, &'static str)>> {
+    let mut results = Vec::new();
+    for (name, command) in HOOKS {
+        if *name == "prepare-commit-msg" && !prepare_commit_msg {
+            continue;
+        }
+        let path = hooks_dir.join(name);
+        let content = script(command);
+        let status = if !path.exists() {
+            "installed"
+        } else if !written_by_tally(&path) {
+            "skipped"
+        } else if fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+            "unchanged"
+        } else {
+            "updated"
+        };
+
+        if !dry_run && matches!(status, "installed" | "updated") {
+            fs::create_dir_all(hooks_dir)?;
+            fs::write(&path, content)?;
+            make_executable(&path)?;
+        }
+        results.push((*name, status));
+    }
+    Ok(results)
+}
+
+/// Remove the hooks tally wrote, leaving any others in place.
+pub fn cmd_hooks_uninstall(dry_run: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
+    let hooks_dir = git::hooks_dir(&paths.root)?;
+    let results = uninstall(&hooks_dir, dry_run)?;
+    report("uninstall", &results, dry_run, json)
+}
+
+fn uninstall(hooks_dir: &Path, dry_run: bool) -> Result<Vec<(&'static str, &'static str)>> {
+    let mut results = Vec::new();
+    for (name, _) in HOOKS {
+        let path = hooks_dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        if !written_by_tally(&path) {
+            results.push((*name, "skipped"));
+            continue;
+        }
+        if !dry_run {
+            fs::remove_file(&path)?;
+        }
+        results.push((*name, "removed"));
+    }
+    Ok(results)
+}
+
+fn report(action: &str, results: &[(&str, &str)], dry_run: bool, json: bool) -> Result<()> {
+    if json {
+        let hooks: Vec<_> = results
+            .iter()
+            .map(|(name, status)| serde_json::json!({ "hook": name, "status": status }))
+            .collect();
+        return output::print_json(&serde_json::json!({
+            "action": action,
+            "dry_run": dry_run,
+            "hooks": hooks,
+        }));
+    }
+
+    if results.is_empty() {
+        println!("No tally hooks to {}.", action);
+    }
+    for (name, status) in results {
+        let status = match (*status, dry_run) {
+            ("skipped", _) => "skipped: not written by tally",
+            ("unchanged", _) => "already installed",
+            ("installed", true) => "would install",
+            ("updated", true) => "would update",
+            ("removed", true) => "would remove",
+            (status, _) => status,
+        };
+        println!("{}: {}", name, status);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// `post-commit`: mark open tasks matching the new commit's `done:` block as
+/// done, recording the commit hash.
+pub fn cmd_hook_post_commit() -> Result<()> {
+    // Hooks run in every commit; a repository without a TODO file is fine.
+    let Ok(paths) = ProjectPaths::get_paths() else {
+        return Ok(());
+    };
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let Some(commit) = git::head_commit(&paths.root, &config.git.done_prefix)? else {
+        return Ok(());
+    };
+    let commits = [commit];
+    let matched: Vec<usize> = storage
+        .tasks()
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| !task.is_closed())
+        .filter(|(_, task)| best_commit_match(task, &commits, config).is_some())
+        .map(|(idx, _)| idx)
+        .collect();
+
+    for &idx in &matched {
+        storage.mark_done(idx, None, Some(commits[0].hash.clone()))?;
+        let task = &storage.tasks()[idx];
+        println!(
+            "tally: marked done: {} {} ({})",
+            task.id, task.description, commits[0].hash
+        );
+    }
+    if !matched.is_empty() {
+        storage.save_list()?;
+    }
+    Ok(())
+}
+
+/// `commit-msg`: warn about `done:` items that match no open task. The hook
+/// script ignores the exit status, so the commit is never rejected.
+pub fn cmd_hook_commit_msg(file: String) -> Result<()> {
+    let Ok(paths) = ProjectPaths::get_paths() else {
+        return Ok(());
+    };
+    let storage = ListStorage::for_reading(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let message = fs::read_to_string(&file)?;
+    let matcher = SkimMatcherV2::default();
+    for item in git::extract_done_items(&message, &config.git.done_prefix) {
+        let matches = storage.tasks().iter().any(|task| {
+            !task.is_closed()
+                && matcher
+                    .fuzzy_match(&task.description, &item)
+                    .is_some_and(|score| score_passes(score, config.matching.task_min_score))
+        });
+        if !matches {
+            eprintln!("tally: no open task matches done item \"{}\"", item);
+        }
+    }
+    Ok(())
+}
+
+/// `prepare-commit-msg`: pre-fill a `done:` block listing the in-progress
+/// tasks, for commits whose message is written in the editor.
+pub fn cmd_hook_prepare_commit_msg(file: String, source: Option<String>) -> Result<()> {
+    // Leave -m, merge, squash and amend messages alone.
+    if source
+        .as_deref()
+        .is_some_and(|source| !source.is_empty() && source != "template")
+    {
+        return Ok(());
+    }
+    let Ok(paths) = ProjectPaths::get_paths() else {
+        return Ok(());
+    };
+    let storage = ListStorage::for_reading(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let message = fs::read_to_string(&file)?;
+    let done_prefix = &config.git.done_prefix;
+    if message
+        .lines()
+        .any(|line| line.trim().eq_ignore_ascii_case(done_prefix))
+    {
+        return Ok(());
+    }
+    let in_progress: Vec<&str> = storage
+        .tasks()
+        .iter()
+        .filter(|task| task.status == TaskStatus::InProgress)
+        .map(|task| task.description.as_str())
+        .collect();
+    if in_progress.is_empty() {
+        return Ok(());
+    }
+
+    fs::write(
+        &file,
+        prefill_done_block(&message, done_prefix, &in_progress),
+    )?;
+    Ok(())
+}
+
+/// Insert a done block above git's `#` comment lines.
+fn prefill_done_block(message: &str, done_prefix: &str, tasks: &[&str]) -> String {
+    let comments_at = message
+        .lines()
+        .position(|line| line.starts_with('#'))
+        .unwrap_or(usize::MAX);
+    let lines: Vec<&str> = message.lines().collect();
+    let (head, tail) = lines.split_at(comments_at.min(lines.len()));
+
+    let mut output = head.join("\n").trim_end().to_string();
+    output.push_str("\n\n");
+    output.push_str(done_prefix);
+    output.push('\n');
+    for task in tasks {
+        output.push_str(&format!("- {}\n", task));
+    }
+    if !tail.is_empty() {
+        output.push('\n');
+        output.push_str(&tail.join("\n"));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn install_is_idempotent_and_leaves_foreign_hooks_alone() {
+        let dir = TempDir::new().unwrap();
+        let hooks_dir = dir.path().join("hooks");
+        fs::create_dir_all(&hooks_dir).unwrap();
+        let foreign = "#!/bin/sh\nexec lint-commit \"$1\"\n";
+        fs::write(hooks_dir.join("commit-msg"), foreign).unwrap();
+
+        let results = install(&hooks_dir, false, false).unwrap();
+        assert_eq!(
+            results,
+            vec![("commit-msg", "skipped"), ("post-commit", "installed")]
+        );
+        let post_commit = fs::read_to_string(hooks_dir.join("post-commit")).unwrap();
+        assert!(post_commit.ends_with("tally hook post-commit || true\n"));
+
+        let results = install(&hooks_dir, true, false).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                ("commit-msg", "skipped"),
+                ("post-commit", "unchanged"),
+                ("prepare-commit-msg", "installed"),
+            ]
+        );
+
+        let results = uninstall(&hooks_dir, false).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                ("commit-msg", "skipped"),
+                ("post-commit", "removed"),
+                ("prepare-commit-msg", "removed"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(hooks_dir.join("commit-msg")).unwrap(),
+            foreign
+        );
+        assert!(!hooks_dir.join("post-commit").exists());
+        assert_eq!(
+            uninstall(&hooks_dir, false).unwrap(),
+            vec![("commit-msg", "skipped")]
+        );
+    }
+
+    #[test]
+    fn prefills_done_block_above_git_comments() {
+        let message = "\n# Please enter the commit message for your changes.\n# On branch main\n";
+        let filled = prefill_done_block(message, "done:", &["Fix login", "Add docs"]);
+        assert_eq!(
+            filled,
+            "\n\ndone:\n- Fix login\n- Add docs\n\n# Please enter the commit message for your changes.\n# On branch main\n"
+        );
+        assert_eq!(
+            git::extract_done_items(&filled, "done:"),
+            vec!["Fix login", "Add docs"]
+        );
+    }
+}
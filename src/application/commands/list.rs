@@ -1,35 +1,91 @@
-use crate::models::common::{Priority, Version};
-use crate::models::tasks::Task;
+use crate::models::changes::Change;
+use crate::models::common::{Priority, TaskStatus, Version};
+use crate::models::tasks::{CustomMeta, Task, dependencies, tree};
 use crate::output;
 use crate::services::storage::changelog_storage::ChangelogStorage;
+use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
+use crate::utils::dates;
 use crate::utils::project_paths::ProjectPaths;
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use chrono::{Days, NaiveDate};
+use console::style;
 use serde::Serialize;
 use std::fmt::Write as _;
 
-pub fn cmd_list(
-    tags: Option<Vec<String>>,
-    priority: Option<Priority>,
-    done: bool,
-    released: Option<String>,
-    json: bool,
-) -> Result<()> {
+/// Which tasks `tally list` shows. Every filter given must match.
+#[derive(Debug, Default)]
+pub struct TaskFilter {
+    pub tags: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    pub done: bool,
+    /// Tasks in any of these states.
+    pub status: Vec<TaskStatus>,
+    /// Open tasks whose dependencies are all finished.
+    pub ready: bool,
+    /// Open tasks whose due date has passed.
+    pub overdue: bool,
+    /// Tasks due before this date.
+    pub due_before: Option<String>,
+    /// Tasks due within a span such as `7d`, counting overdue ones.
+    pub due_within: Option<String>,
+    /// Custom metadata filters as `key=value`, or `key` alone.
+    pub meta: Vec<String>,
+}
+
+impl TaskFilter {
+    /// The first day no longer matched by `--due-before` and `--due-within`.
+    fn due_limit(&self, today: NaiveDate) -> Result<Option<NaiveDate>> {
+        let before = self
+            .due_before
+            .as_deref()
+            .map(|value| dates::parse_date(value, today).context("Invalid --due-before"))
+            .transpose()?;
+        let within = self
+            .due_within
+            .as_deref()
+            .map(|span| {
+                dates::add_span(span, today)
+                    .context("Invalid --due-within")
+                    .map(|last| last + Days::new(1))
+            })
+            .transpose()?;
+
+        Ok(before.into_iter().chain(within).min())
+    }
+
+    fn check_meta(&self) -> Result<()> {
+        for filter in &self.meta {
+            let key = filter
+                .split_once('=')
+                .map_or(filter.as_str(), |(key, _)| key);
+            if CustomMeta::key(key).is_none() {
+                bail!(
+                    "Invalid --meta '{}': use KEY=VALUE or KEY with a custom metadata key",
+                    filter
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn cmd_list(filter: TaskFilter, released: Option<String>, json: bool) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let storage = ListStorage::new(&paths.todo_file)?;
+    let storage = ListStorage::for_reading(&paths)?;
+    filter.check_meta()?;
     if let Some(released_version_str) = released {
         let released_version = Version::parse(&released_version_str)?;
-        return cmd_list_released(
-            &paths.changelog_file,
-            storage.project_name(),
-            tags,
-            priority,
-            released_version,
-            json,
-        );
+        let config_storage = ConfigStorage::for_project(&paths)?;
+        let config = config_storage.get_config();
+        let changelog =
+            ChangelogStorage::for_reading(&paths, storage.project_name(), &config.changelog)?;
+        return cmd_list_released(&changelog, &filter, released_version, json);
     }
 
-    let tasks = filter_tasks(storage.tasks(), tags.as_deref(), priority.as_ref(), done);
+    let today = dates::today();
+    let due_limit = filter.due_limit(today)?;
+    let tasks = filter_tasks(storage.tasks(), &filter, due_limit, today);
 
     if json {
         let task_list: Vec<_> = tasks.iter().map(|(_, task)| task).collect();
@@ -40,9 +96,25 @@ pub fn cmd_list(
             return Ok(());
         }
 
+        // Show subtasks under their parents, indented by how many listed
+        // ancestors they have.
+        let all_tasks = storage.tasks();
+        let listed: Vec<usize> = tasks.iter().map(|(i, _)| *i).collect();
+        let ordered = tree::walk(all_tasks)
+            .into_iter()
+            .filter(|(i, _)| listed.contains(i))
+            .map(|(i, _)| {
+                let depth = tree::ancestors(all_tasks, i)
+                    .into_iter()
+                    .filter(|ancestor| listed.contains(ancestor))
+                    .count();
+                (i, depth, &all_tasks[i])
+            });
+
         let mut output = String::new();
-        for (i, task) in tasks {
-            let checkbox = if task.completed { "x" } else { " " };
+        for (i, depth, task) in ordered {
+            let indent = "  ".repeat(depth);
+            let checkbox = task.status.checkbox();
             let priority_str = match task.priority {
                 Priority::High => " (high)",
                 Priority::Medium => "",
@@ -61,22 +133,57 @@ pub fn cmd_list(
                 )
             };
 
-            writeln!(
-                output,
-                "{}. [{}] {}{}{}",
+            let dates_str = format!(
+                "{}{}{}",
+                task.due
+                    .map(|due| format!(" @due {}", due))
+                    .unwrap_or_default(),
+                task.scheduled
+                    .map(|scheduled| format!(" @scheduled {}", scheduled))
+                    .unwrap_or_default(),
+                task.recur
+                    .as_ref()
+                    .map(|recur| format!(" @recur {}", recur))
+                    .unwrap_or_default()
+            );
+
+            let line = format!(
+                "{}{}. {} [{}] {}{}{}{}",
+                indent,
                 i + 1,
+                task.id,
                 checkbox,
                 task.description,
                 priority_str,
-                tags_str
-            )?;
+                tags_str,
+                dates_str
+            );
+            if is_overdue(task, today) {
+                writeln!(output, "{}", style(line).red())?;
+            } else {
+                writeln!(output, "{}", line)?;
+            }
 
-            if task.completed {
+            if let Some(reason) = &task.blocked_reason {
+                writeln!(output, "{}      @blocked {}", indent, reason)?;
+            }
+            if !task.depends.is_empty() {
+                writeln!(
+                    output,
+                    "{}      @depends {}",
+                    indent,
+                    task.depends.join(", ")
+                )?;
+            }
+            for (key, value) in task.meta.iter() {
+                writeln!(output, "{}      @{} {}", indent, key, value)?;
+            }
+            if task.is_done() {
                 if let Some(ref commit) = task.completed_at_commit {
-                    writeln!(output, "      @commit {}", commit)?;
+                    writeln!(output, "{}      @commit {}", indent, commit)?;
                 }
                 if let Some(ref version) = task.completed_at_version {
-                    writeln!(output, "      @version {}", version)?;
+                    writeln!(output, "{}      @version {}", indent, version)?;
                 }
             }
         }
@@ -88,22 +195,22 @@ pub fn cmd_list(
 
 #[derive(Serialize)]
 struct ReleasedEntry {
+    id: Option<String>,
     version: String,
     description: String,
     priority: Priority,
     tags: Vec<String>,
     commit: Option<String>,
+    subtasks: Vec<Change>,
+    meta: CustomMeta,
 }
 
 fn cmd_list_released(
-    changelog_file: &std::path::Path,
-    project_name: &str,
-    tags: Option<Vec<String>>,
-    priority: Option<Priority>,
+    changelog: &ChangelogStorage,
+    filter: &TaskFilter,
     released_version: Version,
     json: bool,
 ) -> Result<()> {
-    let changelog = ChangelogStorage::new(changelog_file, project_name)?;
     let mut entries: Vec<ReleasedEntry> = Vec::new();
 
     for release in changelog.log().releases.iter().rev() {
@@ -112,23 +219,29 @@ fn cmd_list_released(
         }
         for group in release.changes_by_priority.values() {
             for change in group {
-                if let Some(filter_tags) = tags.as_ref()
+                if let Some(filter_tags) = filter.tags.as_ref()
                     && !filter_tags.iter().any(|tag| change.tags.contains(tag))
                 {
                     continue;
                 }
-                if let Some(filter_priority) = priority.as_ref()
+                if let Some(filter_priority) = filter.priority.as_ref()
                     && &change.priority != filter_priority
                 {
                     continue;
                 }
+                if !filter.meta.iter().all(|meta| change.meta.matches(meta)) {
+                    continue;
+                }
 
                 entries.push(ReleasedEntry {
+                    id: change.id.clone(),
                     version: release.version.to_string(),
                     description: change.description.clone(),
                     priority: change.priority,
                     tags: change.tags.clone(),
                     commit: change.commit.clone(),
+                    subtasks: change.subtasks.clone(),
+                    meta: change.meta.clone(),
                 });
             }
         }
@@ -164,10 +277,16 @@ fn cmd_list_released(
                     .join(" ")
             )
         };
+        let id_str = entry
+            .id
+            .as_ref()
+            .map(|id| format!("{} ", id))
+            .unwrap_or_default();
         writeln!(
             output,
-            "{}. {}{}{} @version {}",
+            "{}. {}{}{}{} @version {}",
             i + 1,
+            id_str,
             entry.description,
             priority_str,
             tags_str,
@@ -176,30 +295,58 @@ fn cmd_list_released(
         if let Some(commit) = &entry.commit {
             writeln!(output, "      @commit {}", commit)?;
         }
+        for subtask in &entry.subtasks {
+            writeln!(output, "    - {}", subtask.description)?;
+        }
     }
     output::page_text(None, &output)?;
 
     Ok(())
 }
 
+fn is_overdue(task: &Task, today: NaiveDate) -> bool {
+    !task.is_closed() && task.due.is_some_and(|due| due < today)
+}
+
 fn filter_tasks<'a>(
     tasks: &'a [Task],
-    tags: Option<&[String]>,
-    priority: Option<&Priority>,
-    done: bool,
+    filter: &TaskFilter,
+    due_limit: Option<NaiveDate>,
+    today: NaiveDate,
 ) -> Vec<(usize, &'a Task)> {
+    let all = tasks;
     let mut tasks: Vec<_> = tasks.iter().enumerate().collect();
 
-    if let Some(filter_tags) = tags {
+    if let Some(filter_tags) = &filter.tags {
         tasks.retain(|(_, task)| filter_tags.iter().any(|tag| task.tags.contains(tag)));
     }
 
-    if let Some(filter_priority) = priority {
+    if let Some(filter_priority) = &filter.priority {
         tasks.retain(|(_, task)| &task.priority == filter_priority);
     }
 
-    if done {
-        tasks.retain(|(_, task)| task.completed);
+    if filter.done {
+        tasks.retain(|(_, task)| task.is_done());
+    }
+
+    if !filter.status.is_empty() {
+        tasks.retain(|(_, task)| filter.status.contains(&task.status));
+    }
+
+    if filter.ready {
+        tasks.retain(|(index, _)| dependencies::is_ready(all, *index));
+    }
+
+    if filter.overdue {
+        tasks.retain(|(_, task)| is_overdue(task, today));
+    }
+
+    if let Some(limit) = due_limit {
+        tasks.retain(|(_, task)| task.due.is_some_and(|due| due < limit));
+    }
+
+    if !filter.meta.is_empty() {
+        tasks.retain(|(_, task)| filter.meta.iter().all(|meta| task.meta.matches(meta)));
     }
 
     tasks
@@ -222,13 +369,31 @@ mod tests {
         completed_version: Option<Version>,
     ) -> Task {
         Task {
+            id: crate::models::tasks::task::derive_id(
+                description,
+                &Utc.with_ymd_and_hms(2026, 4, 1, 12, 0, 0).unwrap(),
+            ),
             description: description.to_string(),
             priority,
             tags: tags.iter().map(|tag| tag.to_string()).collect(),
-            completed,
+            status: if completed {
+                TaskStatus::Done
+            } else {
+                TaskStatus::Open
+            },
+            blocked_reason: None,
+            parent: None,
+            depends: Vec::new(),
+            due: None,
+            scheduled: None,
+            recur: None,
+            recurred: None,
+            notes: None,
+            meta: CustomMeta::default(),
             created_at_time: Utc.with_ymd_and_hms(2026, 4, 1, 12, 0, 0).unwrap(),
             created_at_version: None,
             created_at_commit: None,
+            created_by: None,
             completed_at_time: completed
                 .then(|| Utc.with_ymd_and_hms(2026, 4, 2, 12, 0, 0).unwrap()),
             completed_at_version: completed_version,
@@ -245,11 +410,15 @@ mod tests {
                 Priority::High,
                 &["feature"],
                 true,
-                Some(Version::new(0, 6, 0, false)),
+                Some(Version::new(0, 6, 0)),
             ),
         ];
 
-        let filtered = filter_tasks(&tasks, None, None, true);
+        let filter = TaskFilter {
+            done: true,
+            ..TaskFilter::default()
+        };
+        let filtered = filter_tasks(&tasks, &filter, None, NaiveDate::MIN);
 
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].1.description, "finished");
@@ -263,28 +432,155 @@ mod tests {
                 Priority::High,
                 &["feature", "ux"],
                 true,
-                Some(Version::new(0, 6, 0, false)),
+                Some(Version::new(0, 6, 0)),
             ),
             task(
                 "wrong priority",
                 Priority::Medium,
                 &["feature", "ux"],
                 true,
-                Some(Version::new(0, 6, 0, false)),
+                Some(Version::new(0, 6, 0)),
             ),
             task(
                 "wrong tag",
                 Priority::High,
                 &["backend"],
                 true,
-                Some(Version::new(0, 6, 0, false)),
+                Some(Version::new(0, 6, 0)),
             ),
         ];
-        let tags = vec!["feature".to_string(), "ux".to_string()];
+        let filter = TaskFilter {
+            tags: Some(vec!["feature".to_string(), "ux".to_string()]),
+            priority: Some(Priority::High),
+            done: true,
+            ..TaskFilter::default()
+        };
 
-        let filtered = filter_tasks(&tasks, Some(&tags), Some(&Priority::High), true);
+        let filtered = filter_tasks(&tasks, &filter, None, NaiveDate::MIN);
 
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].1.description, "matching task");
     }
+
+    #[test]
+    fn filter_tasks_by_due_date() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let due = |description: &str, completed: bool, due: &str| Task {
+            due: Some(due.parse().unwrap()),
+            ..task(description, Priority::Medium, &[], completed, None)
+        };
+        let tasks = vec![
+            due("late", false, "2026-10-10"),
+            due("late but done", true, "2026-10-10"),
+            due("this week", false, "2026-10-21"),
+            due("next month", false, "2026-11-14"),
+            task("undated", Priority::Medium, &[], false, None),
+        ];
+        let descriptions = |filter: &TaskFilter| {
+            let limit = filter.due_limit(today).unwrap();
+            filter_tasks(&tasks, filter, limit, today)
+                .into_iter()
+                .map(|(_, task)| task.description.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        let overdue = TaskFilter {
+            overdue: true,
+            ..TaskFilter::default()
+        };
+        assert_eq!(descriptions(&overdue), vec!["late"]);
+
+        let within = TaskFilter {
+            due_within: Some("7d".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(
+            descriptions(&within),
+            vec!["late", "late but done", "this week"]
+        );
+
+        let before = TaskFilter {
+            due_before: Some("2026-10-21".to_string()),
+            ..TaskFilter::default()
+        };
+        assert_eq!(descriptions(&before), vec!["late", "late but done"]);
+    }
+
+    #[test]
+    fn filter_tasks_by_status() {
+        let mut tasks = vec![
+            task("todo", Priority::Medium, &[], false, None),
+            task("doing", Priority::Medium, &[], false, None),
+            task("stuck", Priority::Medium, &[], false, None),
+            task("dropped", Priority::Medium, &[], false, None),
+        ];
+        tasks[1].status = TaskStatus::InProgress;
+        tasks[2].status = TaskStatus::Blocked;
+        tasks[3].status = TaskStatus::Cancelled;
+        let filter = TaskFilter {
+            status: vec![TaskStatus::InProgress, TaskStatus::Blocked],
+            ..TaskFilter::default()
+        };
+
+        let filtered = filter_tasks(&tasks, &filter, None, NaiveDate::MIN);
+
+        let descriptions: Vec<_> = filtered
+            .iter()
+            .map(|(_, task)| task.description.as_str())
+            .collect();
+        assert_eq!(descriptions, vec!["doing", "stuck"]);
+    }
+
+    #[test]
+    fn filter_tasks_ready_skips_tasks_waiting_on_others() {
+        let mut tasks = vec![
+            task("schema", Priority::Medium, &[], false, None),
+            task("migration", Priority::Medium, &[], false, None),
+            task("docs", Priority::Medium, &[], true, None),
+            task("release notes", Priority::Medium, &[], false, None),
+        ];
+        tasks[1].depends = vec![tasks[0].id.clone()];
+        tasks[3].depends = vec![tasks[2].id.clone()];
+        let filter = TaskFilter {
+            ready: true,
+            ..TaskFilter::default()
+        };
+
+        let filtered = filter_tasks(&tasks, &filter, None, NaiveDate::MIN);
+
+        let descriptions: Vec<_> = filtered
+            .iter()
+            .map(|(_, task)| task.description.as_str())
+            .collect();
+        assert_eq!(descriptions, vec!["schema", "release notes"]);
+    }
+
+    #[test]
+    fn filter_tasks_meta_requires_every_key_and_value() {
+        let mut tasks = vec![
+            task("parser", Priority::Medium, &[], false, None),
+            task("docs", Priority::Medium, &[], false, None),
+            task("release", Priority::Medium, &[], false, None),
+        ];
+        tasks[0].meta.insert("owner", "alice");
+        tasks[0].meta.insert("issue", "GH-123");
+        tasks[1].meta.insert("owner", "bob, alice");
+        tasks[2].meta.insert("owner", "carol");
+
+        let matching = |meta: &[&str]| {
+            let filter = TaskFilter {
+                meta: meta.iter().map(|meta| meta.to_string()).collect(),
+                ..TaskFilter::default()
+            };
+            filter_tasks(&tasks, &filter, None, NaiveDate::MIN)
+                .iter()
+                .map(|(_, task)| task.description.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching(&["owner=alice"]), vec!["parser", "docs"]);
+        assert_eq!(matching(&["owner=alice", "issue"]), vec!["parser"]);
+        assert_eq!(matching(&["Owner=carol"]), vec!["release"]);
+        assert!(matching(&["estimate"]).is_empty());
+    }
 }
@@ -0,0 +1,375 @@
+use crate::models::changes::Log;
+use crate::models::merge::{self, Conflict};
+use crate::models::tasks::{Task, dependencies};
+use crate::output;
+use crate::services::git;
+use crate::services::serializers::{changelog_serializer, todo_serializer};
+use crate::services::storage::changelog_storage::ChangelogStorage;
+use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::atomic_file;
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::{Result, anyhow, bail};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Name of the driver in `.git/config` and `.gitattributes`.
+const DRIVER_NAME: &str = "tally";
+const DRIVER_COMMAND: &str = "tally merge-driver %O %A %B %P";
+
+/// Merge two branches' TODO.md or CHANGELOG.md task by task, writing the
+/// result over `ours` as git expects. If the files cannot be merged that
+/// way, falls back to a line merge with conflict markers and fails.
+pub fn cmd_merge_driver(
+    base: String,
+    ours: String,
+    theirs: String,
+    path: Option<String>,
+) -> Result<()> {
+    merge_in(
+        &env::current_dir()?,
+        Path::new(&base),
+        Path::new(&ours),
+        Path::new(&theirs),
+        path.as_deref(),
+    )
+}
+
+/// Run the merge driver from `cwd`. `path` is git's `%P`, relative to the
+/// top of the work tree, which need not be the tally root.
+fn merge_in(cwd: &Path, base: &Path, ours: &Path, theirs: &Path, path: Option<&str>) -> Result<()> {
+    let file = path.map(|path| {
+        git::toplevel(cwd)
+            .unwrap_or_else(|| cwd.to_path_buf())
+            .join(path)
+    });
+    let paths = match file.as_deref().and_then(Path::parent) {
+        Some(dir) => ProjectPaths::containing(dir),
+        None => ProjectPaths::containing(cwd),
+    }
+    .or_else(|_| ProjectPaths::at(cwd.to_path_buf()))?;
+
+    let is_changelog = match &file {
+        Some(file) => *file == paths.changelog_file,
+        None => !read(ours)?.trim_start().starts_with("# TODO"),
+    };
+    let merged = if is_changelog {
+        merge_changelog(&paths, base, ours, theirs).map(|()| None)
+    } else {
+        merge_todo(base, ours, theirs)
+    };
+
+    let file = path.map_or_else(|| ours.display().to_string(), str::to_string);
+    let reason = match merged {
+        Ok(None) => return Ok(()),
+        Ok(Some(cycle)) => bail!(
+            "Merged {} by task, but together the branches' dependencies form a cycle: {}. \
+             Break it with `tally edit --remove-depends`, then mark the file resolved.",
+            file,
+            cycle
+        ),
+        Err(reason) => reason,
+    };
+    if git::merge_file(ours, base, theirs)? {
+        return Ok(());
+    }
+    bail!(
+        "Could not merge {} by task, left conflict markers instead: {}",
+        file,
+        reason
+    )
+}
+
+/// Merge the task lists into `ours`. Returns a dependency cycle that only
+/// the merged list contains, if any; the merge is written either way.
+fn merge_todo(base: &Path, ours: &Path, theirs: &Path) -> Result<Option<String>> {
+    let read_tasks = |path: &Path| -> Result<Vec<Task>> {
+        let content = read(path)?;
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(todo_serializer::deserialize(&content)?.tasks)
+    };
+    let base = read_tasks(base)?;
+    let theirs = read_tasks(theirs)?;
+
+    let mut storage = ListStorage::new(ours)?;
+    let tasks = merge::merge_tasks(&base, storage.tasks(), &theirs).map_err(conflict_error)?;
+    let had_cycle = dependencies::find_cycle(storage.tasks()).is_some()
+        || dependencies::find_cycle(&theirs).is_some();
+    let cycle = dependencies::find_cycle(&tasks)
+        .filter(|_| !had_cycle)
+        .map(|cycle| dependencies::describe_cycle(&tasks, &cycle));
+    storage.list_mut().tasks = tasks;
+    storage.save_list()?;
+    Ok(cycle)
+}
+
+fn merge_changelog(paths: &ProjectPaths, base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let read_log = |path: &Path| -> Result<Log> {
+        let content = read(path)?;
+        let mut log = if content.trim().is_empty() {
+            changelog_serializer::empty_log("")
+        } else {
+            changelog_serializer::from_markdown(&content)?
+        };
+        changelog_serializer::normalize(&mut log);
+        Ok(log)
+    };
+    let base = read_log(base)?;
+    let theirs = read_log(theirs)?;
+
+    let config_storage = ConfigStorage::for_project(paths)?;
+    let config = config_storage.get_config();
+    let mut changelog = ChangelogStorage::new(ours, &theirs.project_name, &config.changelog)?;
+    let releases =
+        merge::merge_releases(&base.releases, &changelog.log().releases, &theirs.releases)
+            .map_err(conflict_error)?;
+    changelog.log_mut().releases = releases;
+    changelog.save()
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+fn conflict_error(conflicts: Vec<Conflict>) -> anyhow::Error {
+    let conflicts: Vec<String> = conflicts.iter().map(|c| format!("  {}", c)).collect();
+    anyhow!("both branches changed:\n{}", conflicts.join("\n"))
+}
+
+/// Register `tally merge-driver` in `.git/config` and route the project's
+/// TODO and CHANGELOG files to it in `.gitattributes`.
+pub fn cmd_install_merge_driver(dry_run: bool, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
+    let missing = install(&paths, dry_run)?;
+
+    if json {
+        return output::print_json(&serde_json::json!({
+            "status": if dry_run { "would_install" } else { "installed" },
+            "dry_run": dry_run,
+            "driver": DRIVER_COMMAND,
+            "attributes_added": missing,
+        }));
+    }
+
+    let verb = if dry_run { "Would set" } else { "Set" };
+    println!("{} merge.{}.driver = {}", verb, DRIVER_NAME, DRIVER_COMMAND);
+    if missing.is_empty() {
+        println!(".gitattributes already routes tally files to the driver");
+    } else {
+        let verb = if dry_run { "Would add" } else { "Added" };
+        println!("{} to .gitattributes:", verb);
+        for line in &missing {
+            println!("  {}", line);
+        }
+    }
+    if !dry_run {
+        println!("Commit .gitattributes so the whole team uses the driver.");
+    }
+    Ok(())
+}
+
+/// Configure the driver and add the `.gitattributes` lines it is missing,
+/// returning those lines.
+fn install(paths: &ProjectPaths, dry_run: bool) -> Result<Vec<String>> {
+    let attributes_file = paths.root.join(".gitattributes");
+    let existing = fs::read_to_string(&attributes_file).unwrap_or_default();
+    let missing: Vec<String> = paths
+        .tally_files()
+        .iter()
+        .map(|file| format!("{} merge={}", file, DRIVER_NAME))
+        .filter(|line| !existing.lines().any(|existing| existing.trim() == line))
+        .collect();
+    if dry_run {
+        return Ok(missing);
+    }
+
+    git::set_config(
+        &paths.root,
+        &format!("merge.{}.name", DRIVER_NAME),
+        "tally task-level merge for TODO and CHANGELOG",
+    )?;
+    git::set_config(
+        &paths.root,
+        &format!("merge.{}.driver", DRIVER_NAME),
+        DRIVER_COMMAND,
+    )?;
+
+    if !missing.is_empty() {
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for line in &missing {
+            content.push_str(line);
+            content.push('\n');
+        }
+        atomic_file::write(&attributes_file, &content)?;
+    }
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    const PARSER: &str = "- [ ] parser\n      @id aaaaaa\n      @created 2026-02-20 10:00\n";
+    const LEXER: &str = "- [ ] lexer\n      @id bbbbbb\n      @created 2026-02-20 10:00\n";
+
+    fn todo(tasks: &[&str]) -> String {
+        format!(
+            "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-20\n\n## Tasks\n\n{}",
+            tasks.join("\n")
+        )
+    }
+
+    fn changelog(releases: &[(&str, &[&str])]) -> String {
+        let mut content = "# Changelog — demo\n\n*Generated on 2026-02-20*\n".to_string();
+        for (version, changes) in releases {
+            content.push_str(&format!("\n## {} — 2026-02-20\n\n### Changes\n\n", version));
+            for change in *changes {
+                content.push_str(&format!("- {}\n", change));
+            }
+        }
+        content
+    }
+
+    /// A git work tree whose tally project lives in `app/`, so `%P` differs
+    /// from the path relative to the tally root.
+    fn repo() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let app = dir.path().join("app");
+        fs::create_dir(&app).unwrap();
+        fs::write(app.join("TODO.md"), todo(&[PARSER])).unwrap();
+        (dir, app)
+    }
+
+    /// Write the three versions git hands the driver and run it on `path`.
+    fn merge(dir: &Path, path: &str, base: &str, ours: &str, theirs: &str) -> Result<String> {
+        let files = [("base", base), ("ours", ours), ("theirs", theirs)].map(|(name, content)| {
+            let file = dir.join(format!(".merge_file_{}", name));
+            fs::write(&file, content).unwrap();
+            file
+        });
+        merge_in(dir, &files[0], &files[1], &files[2], Some(path))?;
+        Ok(fs::read_to_string(&files[1]).unwrap())
+    }
+
+    #[test]
+    fn merges_todo_by_task_and_falls_back_to_markers_on_conflicts() {
+        let (dir, _) = repo();
+        let docs = "- [ ] docs\n      @id cccccc\n      @created 2026-02-20 10:00\n";
+
+        let merged = merge(
+            dir.path(),
+            "app/TODO.md",
+            &todo(&[PARSER]),
+            &todo(&[PARSER, LEXER]),
+            &todo(&[PARSER, docs]),
+        )
+        .unwrap();
+        assert!(merged.contains("- [ ] lexer") && merged.contains("- [ ] docs"));
+        assert!(!merged.contains("<<<<<<<"));
+
+        let err = merge(
+            dir.path(),
+            "app/TODO.md",
+            &todo(&[PARSER]),
+            &todo(&[&PARSER.replace("parser", "fast parser")]),
+            &todo(&[&PARSER.replace("parser", "small parser")]),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Could not merge app/TODO.md by task")
+        );
+        let ours = fs::read_to_string(dir.path().join(".merge_file_ours")).unwrap();
+        assert!(ours.contains("<<<<<<< ours"));
+    }
+
+    #[test]
+    fn merges_changelog_named_relative_to_the_git_top_level() {
+        let (dir, _) = repo();
+        let base = [("1.0.0", &["parser <!-- id: aaaaaa -->"][..])];
+
+        let merged = merge(
+            dir.path(),
+            "app/CHANGELOG.md",
+            &changelog(&base),
+            &changelog(&[("1.1.0", &["lexer <!-- id: bbbbbb -->"]), base[0]]),
+            &changelog(&[("1.1.0", &["docs <!-- id: cccccc -->"]), base[0]]),
+        )
+        .unwrap();
+
+        assert!(!merged.contains("<<<<<<<"));
+        let log = changelog_serializer::from_markdown(&merged).unwrap();
+        assert_eq!(log.releases.len(), 2);
+        let descriptions: Vec<_> = log.releases[0]
+            .changes_by_priority
+            .values()
+            .flatten()
+            .map(|change| change.description.as_str())
+            .collect();
+        assert_eq!(descriptions, ["lexer", "docs"]);
+    }
+
+    #[test]
+    fn reports_a_cycle_only_the_merge_creates() {
+        let (dir, _) = repo();
+        let depends = |task: &str, id: &str| {
+            task.replace("10:00\n", &format!("10:00\n      @depends {}\n", id))
+        };
+
+        let err = merge(
+            dir.path(),
+            "app/TODO.md",
+            &todo(&[PARSER, LEXER]),
+            &todo(&[&depends(PARSER, "bbbbbb"), LEXER]),
+            &todo(&[PARSER, &depends(LEXER, "aaaaaa")]),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("dependencies form a cycle"));
+        let ours = fs::read_to_string(dir.path().join(".merge_file_ours")).unwrap();
+        assert!(!ours.contains("<<<<<<<"));
+        assert_eq!(ours.matches("@depends").count(), 2);
+    }
+
+    #[test]
+    fn install_configures_git_and_adds_missing_attributes_once() {
+        let (_dir, app) = repo();
+        let paths = ProjectPaths::at(app.clone()).unwrap();
+        let attributes = app.join(".gitattributes");
+        fs::write(&attributes, "*.png binary").unwrap();
+
+        let planned = install(&paths, true).unwrap();
+        assert_eq!(planned.len(), 2);
+        assert_eq!(fs::read_to_string(&attributes).unwrap(), "*.png binary");
+
+        assert_eq!(install(&paths, false).unwrap(), planned);
+        let content = fs::read_to_string(&attributes).unwrap();
+        assert_eq!(content, format!("*.png binary\n{}\n", planned.join("\n")));
+        assert!(install(&paths, false).unwrap().is_empty());
+
+        let driver = Command::new("git")
+            .args(["config", "merge.tally.driver"])
+            .current_dir(&app)
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&driver.stdout).trim(),
+            DRIVER_COMMAND
+        );
+    }
+}
@@ -1,15 +1,36 @@
 pub mod add;
+pub mod config;
 pub mod done;
+pub mod edit;
+pub mod graph;
+pub mod history;
+pub mod hooks;
 pub mod list;
+pub mod merge_driver;
 pub mod remove;
 pub mod scan;
 pub mod semver;
+pub mod show;
+pub mod status;
 pub mod yank;
 
 pub use add::cmd_add;
+pub use config::{
+    cmd_config_get, cmd_config_list, cmd_config_set, cmd_config_show, cmd_config_unset,
+};
 pub use done::cmd_done;
+pub use edit::cmd_edit;
+pub use graph::cmd_graph;
+pub use history::{cmd_history, cmd_redo, cmd_undo};
+pub use hooks::{
+    cmd_hook_commit_msg, cmd_hook_post_commit, cmd_hook_prepare_commit_msg, cmd_hooks_install,
+    cmd_hooks_uninstall,
+};
 pub use list::cmd_list;
+pub use merge_driver::{cmd_install_merge_driver, cmd_merge_driver};
 pub use remove::cmd_remove;
 pub use scan::cmd_scan;
 pub use semver::cmd_semver;
+pub use show::cmd_show;
+pub use status::cmd_set_status;
 pub use yank::cmd_yank;
@@ -4,14 +4,17 @@ use crate::services::git;
 use crate::services::storage::changelog_storage::ChangelogStorage;
 use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
-use crate::utils::matching::{MatchCandidate, score_percent, select_unambiguous};
+use crate::utils::matching::{
+    MatchCandidate, id_match, requested_id, score_percent, select_unambiguous, tag_query_score,
+};
 use crate::utils::project_paths::ProjectPaths;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 
 pub fn cmd_remove(
     description: String,
+    id: Option<String>,
     released: Option<String>,
     tags: Option<Vec<String>>,
     dry_run: bool,
@@ -21,38 +24,57 @@ pub fn cmd_remove(
     let paths = ProjectPaths::get_paths()?;
     if let Some(released_version_str) = released {
         let released_version = Version::parse(&released_version_str)?;
-        return cmd_remove_released(description, released_version, tags, dry_run, auto, json);
+        return cmd_remove_released(description, id, released_version, tags, dry_run, auto, json);
     }
 
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
 
-    let matcher = SkimMatcherV2::default();
     let tasks = storage.tasks();
-    let mut candidates = Vec::new();
-
-    for (i, task) in tasks.iter().enumerate() {
-        if let Some(filter_tags) = tags.as_ref()
-            && !filter_tags.iter().any(|tag| task.tags.contains(tag))
-        {
-            continue;
+    let selection = match requested_id(&description, id.as_deref(), |id| {
+        storage.find_task_by_id(id).is_some()
+    }) {
+        Some(id) => {
+            let index = storage
+                .find_task_by_id(&id)
+                .ok_or_else(|| anyhow!("No task found with ID '{}'", id))?;
+            Some(id_match(index, tasks[index].description.clone()))
         }
-        let exact = task.description.eq_ignore_ascii_case(&description);
-        if let Some(score) = matcher
-            .fuzzy_match(&task.description, &description)
-            .or(exact.then_some(i64::MAX))
-        {
-            candidates.push(MatchCandidate {
-                value: i,
-                score,
-                label: task.description.clone(),
-                exact,
-            });
+        None => {
+            let matcher = SkimMatcherV2::default();
+            let mut candidates = Vec::new();
+
+            for (i, task) in tasks.iter().enumerate() {
+                if let Some(filter_tags) = tags.as_ref()
+                    && !filter_tags.iter().any(|tag| task.tags.contains(tag))
+                {
+                    continue;
+                }
+                let exact = task.description.eq_ignore_ascii_case(&description);
+                if let Some(score) = matcher
+                    .fuzzy_match(&task.description, &description)
+                    .or(exact.then_some(i64::MAX))
+                    .or_else(|| {
+                        tag_query_score(&description, &task.tags, |word| {
+                            matcher.fuzzy_match(&task.description, word)
+                        })
+                    })
+                {
+                    candidates.push(MatchCandidate {
+                        value: i,
+                        score,
+                        label: task.description.clone(),
+                        exact,
+                    });
+                }
+            }
+
+            select_unambiguous(candidates, config.matching.task_min_score, &description)?
         }
-    }
+    };
 
-    match select_unambiguous(candidates, config.matching.task_min_score, &description)? {
+    match selection {
         Some(best_match) => {
             let index = best_match.value;
             let score = best_match.score;
@@ -70,8 +92,8 @@ pub fn cmd_remove(
                     }));
                 }
                 println!("Would remove (match: {:.0}%):", score_pct);
-                let checkbox = if task.completed { "x" } else { " " };
-                println!("  [{}] {}", checkbox, task.description);
+                let checkbox = task.status.checkbox();
+                println!("  [{}] {} {}", checkbox, task.id, task.description);
                 return Ok(());
             }
 
@@ -109,6 +131,7 @@ pub fn cmd_remove(
 
 fn cmd_remove_released(
     description: String,
+    id: Option<String>,
     released_version: Version,
     tags: Option<Vec<String>>,
     dry_run: bool,
@@ -116,18 +139,29 @@ fn cmd_remove_released(
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let storage = ListStorage::new(&paths.todo_file)?;
-    let mut changelog = ChangelogStorage::new(&paths.changelog_file, storage.project_name())?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
+    let mut changelog =
+        ChangelogStorage::for_project(&paths, storage.project_name(), &config.changelog)?;
 
-    if dry_run {
-        if let Some((v, change)) = changelog.remove_change(
+    let requested = requested_id(&description, id.as_deref(), |id| {
+        changelog
+            .find_change_by_id(id)
+            .is_some_and(|(version, _)| version == released_version)
+    });
+    let removed = match requested {
+        Some(ref id) => changelog.remove_change_by_id(id, Some(&released_version)),
+        None => changelog.remove_change(
             &description,
             Some(&released_version),
             tags.as_deref(),
             config.matching.released_min_score,
-        )? {
+        )?,
+    };
+
+    if dry_run {
+        if let Some((v, change)) = removed {
             if json {
                 output::print_json(&serde_json::json!({
                     "status": "would_remove_released",
@@ -152,12 +186,7 @@ fn cmd_remove_released(
         return Ok(());
     }
 
-    if let Some((v, change)) = changelog.remove_change(
-        &description,
-        Some(&released_version),
-        tags.as_deref(),
-        config.matching.released_min_score,
-    )? {
+    if let Some((v, change)) = removed {
         changelog.save()?;
         if auto || config.auto_commit_remove() {
             if json {
@@ -177,6 +206,12 @@ fn cmd_remove_released(
             println!("Removed from {}: {}", v, change.description);
         }
         Ok(())
+    } else if let Some(id) = requested {
+        anyhow::bail!(
+            "No released task found with ID '{}' in {}",
+            id,
+            released_version
+        )
     } else {
         anyhow::bail!("No matching released task found.")
     }
@@ -1,19 +1,44 @@
+use crate::application::commands::add::current_version;
 use crate::models::AppConfig;
+use crate::models::common::Priority;
 use crate::models::tasks::Task;
 use crate::output;
+use crate::services::storage::changelog_storage::ChangelogStorage;
 use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::scan_state;
 use crate::services::storage::task_storage::ListStorage;
 use crate::services::{git, source};
 use crate::utils::matching::{MatchCandidate, score_passes, score_percent, select_unambiguous};
 use crate::utils::project_paths::ProjectPaths;
 use crate::utils::task_input::parse_task_input;
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use serde::Serialize;
 use std::collections::HashSet;
 use std::fmt::Write as _;
 
+/// Which commits `scan --git` reads. Without any of these, it reads the
+/// commits since the previous scan, or the latest `scan.git_log_limit`.
+/// Only such a default scan records HEAD in `.tally/scan-head`.
+#[derive(Debug, Default)]
+pub struct CommitRange {
+    /// A revision, tag or date to scan from.
+    pub since: Option<String>,
+    /// An explicit `A..B` revision range.
+    pub range: Option<String>,
+    /// Read commits on every branch, not only the current one.
+    pub all_branches: bool,
+    /// Scan from the git tag of the newest release in the changelog.
+    pub since_last_release: bool,
+}
+
+impl CommitRange {
+    fn is_set(&self) -> bool {
+        self.since.is_some() || self.range.is_some() || self.all_branches || self.since_last_release
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ScanSummary {
     dry_run: bool,
@@ -47,13 +72,16 @@ pub fn cmd_scan(
     git: bool,
     todo: bool,
     done: bool,
+    commits: CommitRange,
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths().or_else(|_| ProjectPaths::for_current_dir())?;
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
 
+    // Choosing commits implies a git scan.
+    let git = git || commits.is_set();
     let has_selector = git || todo || done;
     let run_git = git || !has_selector;
     let run_todo = todo || !has_selector;
@@ -67,12 +95,13 @@ pub fn cmd_scan(
     };
 
     if run_git {
-        summary.git_matches = run_git_scan(&paths.root, &mut storage, config, auto, dry_run, json)?;
+        summary.git_matches =
+            run_git_scan(&paths, &mut storage, config, &commits, auto, dry_run, json)?;
     }
 
     if run_todo || run_done {
         let source_summary = run_source_scan(
-            &paths.root,
+            &paths,
             &mut storage,
             config,
             dry_run,
@@ -92,49 +121,28 @@ pub fn cmd_scan(
 }
 
 fn run_git_scan(
-    root: &std::path::Path,
+    paths: &ProjectPaths,
     storage: &mut ListStorage,
     config: &AppConfig,
+    range: &CommitRange,
     auto: bool,
     dry_run: bool,
     json: bool,
 ) -> Result<Vec<GitScanMatch>> {
-    let commits =
-        git::scan_recent_commits(root, &config.git.done_prefix, config.scan.git_log_limit)?;
-    let matcher = SkimMatcherV2::default();
+    let root = &paths.root;
+    let log_args = commit_log_args(paths, storage, config, range)?;
+    let commits = git::scan_commits(root, &config.git.done_prefix, &log_args)?;
+    let head = git::resolve_commit(root, "HEAD");
     let mut matches_found = 0;
     let mut completed = Vec::new();
     let mut summary = Vec::new();
 
     for (idx, task) in storage.tasks().iter().enumerate() {
-        if task.completed {
+        if task.is_closed() {
             continue;
         }
 
-        let mut best_match: Option<(String, i64, String)> = None;
-
-        for commit in &commits {
-            if commit.date < task.created_at_time {
-                continue;
-            }
-
-            for done in &commit.done_items {
-                if let Some(score) = matcher.fuzzy_match(&task.description, done) {
-                    if !score_passes(score, config.matching.task_min_score) {
-                        continue;
-                    }
-                    let is_better = best_match
-                        .as_ref()
-                        .map(|(_, best, _)| score > *best)
-                        .unwrap_or(true);
-                    if is_better {
-                        best_match = Some((commit.hash.clone(), score, done.clone()));
-                    }
-                }
-            }
-        }
-
-        if let Some((hash, score, done_line)) = best_match {
+        if let Some((hash, score, done_line)) = best_commit_match(task, &commits, config) {
             matches_found += 1;
             let score_pct = score_percent(score);
 
@@ -197,16 +205,23 @@ fn run_git_scan(
 
     if !dry_run {
         for (idx, hash) in &completed {
-            storage.complete_task(*idx, None)?;
-            if let Some(task) = storage.tasks_mut().get_mut(*idx) {
-                task.completed_at_commit = Some(hash.clone());
-            }
+            storage.mark_done(*idx, None, Some(hash.clone()))?;
         }
         if !completed.is_empty() {
             storage.save_list()?;
         }
     }
 
+    // Only a default scan whose matches were all applied moves the mark, so
+    // declined matches and explicitly chosen commits are read again next time.
+    if !dry_run
+        && !range.is_set()
+        && summary.iter().all(|found| found.accepted)
+        && let Some(head) = head
+    {
+        scan_state::record_scanned_commit(&paths.state_dir, &head)?;
+    }
+
     if matches_found == 0 && !json {
         println!("No git commit matches found.");
     }
@@ -214,6 +229,133 @@ fn run_git_scan(
     Ok(summary)
 }
 
+/// `git log` arguments selecting the commits to scan.
+fn commit_log_args(
+    paths: &ProjectPaths,
+    storage: &ListStorage,
+    config: &AppConfig,
+    range: &CommitRange,
+) -> Result<Vec<String>> {
+    let root = &paths.root;
+    let all = range.all_branches.then(|| "--all".to_string());
+    // Everything reachable from HEAD, or from any branch, but not from `rev`.
+    let after = |rev: &str| match &all {
+        Some(all) => vec![all.clone(), format!("^{}", rev)],
+        None => vec![format!("{}..HEAD", rev)],
+    };
+
+    if let Some(range) = &range.range {
+        return Ok(vec!["--end-of-options".to_string(), range.clone()]);
+    }
+
+    let since = if range.since_last_release {
+        Some(last_release_tag(paths, storage, config)?)
+    } else {
+        range.since.clone()
+    };
+    if let Some(since) = since {
+        return Ok(match git::resolve_commit(root, &since) {
+            Some(commit) => after(&commit),
+            // Not a revision, so let git read it as a date.
+            None => all
+                .into_iter()
+                .chain([format!("--since={}", since)])
+                .collect(),
+        });
+    }
+
+    match scan_state::last_scanned_commit(&paths.state_dir) {
+        Some(last) if git::is_ancestor(root, &last, "HEAD") => Ok(after(&last)),
+        _ => Ok(all
+            .into_iter()
+            .chain(["-n".to_string(), config.scan.git_log_limit.to_string()])
+            .collect()),
+    }
+}
+
+/// The git tag of the newest release in the changelog.
+fn last_release_tag(
+    paths: &ProjectPaths,
+    storage: &ListStorage,
+    config: &AppConfig,
+) -> Result<String> {
+    let changelog =
+        ChangelogStorage::for_project(paths, storage.project_name(), &config.changelog)?;
+    let Some(version) = changelog.log().releases.iter().map(|r| &r.version).max() else {
+        bail!(
+            "No releases in {} to scan from; use --since or --range",
+            paths.files.changelog
+        );
+    };
+
+    let candidates = [
+        format!("{}{}", config.changelog.tag_prefix, version),
+        version.to_string(),
+    ];
+    candidates
+        .iter()
+        .find(|tag| git::resolve_commit(&paths.root, tag).is_some())
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "No git tag found for release {} (tried {})",
+                version,
+                candidates.join(", ")
+            )
+        })
+}
+
+/// The `done:` item in `commits` that best matches `task`, as the commit
+/// hash, match score and item text. A commit that closes the task by ID or
+/// `@issue` wins outright. Commits older than the task are skipped.
+pub(crate) fn best_commit_match(
+    task: &Task,
+    commits: &[git::CommitEntry],
+    config: &AppConfig,
+) -> Option<(String, i64, String)> {
+    let matcher = SkimMatcherV2::default();
+    let mut best_match: Option<(String, i64, String)> = None;
+
+    for commit in commits {
+        if commit.date < task.created_at_time {
+            continue;
+        }
+
+        if let Some(reference) = commit.refs.iter().find(|r| refers_to(task, r)) {
+            // A reference is exact, so it scores the full 100.
+            return Some((commit.hash.clone(), 100, format!("closes {}", reference)));
+        }
+
+        for done in &commit.done_items {
+            if let Some(score) = matcher.fuzzy_match(&task.description, done) {
+                if !score_passes(score, config.matching.task_min_score) {
+                    continue;
+                }
+                let is_better = best_match
+                    .as_ref()
+                    .map(|(_, best, _)| score > *best)
+                    .unwrap_or(true);
+                if is_better {
+                    best_match = Some((commit.hash.clone(), score, done.clone()));
+                }
+            }
+        }
+    }
+
+    best_match
+}
+
+/// Whether a commit reference names `task` by ID or by its `@issue` value.
+fn refers_to(task: &Task, reference: &str) -> bool {
+    let normalize = |value: &str| value.trim().trim_start_matches('#').to_ascii_lowercase();
+    let reference = normalize(reference);
+    task.id == reference
+        || task
+            .meta
+            .values("issue")
+            .any(|issue| issue.split(',').any(|issue| normalize(issue) == reference))
+}
+
 #[derive(Debug, Default)]
 struct SourceScanSummary {
     source_added: Vec<Task>,
@@ -221,7 +363,7 @@ struct SourceScanSummary {
 }
 
 fn run_source_scan(
-    root: &std::path::Path,
+    paths: &ProjectPaths,
     storage: &mut ListStorage,
     config: &AppConfig,
     dry_run: bool,
@@ -229,7 +371,12 @@ fn run_source_scan(
     include_done: bool,
     json: bool,
 ) -> Result<SourceScanSummary> {
-    let markers = source::scan_project(root, &config.scan.todo_markers, &config.scan.done_markers)?;
+    let markers = source::scan_project(
+        &paths.root,
+        &paths.tally_files(),
+        &config.scan.todo_markers,
+        &config.scan.done_markers,
+    )?;
 
     if markers.is_empty() {
         if !json {
@@ -242,11 +389,15 @@ fn run_source_scan(
     let mut done_descriptions = HashSet::new();
     for task in storage.tasks() {
         existing.insert(task.description.clone());
-        if task.completed {
+        if task.is_closed() {
             done_descriptions.insert(task.description.clone());
         }
     }
 
+    let record_origin = config.preferences.record_origin;
+    let version = record_origin
+        .then(|| current_version(paths, storage, config))
+        .flatten();
     let mut planned = Vec::new();
     let mut planned_done = Vec::new();
     let mut seen_new = HashSet::new();
@@ -260,7 +411,7 @@ fn run_source_scan(
             let match_text = parsed_source_marker_text(&todo.text);
             let mut candidates = Vec::new();
             for (idx, task) in storage.tasks().iter().enumerate() {
-                if task.completed {
+                if task.is_closed() {
                     continue;
                 }
 
@@ -297,7 +448,7 @@ fn run_source_scan(
             continue;
         }
 
-        let task = task_from_source_todo(&todo)?;
+        let mut task = task_from_source_todo(&todo, json);
         if existing.contains(&task.description) || seen_new.contains(&task.description) {
             if done_descriptions.contains(&task.description) && !json {
                 println!("{} - This seems like it's already done", todo.location());
@@ -306,6 +457,13 @@ fn run_source_scan(
         }
 
         seen_new.insert(task.description.clone());
+        if record_origin
+            && let Some((commit, author)) = git::blame_line(&paths.root, &todo.path, todo.line)
+        {
+            task.created_at_commit = Some(commit);
+            task.created_by = Some(author);
+        }
+        task.created_at_version = version.clone();
         planned.push(task);
     }
 
@@ -351,13 +509,13 @@ fn run_source_scan(
         });
     }
 
-    if !planned.is_empty() {
-        storage.add_tasks(planned.clone())?;
+    for task in &planned {
+        storage.list_mut().add_task(task.clone());
     }
-
     for done_match in &planned_done {
-        storage.complete_task(done_match.task_index, None)?;
+        storage.mark_done(done_match.task_index, None, None)?;
     }
+    storage.save_list()?;
 
     if !planned.is_empty() && !json {
         println!("Added {} source TODO task(s)", planned.len());
@@ -380,17 +538,35 @@ fn parsed_source_marker_text(text: &str) -> String {
         .unwrap_or_else(|_| text.to_string())
 }
 
-fn task_from_source_todo(todo: &source::SourceTodo) -> Result<Task> {
-    let parsed = parse_task_input(&todo.text, None, None)?;
+/// A task for a source TODO marker. Text tally cannot parse, such as a
+/// marker holding only tags, is kept as written rather than failing the scan.
+fn task_from_source_todo(todo: &source::SourceTodo, json: bool) -> Task {
+    let parsed = match parse_task_input(&todo.text, None, None) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            if !json {
+                println!(
+                    "Warning: {} - {:#}; keeping the marker text as written",
+                    todo.location(),
+                    e
+                );
+            }
+            let description = format!("{} - {}", todo.location(), todo.text.trim());
+            return Task::new(description, Priority::Medium, Vec::new());
+        }
+    };
     let description = format!("{} - {}", todo.location(), parsed.description);
-    Ok(Task::new(description, parsed.priority, parsed.tags))
+    let mut task = Task::new(description, parsed.priority, parsed.tags);
+    task.due = parsed.due;
+    task.scheduled = parsed.scheduled;
+    task
 }
 
 fn write_task_line(output: &mut String, task: &Task) -> Result<()> {
     let priority = match task.priority {
-        crate::models::common::Priority::High => " (high)",
-        crate::models::common::Priority::Medium => "",
-        crate::models::common::Priority::Low => " (low)",
+        Priority::High => " (high)",
+        Priority::Medium => "",
+        Priority::Low => " (low)",
     };
 
     let tags = if task.tags.is_empty() {
@@ -409,3 +585,32 @@ fn write_task_line(output: &mut String, task: &Task) -> Result<()> {
     writeln!(output, "  [ ] {}{}{}", task.description, priority, tags)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(text: &str) -> source::SourceTodo {
+        source::SourceTodo {
+            path: "src/lib.rs".to_string(),
+            line: 3,
+            text: text.to_string(),
+            kind: source::SourceMarkerKind::Todo,
+        }
+    }
+
+    #[test]
+    fn source_todos_keep_dates_and_unparsable_text() {
+        let task = task_from_source_todo(&todo("Tidy parser due:2026-11-01 #parser"), true);
+        assert_eq!(task.description, "src/lib.rs:3 - Tidy parser");
+        assert_eq!(task.tags, vec!["parser"]);
+        assert_eq!(task.due, chrono::NaiveDate::from_ymd_opt(2026, 11, 1));
+
+        let task = task_from_source_todo(&todo("revisit due:someday"), true);
+        assert_eq!(task.description, "src/lib.rs:3 - revisit due:someday");
+        assert_eq!(task.due, None);
+
+        let task = task_from_source_todo(&todo("#parser"), true);
+        assert_eq!(task.description, "src/lib.rs:3 - #parser");
+    }
+}
@@ -1,97 +1,236 @@
 use anyhow::Result;
 
-use crate::models::common::Version;
+use crate::models::app_config::{CancelledTasks, Semver};
+use crate::models::changes::Change;
+use crate::models::common::{BumpLevel, TaskStatus, Version};
+use crate::models::tasks::{CustomMeta, Task, recurrence, tree};
 use crate::output;
 use crate::services::git;
 use crate::services::storage::changelog_storage::ChangelogStorage;
 use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
+use crate::utils::conventional_commit;
+use crate::utils::dates;
 use crate::utils::project_paths::ProjectPaths;
+use serde::Serialize;
 use std::fmt::Write as _;
 
+/// Why `--bump` or `--infer` chose the version being released.
+#[derive(Debug, Clone, Serialize)]
+struct BumpDecision {
+    level: BumpLevel,
+    from: Version,
+    reasons: Vec<String>,
+}
+
 pub fn cmd_semver(
-    version_str: String,
+    version_str: Option<String>,
+    bump: Option<BumpLevel>,
+    infer: bool,
     dry_run: bool,
     summary: bool,
     auto: bool,
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let mut changelog = ChangelogStorage::new(&paths.changelog_file, storage.project_name())?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
+    let mut changelog =
+        ChangelogStorage::for_project(&paths, storage.project_name(), &config.changelog)?;
 
-    let version = Version::parse(&version_str)?;
-
-    let unversioned_indices: Vec<usize> = storage
+    let groups = release_groups(storage.tasks());
+    let unversioned_indices: Vec<usize> = groups
+        .iter()
+        .flat_map(|(root, subtasks)| std::iter::once(*root).chain(subtasks.iter().copied()))
+        .collect();
+    let held = storage
         .tasks()
         .iter()
-        .enumerate()
-        .filter(|(_, t)| t.completed && t.completed_at_version.is_none())
-        .map(|(idx, _)| idx)
+        .filter(|t| t.is_done() && t.completed_at_version.is_none())
+        .count()
+        - unversioned_indices.len();
+    let cancelled_indices: Vec<usize> = match config.semver.cancelled {
+        CancelledTasks::Skip => Vec::new(),
+        CancelledTasks::Record => (0..storage.tasks().len())
+            .filter(|&i| {
+                let task = &storage.tasks()[i];
+                task.status == TaskStatus::Cancelled && task.completed_at_version.is_none()
+            })
+            .collect(),
+    };
+    let cancelled_tasks: Vec<_> = cancelled_indices
+        .iter()
+        .map(|idx| storage.tasks()[*idx].clone())
         .collect();
 
-    if unversioned_indices.is_empty() {
+    let selected_tasks: Vec<_> = unversioned_indices
+        .iter()
+        .map(|idx| storage.tasks()[*idx].clone())
+        .collect();
+    let recurring = next_instances(storage.tasks(), &selected_tasks);
+
+    let (version, decision) = match version_str {
+        Some(version_str) => (Version::parse(&version_str)?, None),
+        None => {
+            let from = changelog
+                .log()
+                .releases
+                .iter()
+                .map(|release| &release.version)
+                .max()
+                .cloned()
+                .unwrap_or_else(|| Version::new(0, 0, 0));
+            let (level, reasons) = match bump {
+                Some(level) => (level, vec![format!("--bump {} requested", level)]),
+                None if infer => infer_bump(&selected_tasks, &config.semver),
+                None => anyhow::bail!("Provide a version, --bump LEVEL, or --infer"),
+            };
+            let version = from.bump(level, &config.semver.prerelease_label);
+            (
+                version,
+                Some(BumpDecision {
+                    level,
+                    from,
+                    reasons,
+                }),
+            )
+        }
+    };
+
+    if unversioned_indices.is_empty() && cancelled_indices.is_empty() {
         if json {
             return output::print_json(&serde_json::json!({
                 "status": "noop",
                 "dry_run": dry_run,
                 "version": version,
+                "bump": decision,
                 "tasks": [],
             }));
         }
         println!("Nothing to do: No completed tasks without a version.");
+        print_held(held);
         return Ok(());
     }
 
-    let selected_tasks: Vec<_> = unversioned_indices
-        .iter()
-        .map(|idx| storage.tasks()[*idx].clone())
-        .collect();
-
     if dry_run {
         if json {
             return output::print_json(&serde_json::json!({
                 "status": "would_release",
                 "dry_run": true,
                 "version": version,
+                "bump": decision,
                 "tasks": selected_tasks,
+                "cancelled": cancelled_tasks,
+                "recurring": recurring,
+                "held_subtasks": held,
             }));
         }
         let mut output = String::new();
+        if let Some(decision) = &decision {
+            writeln!(
+                output,
+                "Computed version {} ({} bump from {}):",
+                version, decision.level, decision.from
+            )?;
+            for reason in &decision.reasons {
+                writeln!(output, "  - {}", reason)?;
+            }
+            writeln!(output)?;
+        }
         writeln!(
             output,
-            "Would assign version {} and move {} task(s) to CHANGELOG.md:",
+            "Would assign version {} and move {} task(s) to {}:",
             version,
-            unversioned_indices.len()
+            groups.len(),
+            paths.files.changelog
         )?;
-        for idx in &unversioned_indices {
-            writeln!(output, "  [x] {}", storage.tasks()[*idx].description)?;
+        for (root, subtasks) in &groups {
+            writeln!(output, "  [x] {}", storage.tasks()[*root].description)?;
+            for idx in subtasks {
+                writeln!(output, "    [x] {}", storage.tasks()[*idx].description)?;
+            }
+        }
+        if !cancelled_tasks.is_empty() {
+            writeln!(
+                output,
+                "Would record {} cancelled task(s):",
+                cancelled_tasks.len()
+            )?;
+            for task in &cancelled_tasks {
+                writeln!(output, "  [-] {}", task.description)?;
+            }
+        }
+        if !recurring.is_empty() {
+            writeln!(
+                output,
+                "Would add {} recurring task(s) back:",
+                recurring.len()
+            )?;
+            for task in &recurring {
+                writeln!(output, "  [ ] {}", task.description)?;
+            }
         }
         output::page_text(None, &output)?;
+        print_held(held);
         return Ok(());
     }
 
-    for idx in &unversioned_indices {
+    for idx in unversioned_indices.iter().chain(&cancelled_indices) {
         if let Some(task) = storage.tasks_mut().get_mut(*idx) {
             task.completed_at_version = Some(version.clone());
         }
     }
 
-    let changes = selected_tasks
+    let to_change = |task: &Task| {
+        let mut change = Change::from(task);
+        if !config.semver.keep_meta {
+            change.meta = CustomMeta::default();
+        }
+        change
+    };
+    let changes = groups
         .iter()
-        .map(crate::models::changes::Change::from)
+        .map(|(root, subtasks)| {
+            let mut change = to_change(&storage.tasks()[*root]);
+            change.subtasks = subtasks
+                .iter()
+                .map(|idx| to_change(&storage.tasks()[*idx]))
+                .collect();
+            change
+        })
         .collect();
     let inserted = changelog.merge_changes_for_version(&version, changes);
+    changelog.merge_cancelled_for_version(
+        &version,
+        cancelled_indices
+            .iter()
+            .map(|idx| to_change(&storage.tasks()[*idx]))
+            .collect(),
+    );
 
     let mut removal_indices = unversioned_indices;
+    removal_indices.extend(&cancelled_indices);
     removal_indices.sort_unstable_by(|a, b| b.cmp(a));
     for idx in removal_indices {
-        storage.remove_task(idx)?;
+        storage.list_mut().remove_task(idx);
+    }
+
+    // Copies of released subtasks stay under their parent only if it is
+    // still in TODO.md.
+    let mut recurring = recurring;
+    for task in &mut recurring {
+        if let Some(parent) = &task.parent
+            && storage.find_task_by_id(parent).is_none()
+        {
+            task.parent = None;
+        }
+    }
+    for task in &recurring {
+        storage.list_mut().add_task(task.clone());
     }
 
-    changelog.save()?;
+    changelog.save_with_list(&mut storage)?;
 
     if auto || config.auto_commit_semver() {
         if json {
@@ -106,14 +245,29 @@ pub fn cmd_semver(
             "status": "released",
             "dry_run": false,
             "version": version,
+            "bump": decision,
             "inserted": inserted,
             "tasks": selected_tasks,
+            "cancelled": cancelled_tasks,
+            "recurring": recurring,
+            "held_subtasks": held,
         }))?;
     } else {
         println!(
-            "Moved {} task(s) into CHANGELOG.md under version {}",
-            inserted, version
+            "Moved {} task(s) into {} under version {}",
+            inserted, paths.files.changelog, version
         );
+        if !cancelled_tasks.is_empty() {
+            println!("Recorded {} cancelled task(s)", cancelled_tasks.len());
+        }
+        if !recurring.is_empty() {
+            println!(
+                "Added {} recurring task(s) back to {}",
+                recurring.len(),
+                paths.files.todo
+            );
+        }
+        print_held(held);
     }
 
     if summary && !json {
@@ -128,3 +282,212 @@ pub fn cmd_semver(
 
     Ok(())
 }
+
+/// Fresh copies of released recurring tasks that have no open copy yet.
+/// Time-based tasks usually got theirs when they were completed.
+fn next_instances(tasks: &[Task], released: &[Task]) -> Vec<Task> {
+    let today = dates::today();
+    let mut next: Vec<Task> = Vec::new();
+    for task in released {
+        if recurrence::has_recurred(tasks, task)
+            || next.iter().any(|copy| copy.description == task.description)
+        {
+            continue;
+        }
+        next.extend(recurrence::next_instance(task, today));
+    }
+    next
+}
+
+/// Completed, unversioned tasks to release, each with the subtasks that roll
+/// up into it. Subtasks of a task that is still open wait for their parent,
+/// and a task with open subtasks waits for them, so none is left without
+/// its parent; subtasks of a cancelled task are released on their own.
+fn release_groups(tasks: &[Task]) -> Vec<(usize, Vec<usize>)> {
+    let releasable = |i: usize| tasks[i].is_done() && tasks[i].completed_at_version.is_none();
+
+    (0..tasks.len())
+        .filter(|&i| {
+            releasable(i)
+                && tree::parent_index(tasks, i).is_none_or(|parent| match tasks[parent].status {
+                    TaskStatus::Done => !releasable(parent),
+                    TaskStatus::Cancelled => true,
+                    _ => false,
+                })
+                && tree::descendants(tasks, i)
+                    .into_iter()
+                    .all(|subtask| tasks[subtask].is_closed())
+        })
+        .map(|root| {
+            let subtasks = tree::descendants(tasks, root)
+                .into_iter()
+                .filter(|&i| {
+                    releasable(i)
+                        && tree::ancestors(tasks, i)
+                            .into_iter()
+                            .take_while(|&ancestor| ancestor != root)
+                            .all(releasable)
+                })
+                .collect();
+            (root, subtasks)
+        })
+        .collect()
+}
+
+fn print_held(held: usize) {
+    if held > 0 {
+        println!(
+            "Kept {} completed task(s) in TODO.md until their parent or subtasks are done.",
+            held
+        );
+    }
+}
+
+/// Pick the largest bump any completed task asks for, explaining each choice.
+fn infer_bump(tasks: &[Task], config: &Semver) -> (BumpLevel, Vec<String>) {
+    let signals: Vec<(BumpLevel, String)> =
+        tasks.iter().map(|task| task_bump(task, config)).collect();
+
+    let Some(level) = signals.iter().map(|(level, _)| *level).max() else {
+        return (
+            BumpLevel::Patch,
+            vec!["no completed tasks; defaulting to patch".to_string()],
+        );
+    };
+
+    let reasons = signals
+        .into_iter()
+        .filter(|(signal, _)| *signal == level)
+        .map(|(_, reason)| reason)
+        .collect();
+    (level, reasons)
+}
+
+fn task_bump(task: &Task, config: &Semver) -> (BumpLevel, String) {
+    let description = &task.description;
+    let conventional = conventional_commit::parse(description);
+
+    if conventional.as_ref().is_some_and(|c| c.breaking)
+        || conventional_commit::has_breaking_note(description)
+    {
+        return (
+            BumpLevel::Major,
+            format!("'{}' is marked as a breaking change", description),
+        );
+    }
+
+    let tag_level = task
+        .tags
+        .iter()
+        .filter_map(|tag| config.bump_for_tag(tag).map(|level| (level, tag)))
+        .max_by_key(|(level, _)| *level);
+    let prefix_level = conventional.map(|c| {
+        let level = if c.kind.eq_ignore_ascii_case("feat") {
+            BumpLevel::Minor
+        } else {
+            BumpLevel::Patch
+        };
+        (level, c.kind)
+    });
+
+    match (tag_level, prefix_level) {
+        (Some((tag_level, tag)), prefix) if prefix.is_none_or(|(level, _)| tag_level >= level) => (
+            tag_level,
+            format!("'{}' has tag #{} ({})", description, tag, tag_level),
+        ),
+        (_, Some((level, kind))) => (
+            level,
+            format!("'{}' has a `{}:` prefix ({})", description, kind, level),
+        ),
+        _ => (
+            BumpLevel::Patch,
+            format!("'{}' has no bump signal (patch)", description),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+
+    fn task(description: &str, tags: &[&str]) -> Task {
+        Task::new(
+            description,
+            Priority::Medium,
+            tags.iter().map(|tag| tag.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn infer_bump_uses_largest_conventional_prefix() {
+        let tasks = vec![
+            task("fix: handle empty history", &[]),
+            task("feat: add bump flag", &[]),
+            task("update docs", &[]),
+        ];
+
+        let (level, reasons) = infer_bump(&tasks, &Semver::default());
+
+        assert_eq!(level, BumpLevel::Minor);
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].contains("feat:"));
+    }
+
+    #[test]
+    fn infer_bump_detects_breaking_changes_and_tags() {
+        let breaking = vec![task("feat(config)!: drop legacy keys", &[])];
+        assert_eq!(
+            infer_bump(&breaking, &Semver::default()).0,
+            BumpLevel::Major
+        );
+
+        let note = vec![task("BREAKING CHANGE: new TODO format", &[])];
+        assert_eq!(infer_bump(&note, &Semver::default()).0, BumpLevel::Major);
+
+        let tagged = vec![task("new export command", &["feature"])];
+        let (level, reasons) = infer_bump(&tagged, &Semver::default());
+        assert_eq!(level, BumpLevel::Minor);
+        assert!(reasons[0].contains("#feature"));
+    }
+
+    #[test]
+    fn infer_bump_defaults_to_patch() {
+        let tasks = vec![task("tidy parser", &[])];
+
+        assert_eq!(infer_bump(&tasks, &Semver::default()).0, BumpLevel::Patch);
+        assert_eq!(infer_bump(&[], &Semver::default()).0, BumpLevel::Patch);
+    }
+
+    #[test]
+    fn release_groups_roll_subtasks_into_released_parents() {
+        let mut tasks = vec![
+            task("parser", &[]),
+            task("tokenizer", &[]),
+            task("grammar", &[]),
+            task("precedence", &[]),
+            task("docs", &[]),
+            task("docs outline", &[]),
+        ];
+        for (child, parent) in [(1, 0), (2, 0), (3, 2), (5, 4)] {
+            tasks[child].parent = Some(tasks[parent].id.clone());
+        }
+        for done in [0, 1, 3, 5] {
+            tasks[done].status = TaskStatus::Done;
+        }
+
+        // "grammar" is open, so "parser" and its finished subtasks wait;
+        // "docs" is open, so its finished subtask waits too.
+        assert_eq!(release_groups(&tasks), vec![]);
+
+        tasks[2].status = TaskStatus::Done;
+        assert_eq!(release_groups(&tasks), vec![(0, vec![1, 2, 3])]);
+
+        // Finished work under a cancelled task is released on its own.
+        tasks[4].status = TaskStatus::Cancelled;
+        assert_eq!(
+            release_groups(&tasks),
+            vec![(0, vec![1, 2, 3]), (5, vec![])]
+        );
+    }
+}
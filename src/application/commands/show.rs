@@ -0,0 +1,201 @@
+use crate::models::changes::Change;
+use crate::models::common::{Priority, TaskStatus, Version};
+use crate::models::tasks::Task;
+use crate::output;
+use crate::services::storage::changelog_storage::ChangelogStorage;
+use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::matching::{MatchCandidate, requested_id, select_unambiguous, tag_query_score};
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::{Result, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use std::fmt::Write as _;
+
+pub fn cmd_show(description: String, id: Option<String>, json: bool) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let storage = ListStorage::for_reading(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let changelog =
+        ChangelogStorage::for_reading(&paths, storage.project_name(), &config.changelog)?;
+
+    let requested = requested_id(&description, id.as_deref(), |id| {
+        storage.find_task_by_id(id).is_some() || changelog.find_change_by_id(id).is_some()
+    });
+    if let Some(id) = requested {
+        if let Some(index) = storage.find_task_by_id(&id) {
+            return show_task(&storage.tasks()[index], json);
+        }
+        if let Some((version, change)) = changelog.find_change_by_id(&id) {
+            return show_change(&version, &change, json);
+        }
+
+        bail!("No task found with ID '{}'", id);
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut candidates = Vec::new();
+    for (i, task) in storage.tasks().iter().enumerate() {
+        let exact = task.description.eq_ignore_ascii_case(&description);
+        if let Some(score) = matcher
+            .fuzzy_match(&task.description, &description)
+            .or(exact.then_some(i64::MAX))
+            .or_else(|| {
+                tag_query_score(&description, &task.tags, |word| {
+                    matcher.fuzzy_match(&task.description, word)
+                })
+            })
+        {
+            candidates.push(MatchCandidate {
+                value: i,
+                score,
+                label: task.description.clone(),
+                exact,
+            });
+        }
+    }
+
+    if let Some(best_match) =
+        select_unambiguous(candidates, config.matching.task_min_score, &description)?
+    {
+        return show_task(&storage.tasks()[best_match.value], json);
+    }
+
+    // Released entries are searched only when no task matches, so an open
+    // task is never ambiguous with an earlier release of the same work.
+    match changelog.find_change(&description, None, None, config.matching.released_min_score)? {
+        Some((version, change)) => show_change(&version, &change, json),
+        None => bail!("No matching task found for: '{}'", description),
+    }
+}
+
+fn show_task(task: &Task, json: bool) -> Result<()> {
+    if json {
+        return output::print_json(&serde_json::json!({
+            "source": "todo",
+            "task": task,
+        }));
+    }
+
+    let mut output = String::new();
+    let checkbox = task.status.checkbox();
+    writeln!(
+        output,
+        "{} [{}] {}{}{}",
+        task.id,
+        checkbox,
+        task.description,
+        priority_suffix(task.priority),
+        tags_suffix(&task.tags)
+    )?;
+    writeln!(
+        output,
+        "  created: {}",
+        task.created_at_time.format("%Y-%m-%d %H:%M")
+    )?;
+    if let Some(reason) = &task.blocked_reason {
+        writeln!(output, "  blocked: {}", reason)?;
+    }
+    if let Some(recur) = &task.recur {
+        writeln!(output, "  recurs: {}", recur)?;
+    }
+    if let Some(version) = &task.created_at_version {
+        writeln!(output, "  created version: {}", version)?;
+    }
+    if let Some(commit) = &task.created_at_commit {
+        writeln!(output, "  created commit: {}", commit)?;
+    }
+    if let Some(author) = &task.created_by {
+        writeln!(output, "  created by: {}", author)?;
+    }
+    if let Some(completed) = &task.completed_at_time {
+        writeln!(
+            output,
+            "  {}: {}",
+            if task.status == TaskStatus::Cancelled {
+                "cancelled"
+            } else {
+                "completed"
+            },
+            completed.format("%Y-%m-%d %H:%M")
+        )?;
+    }
+    if let Some(version) = &task.completed_at_version {
+        writeln!(output, "  completed version: {}", version)?;
+    }
+    if let Some(commit) = &task.completed_at_commit {
+        writeln!(output, "  completed commit: {}", commit)?;
+    }
+    for (key, value) in task.meta.iter() {
+        writeln!(output, "  {}: {}", key, value)?;
+    }
+    write_notes(&mut output, task.notes.as_deref())?;
+    output::page_text(None, &output)
+}
+
+fn show_change(version: &Version, change: &Change, json: bool) -> Result<()> {
+    if json {
+        return output::print_json(&serde_json::json!({
+            "source": "changelog",
+            "version": version,
+            "change": change,
+        }));
+    }
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "{} [x] {}{}{}",
+        change.id.as_deref().unwrap_or("-"),
+        change.description,
+        priority_suffix(change.priority),
+        tags_suffix(&change.tags)
+    )?;
+    writeln!(output, "  released: {}", version)?;
+    if let Some(commit) = &change.commit {
+        writeln!(output, "  commit: {}", commit)?;
+    }
+    for (key, value) in change.meta.iter() {
+        writeln!(output, "  {}: {}", key, value)?;
+    }
+    write_notes(&mut output, change.notes.as_deref())?;
+    output::page_text(None, &output)
+}
+
+fn write_notes(output: &mut String, notes: Option<&str>) -> Result<()> {
+    if let Some(notes) = notes {
+        writeln!(output)?;
+        for line in notes.lines() {
+            if line.trim().is_empty() {
+                writeln!(output)?;
+            } else {
+                writeln!(output, "  {}", line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn priority_suffix(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => " (high)",
+        Priority::Medium => "",
+        Priority::Low => " (low)",
+    }
+}
+
+fn tags_suffix(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " {}",
+            tags.iter()
+                .map(|t| format!("#{}", t))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    }
+}
@@ -0,0 +1,178 @@
+use crate::models::common::TaskStatus;
+use crate::models::tasks::{Task, tree};
+use crate::output;
+use crate::services::git;
+use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::matching::{
+    MatchCandidate, id_match, requested_id, score_percent, select_unambiguous, tag_query_score,
+};
+use crate::utils::project_paths::ProjectPaths;
+use anyhow::{Result, anyhow, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+/// Move a task to in-progress, blocked or cancelled. A cancelled task can be
+/// picked again by ID to reopen it.
+pub fn cmd_set_status(
+    description: String,
+    id: Option<String>,
+    status: TaskStatus,
+    reason: Option<String>,
+    dry_run: bool,
+    auto: bool,
+    json: bool,
+) -> Result<()> {
+    let paths = ProjectPaths::get_paths()?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
+    let config = config_storage.get_config();
+
+    let tasks = storage.tasks();
+    let selection = match requested_id(&description, id.as_deref(), |id| {
+        storage.find_task_by_id(id).is_some()
+    }) {
+        Some(id) => {
+            let index = storage
+                .find_task_by_id(&id)
+                .ok_or_else(|| anyhow!("No task found with ID '{}'", id))?;
+            Some(id_match(index, tasks[index].description.clone()))
+        }
+        None => {
+            let matcher = SkimMatcherV2::default();
+            let mut candidates = Vec::new();
+
+            for (i, task) in tasks.iter().enumerate() {
+                if task.is_closed() {
+                    continue;
+                }
+
+                let exact = task.description.eq_ignore_ascii_case(&description);
+                if let Some(score) = matcher
+                    .fuzzy_match(&task.description, &description)
+                    .or(exact.then_some(i64::MAX))
+                    .or_else(|| {
+                        tag_query_score(&description, &task.tags, |word| {
+                            matcher.fuzzy_match(&task.description, word)
+                        })
+                    })
+                {
+                    candidates.push(MatchCandidate {
+                        value: i,
+                        score,
+                        label: task.description.clone(),
+                        exact,
+                    });
+                }
+            }
+
+            select_unambiguous(candidates, config.matching.task_min_score, &description)?
+        }
+    };
+
+    let Some(best_match) = selection else {
+        bail!("No matching task found for: '{}'", description);
+    };
+    let index = best_match.value;
+    let score_pct = score_percent(best_match.score);
+    let task = &tasks[index];
+
+    if task.is_done() {
+        bail!("Task {} is already done", task.id);
+    }
+    if task.status == status && task.blocked_reason == reason {
+        bail!("Task {} is already {}", task.id, status);
+    }
+
+    let open_subtasks: Vec<Task> = tree::descendants(tasks, index)
+        .into_iter()
+        .map(|i| tasks[i].clone())
+        .filter(|subtask| !subtask.is_closed())
+        .collect();
+    let warn_subtasks = status == TaskStatus::Cancelled && !open_subtasks.is_empty();
+
+    if dry_run {
+        let mut preview = task.clone();
+        preview.status = status;
+        preview.blocked_reason = reason;
+        if json {
+            return output::print_json(&serde_json::json!({
+                "status": "would_update",
+                "dry_run": true,
+                "match_score": score_pct,
+                "task": preview,
+                "open_subtasks": open_subtasks,
+            }));
+        }
+        println!("Would mark as {} (score: {:.0}%):", status, score_pct);
+        print_task(&preview);
+        if warn_subtasks {
+            warn_open_subtasks(&open_subtasks);
+        }
+        return Ok(());
+    }
+
+    storage.set_status(index, status, reason)?;
+    let updated = storage.tasks()[index].clone();
+
+    if auto || config.auto_commit_edit() {
+        let message = format!("update TODO: {} task", verb(status));
+        if json {
+            git::commit_tally_files_quiet(&message)?;
+        } else {
+            git::commit_tally_files(&message)?;
+        }
+    }
+
+    if json {
+        output::print_json(&serde_json::json!({
+            "status": "updated",
+            "dry_run": false,
+            "match_score": score_pct,
+            "task": updated,
+            "open_subtasks": open_subtasks,
+        }))?;
+    } else {
+        println!("Marked as {}:", status);
+        print_task(&updated);
+        if warn_subtasks {
+            warn_open_subtasks(&open_subtasks);
+        }
+    }
+
+    Ok(())
+}
+
+fn verb(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Open => "reopen",
+        TaskStatus::InProgress => "start",
+        TaskStatus::Blocked => "block",
+        TaskStatus::Done => "complete",
+        TaskStatus::Cancelled => "cancel",
+    }
+}
+
+fn print_task(task: &Task) {
+    println!(
+        "  [{}] {} {}",
+        task.status.checkbox(),
+        task.id,
+        task.description
+    );
+    if let Some(reason) = &task.blocked_reason {
+        println!("      @blocked {}", reason);
+    }
+}
+
+fn warn_open_subtasks(open_subtasks: &[Task]) {
+    println!("Warning: {} open subtask(s) remain:", open_subtasks.len());
+    for subtask in open_subtasks {
+        println!(
+            "  [{}] {} {}",
+            subtask.status.checkbox(),
+            subtask.id,
+            subtask.description
+        );
+    }
+}
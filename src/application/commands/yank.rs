@@ -1,34 +1,48 @@
 use crate::models::changes::Change;
-use crate::models::tasks::Task;
+use crate::models::common::TaskStatus;
+use crate::models::tasks::{Task, task};
 use crate::output;
 use crate::services::git;
 use crate::services::storage::changelog_storage::ChangelogStorage;
 use crate::services::storage::config_storage::ConfigStorage;
 use crate::services::storage::task_storage::ListStorage;
+use crate::utils::matching::requested_id;
 use crate::utils::project_paths::ProjectPaths;
 use anyhow::Result;
 use chrono::Utc;
 
 pub fn cmd_yank(
     description: String,
+    id: Option<String>,
     tags: Option<Vec<String>>,
     dry_run: bool,
     auto: bool,
     json: bool,
 ) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let mut storage = ListStorage::new(&paths.todo_file)?;
-    let mut changelog = ChangelogStorage::new(&paths.changelog_file, storage.project_name())?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let mut storage = ListStorage::for_project(&paths)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
+    let mut changelog =
+        ChangelogStorage::for_project(&paths, storage.project_name(), &config.changelog)?;
 
-    if dry_run {
-        let changes = changelog.remove_changes(
+    let requested = requested_id(&description, id.as_deref(), |id| {
+        changelog.find_change_by_id(id).is_some()
+    });
+    let changes = match requested {
+        Some(ref id) => changelog
+            .remove_change_by_id(id, None)
+            .into_iter()
+            .collect(),
+        None => changelog.remove_changes(
             &description,
             None,
             tags.as_deref(),
             config.matching.released_min_score,
-        )?;
+        )?,
+    };
+
+    if dry_run {
         if json {
             return output::print_json(&serde_json::json!({
                 "status": if changes.is_empty() { "not_found" } else { "would_yank" },
@@ -57,20 +71,15 @@ pub fn cmd_yank(
         return Ok(());
     }
 
-    let changes = changelog.remove_changes(
-        &description,
-        None,
-        tags.as_deref(),
-        config.matching.released_min_score,
-    )?;
-
     if !changes.is_empty() {
         let tasks: Vec<Task> = changes
             .iter()
-            .map(|(_, change)| task_from_change(change))
+            .flat_map(|(_, change)| tasks_from_change(change))
             .collect();
-        storage.add_tasks(tasks.clone())?;
-        changelog.save()?;
+        for task in &tasks {
+            storage.list_mut().add_task(task.clone());
+        }
+        changelog.save_with_list(&mut storage)?;
 
         if auto || config.auto_commit_yank() {
             if json {
@@ -102,20 +111,48 @@ pub fn cmd_yank(
         }
 
         Ok(())
+    } else if let Some(id) = requested {
+        anyhow::bail!("No released task found with ID '{}'", id)
     } else {
         anyhow::bail!("No matching released task found.")
     }
 }
 
-fn task_from_change(change: &Change) -> Task {
+/// The released task followed by its subtasks, all completed.
+fn tasks_from_change(change: &Change) -> Vec<Task> {
+    let parent = task_from_change(change);
+    let subtasks = change.subtasks.iter().map(|subtask| {
+        let mut subtask = task_from_change(subtask);
+        subtask.parent = Some(parent.id.clone());
+        subtask
+    });
+
+    std::iter::once(parent.clone()).chain(subtasks).collect()
+}
+
+pub(crate) fn task_from_change(change: &Change) -> Task {
     Task {
+        id: change
+            .id
+            .clone()
+            .unwrap_or_else(|| task::derive_id(&change.description, &change.completed_at)),
         description: change.description.clone(),
         priority: change.priority,
         tags: change.tags.clone(),
-        completed: true,
+        status: TaskStatus::Done,
+        blocked_reason: None,
+        parent: None,
+        depends: Vec::new(),
+        due: None,
+        scheduled: None,
+        recur: None,
+        recurred: None,
+        meta: change.meta.clone(),
+        notes: change.notes.clone(),
         created_at_time: Utc::now(),
         created_at_version: None,
         created_at_commit: None,
+        created_by: None,
         completed_at_time: Some(change.completed_at),
         completed_at_version: None,
         completed_at_commit: change.commit.clone(),
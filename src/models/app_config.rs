@@ -1,3 +1,4 @@
+use crate::models::common::BumpLevel;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -12,9 +13,15 @@ pub struct AppConfig {
     pub scan: Scan,
     #[serde(default)]
     pub matching: Matching,
+    #[serde(default)]
+    pub semver: Semver,
+    #[serde(default)]
+    pub changelog: Changelog,
+    #[serde(default)]
+    pub files: Files,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preferences {
     #[serde(default)]
     pub auto_commit_todo: bool,
@@ -22,6 +29,20 @@ pub struct Preferences {
     pub auto_complete_tasks: bool,
     #[serde(default)]
     pub editor: Option<String>,
+    /// Record the commit, version and author new tasks were created at.
+    #[serde(default = "default_record_origin")]
+    pub record_origin: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            auto_commit_todo: false,
+            auto_complete_tasks: false,
+            editor: None,
+            record_origin: default_record_origin(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +78,8 @@ pub struct AutoCommit {
     #[serde(default)]
     pub done: bool,
     #[serde(default)]
+    pub edit: bool,
+    #[serde(default)]
     pub remove: bool,
     #[serde(default)]
     pub semver: bool,
@@ -104,6 +127,131 @@ impl Default for Matching {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Semver {
+    /// Identifier used when `--bump prerelease` starts a new prerelease.
+    #[serde(default = "default_prerelease_label")]
+    pub prerelease_label: String,
+    /// Tags that make `--infer` choose a major bump.
+    #[serde(default = "default_major_tags")]
+    pub major_tags: Vec<String>,
+    /// Tags that make `--infer` choose a minor bump.
+    #[serde(default = "default_minor_tags")]
+    pub minor_tags: Vec<String>,
+    /// Tags that make `--infer` choose a patch bump.
+    #[serde(default = "default_patch_tags")]
+    pub patch_tags: Vec<String>,
+    /// What a release does with cancelled tasks.
+    #[serde(default)]
+    pub cancelled: CancelledTasks,
+    /// Copy custom `@key value` task metadata into CHANGELOG.md entries.
+    #[serde(default)]
+    pub keep_meta: bool,
+}
+
+impl Default for Semver {
+    fn default() -> Self {
+        Self {
+            prerelease_label: default_prerelease_label(),
+            major_tags: default_major_tags(),
+            minor_tags: default_minor_tags(),
+            patch_tags: default_patch_tags(),
+            cancelled: CancelledTasks::default(),
+            keep_meta: false,
+        }
+    }
+}
+
+impl Semver {
+    /// The bump level configured for a tag, preferring the largest match.
+    pub fn bump_for_tag(&self, tag: &str) -> Option<BumpLevel> {
+        [
+            (BumpLevel::Major, &self.major_tags),
+            (BumpLevel::Minor, &self.minor_tags),
+            (BumpLevel::Patch, &self.patch_tags),
+        ]
+        .into_iter()
+        .find(|(_, tags)| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .map(|(level, _)| level)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Changelog {
+    #[serde(default)]
+    pub style: ChangelogStyle,
+    /// How tally-style releases are split into sections. Keep a Changelog
+    /// always uses its own sections.
+    #[serde(default)]
+    pub grouping: ChangelogGrouping,
+    /// Repository web URL used for Keep a Changelog compare links.
+    /// Defaults to the `origin` remote when unset.
+    #[serde(default)]
+    pub compare_url: Option<String>,
+    /// Prefix that turns a version into its git tag in compare links.
+    #[serde(default = "default_tag_prefix")]
+    pub tag_prefix: String,
+}
+
+impl Default for Changelog {
+    fn default() -> Self {
+        Self {
+            style: ChangelogStyle::default(),
+            grouping: ChangelogGrouping::default(),
+            compare_url: None,
+            tag_prefix: default_tag_prefix(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelledTasks {
+    /// Leave cancelled tasks in TODO.md.
+    #[default]
+    Skip,
+    /// Move cancelled tasks under a Cancelled section of the release.
+    Record,
+}
+
+/// Where tally keeps its task files, relative to the project root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Files {
+    #[serde(default = "default_todo_file")]
+    pub todo: String,
+    #[serde(default = "default_changelog_file")]
+    pub changelog: String,
+}
+
+impl Default for Files {
+    fn default() -> Self {
+        Self {
+            todo: default_todo_file(),
+            changelog: default_changelog_file(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangelogStyle {
+    /// `## 1.2.3 — date` with High Priority / Changes / Minor Changes sections.
+    #[default]
+    Tally,
+    /// keepachangelog.com: `## [1.2.3] - date`, typed sections and compare links.
+    KeepAChangelog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangelogGrouping {
+    /// High Priority / Changes / Minor Changes.
+    #[default]
+    Priority,
+    /// Breaking Changes, Features, Bug Fixes, ... by conventional-commit type.
+    Conventional,
+}
+
 impl AppConfig {
     pub fn auto_commit_add(&self) -> bool {
         self.preferences.auto_commit_todo || self.auto_commit.add
@@ -113,6 +261,10 @@ impl AppConfig {
         self.preferences.auto_commit_todo || self.auto_commit.done
     }
 
+    pub fn auto_commit_edit(&self) -> bool {
+        self.preferences.auto_commit_todo || self.auto_commit.edit
+    }
+
     pub fn auto_commit_remove(&self) -> bool {
         self.preferences.auto_commit_todo || self.auto_commit.remove
     }
@@ -126,6 +278,10 @@ impl AppConfig {
     }
 }
 
+fn default_record_origin() -> bool {
+    true
+}
+
 fn default_done_prefix() -> String {
     "done:".to_string()
 }
@@ -145,3 +301,31 @@ fn default_done_markers() -> Vec<String> {
 fn default_min_score() -> f64 {
     50.0
 }
+
+fn default_prerelease_label() -> String {
+    "rc".to_string()
+}
+
+fn default_major_tags() -> Vec<String> {
+    vec!["breaking".to_string()]
+}
+
+fn default_minor_tags() -> Vec<String> {
+    vec!["feature".to_string()]
+}
+
+fn default_patch_tags() -> Vec<String> {
+    vec!["bug".to_string(), "fix".to_string()]
+}
+
+fn default_tag_prefix() -> String {
+    "v".to_string()
+}
+
+fn default_todo_file() -> String {
+    "TODO.md".to_string()
+}
+
+fn default_changelog_file() -> String {
+    "CHANGELOG.md".to_string()
+}
@@ -1,24 +1,42 @@
-use crate::models::{common::Priority, tasks::Task};
+use crate::models::{
+    common::Priority,
+    tasks::{CustomMeta, Task},
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Change {
+    pub id: Option<String>,
     pub description: String,
     pub priority: Priority,
     pub tags: Vec<String>,
     pub commit: Option<String>,
     pub completed_at: DateTime<Utc>,
+    /// Completed subtasks released with this change, with their own ID,
+    /// priority, tags and metadata.
+    #[serde(default)]
+    pub subtasks: Vec<Change>,
+    /// Notes carried over from the task, shown below the bullet.
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Custom task metadata, kept when `semver.keep_meta` is set.
+    #[serde(default)]
+    pub meta: CustomMeta,
 }
 
 impl From<&Task> for Change {
     fn from(task: &Task) -> Self {
         Self {
+            id: Some(task.id.clone()),
             description: task.description.clone(),
             priority: task.priority,
             tags: task.tags.clone(),
             commit: task.completed_at_commit.clone(),
             completed_at: task.completed_at_time.unwrap_or_else(Utc::now),
+            subtasks: Vec::new(),
+            notes: task.notes.clone(),
+            meta: task.meta.clone(),
         }
     }
 }
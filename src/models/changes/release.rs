@@ -12,6 +12,9 @@ pub struct Release {
     pub date: DateTime<Utc>,
     pub changes_by_priority: BTreeMap<Priority, Vec<Change>>,
     pub changes_by_tag: BTreeMap<String, Vec<Change>>,
+    /// Tasks cancelled before this release, recorded apart from its changes.
+    #[serde(default)]
+    pub cancelled: Vec<Change>,
 }
 
 impl Release {
@@ -39,6 +42,21 @@ impl Release {
             date,
             changes_by_priority,
             changes_by_tag,
+            cancelled: Vec::new(),
         }
     }
+
+    /// All changes, highest priority first.
+    pub fn changes(&self) -> Vec<Change> {
+        [Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .filter_map(|priority| self.changes_by_priority.get(&priority))
+            .flat_map(|changes| changes.iter().cloned())
+            .collect()
+    }
+
+    pub fn with_cancelled(mut self, cancelled: Vec<Change>) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
 }
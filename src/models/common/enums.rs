@@ -32,3 +32,89 @@ impl Display for Priority {
         }
     }
 }
+
+/// How far `tally semver` moves the version, ordered from smallest to largest bump.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BumpLevel {
+    Prerelease,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Display for BumpLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BumpLevel::Prerelease => write!(f, "prerelease"),
+            BumpLevel::Patch => write!(f, "patch"),
+            BumpLevel::Minor => write!(f, "minor"),
+            BumpLevel::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// Where a task is in its lifecycle. Each state has its own TODO.md checkbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum, Default, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[default]
+    Open,
+    InProgress,
+    Blocked,
+    Done,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// The character between the brackets of this state's checkbox.
+    pub fn checkbox(self) -> char {
+        match self {
+            TaskStatus::Open => ' ',
+            TaskStatus::InProgress => '/',
+            TaskStatus::Blocked => '~',
+            TaskStatus::Done => 'x',
+            TaskStatus::Cancelled => '-',
+        }
+    }
+
+    pub fn from_checkbox(mark: char) -> Option<Self> {
+        match mark {
+            ' ' => Some(TaskStatus::Open),
+            '/' => Some(TaskStatus::InProgress),
+            '~' => Some(TaskStatus::Blocked),
+            'x' | 'X' => Some(TaskStatus::Done),
+            '-' => Some(TaskStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Done and cancelled tasks need no more work.
+    pub fn is_closed(self) -> bool {
+        matches!(self, TaskStatus::Done | TaskStatus::Cancelled)
+    }
+}
+
+impl Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskStatus::Open => write!(f, "open"),
+            TaskStatus::InProgress => write!(f, "in progress"),
+            TaskStatus::Blocked => write!(f, "blocked"),
+            TaskStatus::Done => write!(f, "done"),
+            TaskStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Output format for `tally graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    #[default]
+    Dot,
+    /// Mermaid flowchart, for Markdown that renders it.
+    Mermaid,
+}
@@ -1,5 +1,5 @@
 pub mod enums;
 pub mod version;
 
-pub use enums::Priority;
+pub use enums::{BumpLevel, GraphFormat, Priority, TaskStatus};
 pub use version::Version;
@@ -1,25 +1,38 @@
+use crate::models::common::BumpLevel;
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A SemVer 2.0 version with optional prerelease identifiers and build metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Version {
     pub major: u32,
     pub minor: u32,
     pub patch: u32,
-    pub is_prerelease: bool,
+    /// Dot-separated prerelease identifiers, e.g. `rc.1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<String>,
+    /// Dot-separated build metadata, e.g. `build.5`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<String>,
 }
 
 impl Version {
-    pub fn new(major: u32, minor: u32, patch: u32, is_prerelease: bool) -> Self {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
         Self {
             major,
             minor,
             patch,
-            is_prerelease,
+            pre: None,
+            build: None,
         }
     }
 
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
     pub fn parse(s: &str) -> Result<Self> {
         let s = s.trim();
         if s.is_empty() {
@@ -31,7 +44,16 @@ impl Version {
             .or_else(|| s.strip_prefix('V'))
             .unwrap_or(s);
 
-        let parts: Vec<&str> = s.split('.').collect();
+        let (s, build) = match s.split_once('+') {
+            Some((rest, build)) => (rest, Some(parse_identifiers(build, "build metadata")?)),
+            None => (s, None),
+        };
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(parse_prerelease(pre)?)),
+            None => (s, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
         if parts.is_empty() || parts.len() > 3 {
             bail!("Invalid version format");
         }
@@ -54,45 +76,151 @@ impl Version {
             0
         };
 
-        Ok(Version::new(major, minor, patch, false))
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    /// Return the next version for `level`, dropping any build metadata.
+    ///
+    /// A prerelease is finalized by the bump it precedes (`1.0.0-rc.2` bumped
+    /// as major becomes `1.0.0`), and a prerelease bump increments the last
+    /// numeric identifier or starts `<label>.1` on the next patch.
+    pub fn bump(&self, level: BumpLevel, prerelease_label: &str) -> Version {
+        let (major, minor, patch) = (self.major, self.minor, self.patch);
+        let pending = self.is_prerelease();
+
+        match level {
+            BumpLevel::Major if pending && minor == 0 && patch == 0 => Version::new(major, 0, 0),
+            BumpLevel::Major => Version::new(major + 1, 0, 0),
+            BumpLevel::Minor if pending && patch == 0 => Version::new(major, minor, 0),
+            BumpLevel::Minor => Version::new(major, minor + 1, 0),
+            BumpLevel::Patch if pending => Version::new(major, minor, patch),
+            BumpLevel::Patch => Version::new(major, minor, patch + 1),
+            BumpLevel::Prerelease => {
+                let pre = match &self.pre {
+                    Some(pre) => next_prerelease(pre),
+                    None => format!("{prerelease_label}.1"),
+                };
+                let mut next = if pending {
+                    Version::new(major, minor, patch)
+                } else {
+                    Version::new(major, minor, patch + 1)
+                };
+                next.pre = Some(pre);
+                next
+            }
+        }
+    }
+
+    /// Compare by SemVer precedence, which ignores build metadata.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => cmp_prerelease(a, b),
+            })
     }
 }
 
-impl fmt::Display for Version {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.is_prerelease {
-            write!(f, "{}.{}.{}-pre", self.major, self.minor, self.patch)
-        } else {
-            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+fn parse_identifiers(value: &str, kind: &str) -> Result<String> {
+    for identifier in value.split('.') {
+        if identifier.is_empty() {
+            bail!("Invalid {kind}: empty identifier in '{value}'");
+        }
+        if !identifier
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+        {
+            bail!("Invalid {kind}: '{identifier}' contains characters outside [0-9A-Za-z-]");
         }
     }
+    Ok(value.to_string())
 }
 
-impl PartialOrd for Version {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(std::cmp::Ord::cmp(self, other))
+fn parse_prerelease(value: &str) -> Result<String> {
+    let value = parse_identifiers(value, "prerelease")?;
+    for identifier in value.split('.') {
+        if identifier.len() > 1
+            && identifier.starts_with('0')
+            && identifier.chars().all(|ch| ch.is_ascii_digit())
+        {
+            bail!("Invalid prerelease: numeric identifier '{identifier}' has a leading zero");
+        }
     }
+    Ok(value)
 }
 
-impl Ord for Version {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.major.cmp(&other.major) {
-            std::cmp::Ordering::Equal => {}
-            ord => return ord,
+fn next_prerelease(pre: &str) -> String {
+    let mut identifiers: Vec<String> = pre.split('.').map(str::to_string).collect();
+    match identifiers.last().map(|last| last.parse::<u64>()) {
+        Some(Ok(number)) => {
+            let last = identifiers.len() - 1;
+            identifiers[last] = (number + 1).to_string();
         }
-        match self.minor.cmp(&other.minor) {
-            std::cmp::Ordering::Equal => {}
-            ord => return ord,
+        _ => identifiers.push("1".to_string()),
+    }
+    identifiers.join(".")
+}
+
+fn cmp_prerelease(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
         }
-        match self.patch.cmp(&other.patch) {
-            std::cmp::Ordering::Equal => {}
-            ord => return ord,
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
         }
-        match (self.is_prerelease, other.is_prerelease) {
-            (false, true) => std::cmp::Ordering::Greater,
-            (true, false) => std::cmp::Ordering::Less,
-            _ => std::cmp::Ordering::Equal,
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
         }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(Ord::cmp(self, other))
+    }
+}
+
+impl Ord for Version {
+    /// SemVer precedence, with build metadata as a final tie-breaker so the
+    /// ordering stays consistent with `Eq` for use as a map key.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
     }
 }
 
@@ -120,7 +248,18 @@ mod tests {
 
     #[test]
     fn parse_rejects_invalid_inputs() {
-        for input in ["", "1.2.3.4", "x.2.3", "1.x.3", "1.2.x"] {
+        for input in [
+            "",
+            "1.2.3.4",
+            "x.2.3",
+            "1.x.3",
+            "1.2.x",
+            "1.2.3-",
+            "1.2.3-rc..1",
+            "1.2.3-rc.01",
+            "1.2.3+",
+            "1.2.3-rc_1",
+        ] {
             assert!(
                 Version::parse(input).is_err(),
                 "expected parse failure for '{input}'"
@@ -128,13 +267,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_and_display_round_trip_prerelease_and_build() {
+        for input in [
+            "1.2.3-rc.1",
+            "1.2.3+build.5",
+            "1.2.3-alpha.2+sha.0abc",
+            "1.0.0-x-y",
+        ] {
+            let version = Version::parse(input).unwrap();
+            assert_eq!(version.to_string(), input);
+        }
+
+        let version = Version::parse("v1.2.3-rc.1+build.5").unwrap();
+        assert_eq!(version.pre.as_deref(), Some("rc.1"));
+        assert_eq!(version.build.as_deref(), Some("build.5"));
+        assert!(version.is_prerelease());
+    }
+
     #[test]
     fn comparison_handles_prerelease_and_numeric_ordering() {
-        let prerelease = Version::new(1, 2, 3, true);
-        let stable = Version::new(1, 2, 3, false);
-        let newer_patch = Version::new(1, 2, 4, false);
+        let prerelease = Version::parse("1.2.3-pre").unwrap();
+        let stable = Version::new(1, 2, 3);
+        let newer_patch = Version::new(1, 2, 4);
 
         assert!(stable > prerelease);
         assert!(newer_patch > stable);
     }
+
+    #[test]
+    fn comparison_follows_semver_precedence_examples() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+
+        for pair in ordered.windows(2) {
+            let lower = Version::parse(pair[0]).unwrap();
+            let higher = Version::parse(pair[1]).unwrap();
+            assert!(lower < higher, "expected {} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn bump_increments_requested_component() {
+        let base = Version::parse("1.2.3+build.9").unwrap();
+
+        assert_eq!(base.bump(BumpLevel::Major, "rc").to_string(), "2.0.0");
+        assert_eq!(base.bump(BumpLevel::Minor, "rc").to_string(), "1.3.0");
+        assert_eq!(base.bump(BumpLevel::Patch, "rc").to_string(), "1.2.4");
+        assert_eq!(
+            base.bump(BumpLevel::Prerelease, "rc").to_string(),
+            "1.2.4-rc.1"
+        );
+    }
+
+    #[test]
+    fn bump_finalizes_or_advances_prereleases() {
+        let rc = Version::parse("2.0.0-rc.2").unwrap();
+        assert_eq!(rc.bump(BumpLevel::Major, "rc").to_string(), "2.0.0");
+        assert_eq!(rc.bump(BumpLevel::Patch, "rc").to_string(), "2.0.0");
+        assert_eq!(
+            rc.bump(BumpLevel::Prerelease, "rc").to_string(),
+            "2.0.0-rc.3"
+        );
+
+        let alpha = Version::parse("1.4.1-alpha").unwrap();
+        assert_eq!(alpha.bump(BumpLevel::Minor, "rc").to_string(), "1.5.0");
+        assert_eq!(
+            alpha.bump(BumpLevel::Prerelease, "rc").to_string(),
+            "1.4.1-alpha.1"
+        );
+    }
+
+    #[test]
+    fn build_metadata_does_not_affect_precedence() {
+        let plain = Version::parse("1.0.0").unwrap();
+        let built = Version::parse("1.0.0+build.7").unwrap();
+
+        assert_eq!(plain.cmp_precedence(&built), Ordering::Equal);
+        assert_ne!(plain, built);
+    }
 }
@@ -0,0 +1,36 @@
+use crate::models::history::JournalEntry;
+use serde::{Deserialize, Serialize};
+
+/// Recorded commands in order; entries at or after `position` have been undone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+    pub position: usize,
+}
+
+impl Journal {
+    /// Append an entry, dropping anything that could still be redone.
+    pub fn push(&mut self, entry: JournalEntry, max_entries: usize) {
+        self.entries.truncate(self.position);
+        self.entries.push(entry);
+        if self.entries.len() > max_entries {
+            let excess = self.entries.len() - max_entries;
+            self.entries.drain(..excess);
+        }
+        self.position = self.entries.len();
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.entries.last().map_or(1, |entry| entry.id + 1)
+    }
+
+    /// The entry `undo` would revert.
+    pub fn undo_target(&self) -> Option<&JournalEntry> {
+        self.position.checked_sub(1).map(|i| &self.entries[i])
+    }
+
+    /// The entry `redo` would reapply.
+    pub fn redo_target(&self) -> Option<&JournalEntry> {
+        self.entries.get(self.position)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod entry;
+pub mod journal;
+
+pub use entry::{FileChange, JournalEntry};
+pub use journal::Journal;
@@ -0,0 +1,340 @@
+use crate::models::changes::{Change, Release};
+use crate::models::common::Version;
+use crate::models::tasks::Task;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// An item that both sides changed in ways that cannot be combined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub key: String,
+    pub label: String,
+    /// One line per conflicting field, e.g. `due: ours 2026-10-20, theirs 2026-10-22`.
+    pub details: Vec<String>,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.label, self.key)?;
+        for detail in &self.details {
+            write!(f, "\n    {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Three-way merge of two edited copies of `base`, matching items by `key`.
+///
+/// Items added or removed on one side are added or removed. An item changed
+/// on both sides is merged field by field; it conflicts only when both sides
+/// set the same field to different values, or one side removed it while the
+/// other changed it. Items keep `ours` order, with additions from `theirs`
+/// appended.
+pub fn merge_by_key<T>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    key: impl Fn(&T) -> String,
+    label: impl Fn(&T) -> String,
+) -> Result<Vec<T>, Vec<Conflict>>
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    let base_by_key: HashMap<String, &T> = base.iter().map(|item| (key(item), item)).collect();
+    let theirs_by_key: HashMap<String, &T> = theirs.iter().map(|item| (key(item), item)).collect();
+    let ours_keys: Vec<String> = ours.iter().map(&key).collect();
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+    let conflict = |item: &T, details: Vec<String>| Conflict {
+        key: key(item),
+        label: label(item),
+        details,
+    };
+
+    for (item, item_key) in ours.iter().zip(&ours_keys) {
+        let base_item = base_by_key.get(item_key).map(|item| to_value(*item));
+        let theirs_item = theirs_by_key.get(item_key).map(|item| to_value(*item));
+        let ours_item = to_value(item);
+
+        match (base_item, theirs_item) {
+            (Some(base_item), None) if base_item == ours_item => {}
+            (Some(_), None) => conflicts.push(conflict(
+                item,
+                vec!["removed in theirs, changed in ours".to_string()],
+            )),
+            (base_item, Some(theirs_item)) => {
+                let base_item = base_item.unwrap_or(Value::Object(Map::new()));
+                match merge_fields(&base_item, &ours_item, &theirs_item) {
+                    Ok(value) => match serde_json::from_value(value) {
+                        Ok(value) => merged.push(value),
+                        Err(e) => conflicts.push(conflict(item, vec![e.to_string()])),
+                    },
+                    Err(details) => conflicts.push(conflict(item, details)),
+                }
+            }
+            (None, None) => merged.push(item.clone()),
+        }
+    }
+
+    for item in theirs {
+        let item_key = key(item);
+        if ours_keys.contains(&item_key) {
+            continue;
+        }
+        match base_by_key.get(&item_key) {
+            None => merged.push(item.clone()),
+            Some(base_item) if to_value(*base_item) == to_value(item) => {}
+            Some(_) => conflicts.push(conflict(
+                item,
+                vec!["removed in ours, changed in theirs".to_string()],
+            )),
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(merged)
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Three-way merge of a task list, matching tasks by ID.
+pub fn merge_tasks(
+    base: &[Task],
+    ours: &[Task],
+    theirs: &[Task],
+) -> Result<Vec<Task>, Vec<Conflict>> {
+    merge_by_key(
+        base,
+        ours,
+        theirs,
+        |task| task.id.clone(),
+        |task| task.description.clone(),
+    )
+}
+
+/// Three-way merge of changelog releases, matching releases by version and
+/// their changes by ID, commit or description. Releases come out newest
+/// first; a release left without entries is dropped.
+pub fn merge_releases(
+    base: &[Release],
+    ours: &[Release],
+    theirs: &[Release],
+) -> Result<Vec<Release>, Vec<Conflict>> {
+    let find = |releases: &[Release], version: &Version| {
+        releases.iter().find(|r| &r.version == version).cloned()
+    };
+    let mut versions: Vec<Version> = ours
+        .iter()
+        .chain(theirs)
+        .map(|r| r.version.clone())
+        .collect();
+    versions.sort_by(|a, b| b.cmp(a));
+    versions.dedup();
+
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+    for version in versions {
+        let [base, ours, theirs] = [base, ours, theirs].map(|releases| find(releases, &version));
+        let entries = |release: &Option<Release>| {
+            release
+                .as_ref()
+                .map(|r| (r.changes(), r.cancelled.clone()))
+                .unwrap_or_default()
+        };
+        let (base_changes, base_cancelled) = entries(&base);
+        let (ours_changes, ours_cancelled) = entries(&ours);
+        let (theirs_changes, theirs_cancelled) = entries(&theirs);
+
+        let label = |change: &Change| format!("{} in {}", change.description, version);
+        let changes = merge_by_key(
+            &base_changes,
+            &ours_changes,
+            &theirs_changes,
+            change_key,
+            label,
+        );
+        let cancelled = merge_by_key(
+            &base_cancelled,
+            &ours_cancelled,
+            &theirs_cancelled,
+            change_key,
+            label,
+        );
+        let (changes, cancelled) = match (changes, cancelled) {
+            (Ok(changes), Ok(cancelled)) => (changes, cancelled),
+            (changes, cancelled) => {
+                conflicts.extend(changes.err().into_iter().flatten());
+                conflicts.extend(cancelled.err().into_iter().flatten());
+                continue;
+            }
+        };
+        if changes.is_empty() && cancelled.is_empty() {
+            continue;
+        }
+
+        let date = match (&base, &ours, &theirs) {
+            (Some(base), Some(ours), Some(theirs)) if ours.date == base.date => theirs.date,
+            (_, Some(ours), _) => ours.date,
+            (_, _, theirs) => theirs.as_ref().map(|r| r.date).unwrap_or_default(),
+        };
+        let refs: Vec<&Change> = changes.iter().collect();
+        merged.push(Release::from_changes(version, date, refs).with_cancelled(cancelled));
+    }
+
+    if conflicts.is_empty() {
+        Ok(merged)
+    } else {
+        Err(conflicts)
+    }
+}
+
+fn change_key(change: &Change) -> String {
+    change
+        .id
+        .clone()
+        .or_else(|| change.commit.clone())
+        .unwrap_or_else(|| change.description.clone())
+}
+
+fn to_value<T: Serialize>(item: &T) -> Value {
+    serde_json::to_value(item).unwrap_or(Value::Null)
+}
+
+fn merge_fields(base: &Value, ours: &Value, theirs: &Value) -> Result<Value, Vec<String>> {
+    let (Some(base), Some(ours), Some(theirs)) =
+        (base.as_object(), ours.as_object(), theirs.as_object())
+    else {
+        return merge_field("value", base, ours, theirs).map_err(|detail| vec![detail]);
+    };
+
+    let mut merged = Map::new();
+    let mut details = Vec::new();
+    let fields = ours
+        .keys()
+        .chain(theirs.keys().filter(|k| !ours.contains_key(*k)));
+    for field in fields {
+        let get = |map: &Map<String, Value>| map.get(field).cloned().unwrap_or(Value::Null);
+        match merge_field(field, &get(base), &get(ours), &get(theirs)) {
+            Ok(value) => {
+                merged.insert(field.clone(), value);
+            }
+            Err(detail) => details.push(detail),
+        }
+    }
+
+    if details.is_empty() {
+        Ok(Value::Object(merged))
+    } else {
+        Err(details)
+    }
+}
+
+fn merge_field(name: &str, base: &Value, ours: &Value, theirs: &Value) -> Result<Value, String> {
+    if ours == theirs || theirs == base {
+        Ok(ours.clone())
+    } else if ours == base {
+        Ok(theirs.clone())
+    } else {
+        Err(format!(
+            "{}: ours {}, theirs {}",
+            name,
+            show(ours),
+            show(theirs)
+        ))
+    }
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => "none".to_string(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+
+    fn merge(base: &[Task], ours: &[Task], theirs: &[Task]) -> Result<Vec<Task>, Vec<Conflict>> {
+        merge_tasks(base, ours, theirs)
+    }
+
+    #[test]
+    fn merges_tasks_changed_on_both_sides() {
+        let keep = Task::new("Keep", Priority::Medium, vec![]);
+        let drop = Task::new("Drop", Priority::Medium, vec![]);
+        let base = vec![keep.clone(), drop.clone()];
+
+        let mut ours = base.clone();
+        ours[0].priority = Priority::High;
+        ours.push(Task::new("Ours", Priority::Low, vec![]));
+
+        let mut theirs = vec![keep.clone()];
+        theirs[0].tags = vec!["api".into()];
+        theirs.push(Task::new("Theirs", Priority::Low, vec![]));
+
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        let names: Vec<_> = merged.iter().map(|t| t.description.as_str()).collect();
+        assert_eq!(names, vec!["Keep", "Ours", "Theirs"]);
+        assert_eq!(merged[0].priority, Priority::High);
+        assert_eq!(merged[0].tags, vec!["api"]);
+    }
+
+    #[test]
+    fn reports_fields_both_sides_changed() {
+        let base = vec![Task::new("Ship", Priority::Medium, vec![])];
+        let mut ours = base.clone();
+        ours[0].priority = Priority::High;
+        let mut theirs = base.clone();
+        theirs[0].priority = Priority::Low;
+
+        let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].label, "Ship");
+        assert_eq!(
+            conflicts[0].details,
+            vec!["priority: ours High, theirs Low"]
+        );
+
+        let conflicts = merge(&base, &ours, &[]).unwrap_err();
+        assert_eq!(
+            conflicts[0].details,
+            vec!["removed in theirs, changed in ours"]
+        );
+    }
+
+    #[test]
+    fn merges_releases_by_version_and_change() {
+        let change =
+            |description: &str| Change::from(&Task::new(description, Priority::Medium, vec![]));
+        let release = |version: &str, changes: &[&Change]| {
+            Release::from_changes(
+                Version::parse(version).unwrap(),
+                Default::default(),
+                changes.to_vec(),
+            )
+        };
+        let (a, b, c) = (change("A"), change("B"), change("C"));
+
+        let base = vec![release("0.1.0", &[&a])];
+        let ours = vec![release("0.2.0", &[&b]), release("0.1.0", &[&a])];
+        let theirs = vec![release("0.1.0", &[&a, &c])];
+
+        let merged = merge_releases(&base, &ours, &theirs).unwrap();
+        let versions: Vec<_> = merged.iter().map(|r| r.version.to_string()).collect();
+        assert_eq!(versions, vec!["0.2.0", "0.1.0"]);
+        let names: Vec<_> = merged[1]
+            .changes()
+            .into_iter()
+            .map(|c| c.description)
+            .collect();
+        assert_eq!(names, vec!["A", "C"]);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod app_config;
 pub mod changes;
 pub mod common;
+pub mod history;
+pub mod merge;
 pub mod tasks;
 
 pub use app_config::AppConfig;
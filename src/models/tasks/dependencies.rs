@@ -0,0 +1,225 @@
+use crate::models::common::TaskStatus;
+use crate::models::tasks::Task;
+use anyhow::{Result, bail};
+use std::collections::VecDeque;
+
+/// Indices of the tasks a task depends on. IDs no longer in `tasks`, such
+/// as released work, are left out.
+pub fn dependency_indices(tasks: &[Task], index: usize) -> Vec<usize> {
+    tasks[index]
+        .depends
+        .iter()
+        .filter_map(|id| tasks.iter().position(|task| &task.id == id))
+        .collect()
+}
+
+/// Dependencies of a task that are not done or cancelled yet.
+pub fn open_dependencies(tasks: &[Task], index: usize) -> Vec<usize> {
+    dependency_indices(tasks, index)
+        .into_iter()
+        .filter(|&dependency| !tasks[dependency].is_closed())
+        .collect()
+}
+
+/// Whether a task can be worked on now: it is open or in progress and all
+/// of its dependencies are closed.
+pub fn is_ready(tasks: &[Task], index: usize) -> bool {
+    matches!(
+        tasks[index].status,
+        TaskStatus::Open | TaskStatus::InProgress
+    ) && open_dependencies(tasks, index).is_empty()
+}
+
+/// The first dependency cycle found, as task indices where each one depends
+/// on the next and the last depends on the first.
+pub fn find_cycle(tasks: &[Task]) -> Option<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        New,
+        Visiting,
+        Done,
+    }
+
+    let mut marks = vec![Mark::New; tasks.len()];
+
+    for start in 0..tasks.len() {
+        if marks[start] != Mark::New {
+            continue;
+        }
+
+        // Depth-first walk keeping the current path and each node's
+        // remaining dependencies.
+        let mut path = vec![start];
+        let mut pending = vec![dependency_indices(tasks, start)];
+        marks[start] = Mark::Visiting;
+
+        while let Some(next) = pending.last_mut() {
+            let Some(dependency) = next.pop() else {
+                let finished = path.pop().expect("path follows pending");
+                marks[finished] = Mark::Done;
+                pending.pop();
+                continue;
+            };
+
+            match marks[dependency] {
+                Mark::Visiting => {
+                    let from = path
+                        .iter()
+                        .position(|&index| index == dependency)
+                        .expect("visiting tasks are on the path");
+                    return Some(path[from..].to_vec());
+                }
+                Mark::New => {
+                    marks[dependency] = Mark::Visiting;
+                    path.push(dependency);
+                    pending.push(dependency_indices(tasks, dependency));
+                }
+                Mark::Done => {}
+            }
+        }
+    }
+
+    None
+}
+
+/// A dependency cycle that runs through the task at `start`, in the same
+/// form as `find_cycle` and beginning with `start`.
+pub fn find_cycle_through(tasks: &[Task], start: usize) -> Option<Vec<usize>> {
+    let mut came_from: Vec<Option<usize>> = vec![None; tasks.len()];
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        for dependency in dependency_indices(tasks, current) {
+            if dependency == start {
+                let mut cycle = vec![current];
+                while let Some(previous) = came_from[*cycle.last().expect("not empty")] {
+                    cycle.push(previous);
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if came_from[dependency].is_none() {
+                came_from[dependency] = Some(current);
+                queue.push_back(dependency);
+            }
+        }
+    }
+
+    None
+}
+
+/// A cycle as `a1b2c3 → d4e5f6 → a1b2c3`.
+pub fn describe_cycle(tasks: &[Task], cycle: &[usize]) -> String {
+    cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|&index| tasks[index].id.as_str())
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Check `task` before it replaces the task at `index`, or is appended when
+/// `index` is `None`: newly added dependencies must name other tasks in the
+/// list and may not close a cycle through the task. Edits that add no
+/// dependency pass, so a cycle can always be broken.
+pub fn check(tasks: &[Task], index: Option<usize>, task: &Task) -> Result<()> {
+    let previous = index.map(|index| &tasks[index].depends);
+    let mut added = false;
+    for id in &task.depends {
+        if previous.is_some_and(|previous| previous.contains(id)) {
+            continue;
+        }
+        added = true;
+        if id == &task.id {
+            bail!("Task {} cannot depend on itself", id);
+        }
+        if !tasks.iter().any(|other| &other.id == id) {
+            bail!("No task found with ID '{}' to depend on", id);
+        }
+    }
+
+    if !added {
+        return Ok(());
+    }
+    let mut updated = tasks.to_vec();
+    let position = match index {
+        Some(index) => {
+            updated[index] = task.clone();
+            index
+        }
+        None => {
+            updated.push(task.clone());
+            updated.len() - 1
+        }
+    };
+    if let Some(cycle) = find_cycle_through(&updated, position) {
+        bail!("Dependency cycle: {}", describe_cycle(&updated, &cycle));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+
+    fn task(id: &str, depends: &[&str]) -> Task {
+        let mut task = Task::new(id, Priority::Medium, vec![]);
+        task.id = id.to_string();
+        task.depends = depends.iter().map(|id| id.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn readiness_follows_open_dependencies() {
+        let mut tasks = vec![task("a", &[]), task("b", &["a"]), task("c", &["b", "gone"])];
+
+        assert!(is_ready(&tasks, 0));
+        assert!(!is_ready(&tasks, 1));
+        assert_eq!(open_dependencies(&tasks, 2), vec![1]);
+
+        tasks[1].status = TaskStatus::Done;
+        assert!(is_ready(&tasks, 2));
+        assert!(!is_ready(&tasks, 1));
+    }
+
+    #[test]
+    fn find_cycle_reports_the_full_path() {
+        let tasks = vec![
+            task("a", &["b"]),
+            task("b", &["c"]),
+            task("c", &["d"]),
+            task("d", &["b"]),
+        ];
+
+        let cycle = find_cycle(&tasks).unwrap();
+        assert_eq!(describe_cycle(&tasks, &cycle), "b → c → d → b");
+
+        let acyclic = vec![task("a", &["b", "c"]), task("b", &["c"]), task("c", &[])];
+        assert_eq!(find_cycle(&acyclic), None);
+        assert!(find_cycle(&[task("self", &["self"])]).is_some());
+    }
+
+    #[test]
+    fn check_rejects_unknown_ids_and_new_cycles() {
+        let tasks = vec![task("a", &[]), task("b", &["a"])];
+
+        assert!(check(&tasks, None, &task("c", &["b"])).is_ok());
+        assert!(check(&tasks, None, &task("c", &["zz"])).is_err());
+
+        let err = check(&tasks, Some(0), &task("a", &["b"])).unwrap_err();
+        assert_eq!(err.to_string(), "Dependency cycle: a → b → a");
+    }
+
+    #[test]
+    fn check_allows_edits_that_break_an_existing_cycle() {
+        let tasks = vec![task("a", &["b"]), task("b", &["a"]), task("c", &[])];
+
+        assert!(check(&tasks, Some(0), &task("a", &[])).is_ok());
+        assert!(check(&tasks, Some(2), &task("c", &["a"])).is_ok());
+
+        let tasks = vec![task("a", &[]), task("b", &["a"]), task("c", &["b"])];
+        let err = check(&tasks, Some(0), &task("a", &["c"])).unwrap_err();
+        assert_eq!(err.to_string(), "Dependency cycle: a → c → b → a");
+    }
+}
@@ -1,6 +1,10 @@
-use crate::models::{common::Version, tasks::Task};
+use crate::models::{
+    common::Version,
+    tasks::{Task, task},
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct List {
@@ -25,8 +29,69 @@ impl List {
     }
 
     /// Add a task to the list
-    pub fn add_task(&mut self, task: Task) {
+    pub fn add_task(&mut self, mut task: Task) {
+        let mut attempt = 0;
+        while self.tasks.iter().any(|t| t.id == task.id) {
+            attempt += 1;
+            task.id = task::rehash_id(&task.id, attempt);
+        }
         self.tasks.push(task);
         self.modified_at = Utc::now();
     }
+
+    /// Remove a task by index. Its subtasks move up to its parent.
+    pub fn remove_task(&mut self, index: usize) -> Option<Task> {
+        if index >= self.tasks.len() {
+            return None;
+        }
+        let task = self.tasks.remove(index);
+        for other in &mut self.tasks {
+            if other.parent.as_deref() == Some(task.id.as_str()) {
+                other.parent = task.parent.clone();
+            }
+        }
+        self.modified_at = Utc::now();
+        Some(task)
+    }
+
+    /// Find the index of a task by its ID
+    pub fn find_task_by_id(&self, id: &str) -> Option<usize> {
+        let id = task::normalize_id(id);
+        self.tasks.iter().position(|task| task.id == id)
+    }
+
+    /// Reassign duplicate task IDs so every task is addressable. A
+    /// dependency on a duplicated ID is taken to mean the nearest task with
+    /// that ID above it (or below, if there is none above), and follows
+    /// that task to its new ID.
+    pub fn ensure_unique_ids(&mut self) {
+        let original: Vec<String> = self.tasks.iter().map(|task| task.id.clone()).collect();
+        let mut seen = HashSet::new();
+        for task in &mut self.tasks {
+            let mut attempt = 0;
+            while !seen.insert(task.id.clone()) {
+                attempt += 1;
+                task.id = task::rehash_id(&task.id, attempt);
+            }
+        }
+
+        let ids: Vec<String> = self.tasks.iter().map(|task| task.id.clone()).collect();
+        if ids == original {
+            return;
+        }
+        for (index, task) in self.tasks.iter_mut().enumerate() {
+            for dependency in &mut task.depends {
+                if original.iter().filter(|id| *id == dependency).count() < 2 {
+                    continue;
+                }
+                let nearest = (0..index)
+                    .rev()
+                    .chain(index + 1..original.len())
+                    .find(|&other| &original[other] == dependency);
+                if let Some(nearest) = nearest {
+                    *dependency = ids[nearest].clone();
+                }
+            }
+        }
+    }
 }
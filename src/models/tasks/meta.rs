@@ -0,0 +1,227 @@
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Metadata keys tally reads itself; any other `@key value` line is custom.
+pub const KNOWN_KEYS: &[&str] = &[
+    "id",
+    "created",
+    "created_version",
+    "created_commit",
+    "created_by",
+    "blocked",
+    "depends",
+    "due",
+    "scheduled",
+    "recur",
+    "recurred",
+    "completed",
+    "completed_version",
+    "completed_commit",
+];
+
+/// Custom `@key value` fields such as `@owner alice`, kept in the order
+/// and with the key spelling they were written. Keys compare without
+/// regard to case, and a key may repeat.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomMeta {
+    entries: Vec<(String, String)>,
+}
+
+impl CustomMeta {
+    /// Validate a key, returning it trimmed; `None` if it is empty, contains
+    /// characters other than letters, digits, `_` and `-`, or is one of the
+    /// `KNOWN_KEYS`.
+    pub fn key(key: &str) -> Option<String> {
+        let key = key.trim();
+        let valid = !key.is_empty()
+            && key
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+        let reserved = KNOWN_KEYS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(key));
+        (valid && !reserved).then(|| key.to_string())
+    }
+
+    /// The first value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let index = self.position(key.trim())?;
+        Some(&self.entries[index].1)
+    }
+
+    /// Every value of `key`, in the order they were written.
+    pub fn values(&self, key: &str) -> impl Iterator<Item = &str> {
+        let key = key.trim();
+        self.entries
+            .iter()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set a single value, keeping the key's first position if it is already
+    /// present and dropping its other values. Invalid and reserved keys are
+    /// ignored.
+    pub fn insert(&mut self, key: &str, value: impl Into<String>) {
+        let Some(key) = Self::key(key) else {
+            return;
+        };
+        let value = value.into();
+        let Some(first) = self.position(&key) else {
+            self.entries.push((key, value));
+            return;
+        };
+        let mut index = 0;
+        self.entries.retain(|(name, _)| {
+            let keep = index <= first || !name.eq_ignore_ascii_case(&key);
+            index += 1;
+            keep
+        });
+        self.entries[first] = (key, value);
+    }
+
+    /// Add another value, after any the key already has. Invalid and
+    /// reserved keys are ignored.
+    pub fn push(&mut self, key: &str, value: impl Into<String>) {
+        if let Some(key) = Self::key(key) {
+            self.entries.push((key, value.into()));
+        }
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(name, _)| name.eq_ignore_ascii_case(key))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether this matches a filter given as `key=value`, or `key` to
+    /// require the key with any value. A value of several comma-separated
+    /// entries, or a repeated key, matches any one of them.
+    pub fn matches(&self, filter: &str) -> bool {
+        let (key, expected) = match filter.split_once('=') {
+            Some((key, value)) => (key, Some(value.trim())),
+            None => (filter, None),
+        };
+        let mut values = self.values(key).peekable();
+        match expected {
+            None => values.peek().is_some(),
+            Some(expected) => values.any(|value| {
+                value == expected || value.split(',').any(|part| part.trim() == expected)
+            }),
+        }
+    }
+
+    /// Key/value pairs in the order they were written.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+/// A key's values in JSON: a string, or an array when the key repeats.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonValues {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Serialize for CustomMeta {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut keys: Vec<&str> = Vec::new();
+        for (key, _) in self.iter() {
+            if !keys.iter().any(|seen| seen.eq_ignore_ascii_case(key)) {
+                keys.push(key);
+            }
+        }
+
+        let mut map = serializer.serialize_map(Some(keys.len()))?;
+        for key in keys {
+            let mut values: Vec<String> = self.values(key).map(str::to_string).collect();
+            let values = if values.len() == 1 {
+                JsonValues::One(values.remove(0))
+            } else {
+                JsonValues::Many(values)
+            };
+            map.serialize_entry(key, &values)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomMeta {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = BTreeMap::<String, JsonValues>::deserialize(deserializer)?;
+        let mut meta = Self::default();
+        for (key, values) in values {
+            match values {
+                JsonValues::One(value) => meta.push(&key, value),
+                JsonValues::Many(values) => {
+                    for value in values {
+                        meta.push(&key, value);
+                    }
+                }
+            }
+        }
+        Ok(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order_and_key_case_and_rejects_reserved_keys() {
+        let mut meta = CustomMeta::default();
+        meta.insert("Owner", "alice");
+        meta.insert("issue", "GH-123");
+        meta.insert("owner", "bob");
+        meta.insert("DUE", "tomorrow");
+        meta.insert("bad key", "x");
+
+        let pairs: Vec<_> = meta.iter().collect();
+        assert_eq!(pairs, vec![("owner", "bob"), ("issue", "GH-123")]);
+        assert_eq!(meta.get("OWNER"), Some("bob"));
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(json, r#"{"owner":"bob","issue":"GH-123"}"#);
+    }
+
+    #[test]
+    fn repeated_keys_keep_every_value() {
+        let mut meta = CustomMeta::default();
+        meta.push("Reviewer", "bob");
+        meta.push("estimate", "3h");
+        meta.push("reviewer", "carol");
+
+        let pairs: Vec<_> = meta.iter().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("Reviewer", "bob"),
+                ("estimate", "3h"),
+                ("reviewer", "carol")
+            ]
+        );
+        assert!(meta.matches("reviewer=carol"));
+        assert!(!meta.matches("reviewer=dave"));
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(json, r#"{"Reviewer":["bob","carol"],"estimate":"3h"}"#);
+        let parsed: CustomMeta = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed.values("reviewer").collect::<Vec<_>>(),
+            ["bob", "carol"]
+        );
+
+        meta.insert("REVIEWER", "dave");
+        let pairs: Vec<_> = meta.iter().collect();
+        assert_eq!(pairs, vec![("REVIEWER", "dave"), ("estimate", "3h")]);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod dependencies;
 pub mod list;
+pub mod meta;
+pub mod recurrence;
 pub mod task;
+pub mod tree;
 
 pub use list::List;
+pub use meta::CustomMeta;
+pub use recurrence::Recurrence;
 pub use task::Task;
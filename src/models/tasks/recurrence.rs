@@ -0,0 +1,189 @@
+use crate::models::tasks::Task;
+use crate::utils::dates;
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// When a recurring task comes back after it is finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    /// A span such as `1w` after completion, or after the previous due date.
+    Every(String),
+    /// Once each time the task is released by `tally semver`.
+    EveryRelease,
+}
+
+impl Recurrence {
+    pub fn is_time_based(&self) -> bool {
+        matches!(self, Recurrence::Every(_))
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = anyhow::Error;
+
+    /// Parse `daily`, `weekly`, `monthly`, `every 2w`, `every day` or
+    /// `every-release`.
+    fn from_str(input: &str) -> Result<Self> {
+        let value = input
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_ascii_lowercase();
+
+        let span = match value.as_str() {
+            "every-release" | "every release" => return Ok(Recurrence::EveryRelease),
+            "daily" | "every day" => "1d".to_string(),
+            "weekly" | "every week" => "1w".to_string(),
+            "monthly" | "every month" => "1m".to_string(),
+            _ => value
+                .strip_prefix("every ")
+                .map(|span| span.replace(' ', ""))
+                .unwrap_or_default(),
+        };
+
+        dates::add_span(&span, NaiveDate::default())
+            .map(|_| Recurrence::Every(span))
+            .map_err(|_| {
+                anyhow!(
+                    "Invalid recurrence '{}': use daily, weekly, monthly, every <N>d|w|m, or every-release",
+                    input.trim()
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> Self {
+        recurrence.to_string()
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Every(span) => match span.as_str() {
+                "1d" => write!(f, "daily"),
+                "1w" => write!(f, "weekly"),
+                "1m" => write!(f, "monthly"),
+                span => write!(f, "every {}", span),
+            },
+            Recurrence::EveryRelease => write!(f, "every-release"),
+        }
+    }
+}
+
+/// A fresh open copy of a finished recurring task, or `None` if the task does
+/// not recur. Time-based copies are due one span after the previous due date,
+/// moved forward past `today`, or one span after `today` without one; a
+/// scheduled date moves by the same amount.
+pub fn next_instance(task: &Task, today: NaiveDate) -> Option<Task> {
+    let recurrence = task.recur.as_ref()?;
+
+    let mut next = Task::new(task.description.clone(), task.priority, task.tags.clone());
+    next.parent = task.parent.clone();
+    next.recur = Some(recurrence.clone());
+    next.meta = task.meta.clone();
+    next.notes = task.notes.clone();
+
+    if let Recurrence::Every(span) = recurrence {
+        let base = task.due.unwrap_or(today);
+        let mut due = dates::add_span(span, base).ok()?;
+        while due <= today {
+            due = dates::add_span(span, due).ok()?;
+        }
+        next.due = Some(due);
+        next.scheduled = task
+            .scheduled
+            .and_then(|scheduled| scheduled.checked_add_signed(due - base));
+    }
+
+    Some(next)
+}
+
+/// Whether a finished recurring task already made its next copy. Tasks
+/// finished before copies were linked fall back to looking for an open copy
+/// with the same description.
+pub fn has_recurred(tasks: &[Task], task: &Task) -> bool {
+    if task.recurred.is_some() {
+        return true;
+    }
+    tasks.iter().any(|other| {
+        other.id != task.id
+            && !other.is_closed()
+            && other.recur.is_some()
+            && other.description == task.description
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays_rules() {
+        let parse = |rule: &str| rule.parse::<Recurrence>().unwrap().to_string();
+
+        assert_eq!(parse("Weekly"), "weekly");
+        assert_eq!(parse("every week"), "weekly");
+        assert_eq!(parse("every 2w"), "every 2w");
+        assert_eq!(parse("every 10 d"), "every 10d");
+        assert_eq!(parse("every-release"), "every-release");
+        assert!("fortnightly".parse::<Recurrence>().is_err());
+        assert!("every 2y".parse::<Recurrence>().is_err());
+        assert!("every 0d".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn next_instance_copies_the_task_and_moves_dates_forward() {
+        let mut task = Task::new("Rotate credentials", Priority::High, vec!["ops".into()]);
+        task.recur = Some("every 2w".parse().unwrap());
+        task.due = Some(day(2026, 10, 1));
+        task.scheduled = Some(day(2026, 9, 28));
+
+        let next = next_instance(&task, day(2026, 10, 20)).unwrap();
+        assert_eq!(next.description, task.description);
+        assert_eq!(next.priority, Priority::High);
+        assert_eq!(next.tags, vec!["ops"]);
+        assert!(!next.is_closed());
+        assert_eq!(next.due, Some(day(2026, 10, 29)));
+        assert_eq!(next.scheduled, Some(day(2026, 10, 26)));
+
+        task.recur = Some(Recurrence::EveryRelease);
+        let next = next_instance(&task, day(2026, 10, 20)).unwrap();
+        assert_eq!(next.due, None);
+
+        task.recur = None;
+        assert!(next_instance(&task, day(2026, 10, 20)).is_none());
+    }
+
+    #[test]
+    fn has_recurred_follows_the_link_to_the_copy() {
+        let mut done = Task::new("Water plants", Priority::Medium, vec![]);
+        done.recur = Some("weekly".parse().unwrap());
+        let copy = next_instance(&done, day(2026, 10, 20)).unwrap();
+
+        assert!(has_recurred(&[done.clone(), copy.clone()], &done));
+        assert!(!has_recurred(std::slice::from_ref(&done), &done));
+
+        // The copy was deleted on purpose: the link still says it was made.
+        done.recurred = Some(copy.id.clone());
+        assert!(has_recurred(std::slice::from_ref(&done), &done));
+    }
+}
@@ -1,18 +1,54 @@
-use crate::models::common::{Priority, Version};
-use chrono::{DateTime, Utc};
+use crate::models::common::{Priority, TaskStatus, Version};
+use crate::models::tasks::{CustomMeta, Recurrence};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Number of hex characters in a generated task ID.
+const ID_LEN: usize = 6;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
+    pub id: String,
     pub description: String,
     pub priority: Priority,
     pub tags: Vec<String>,
-    pub completed: bool,
+    #[serde(default)]
+    pub status: TaskStatus,
+    /// Why the task is blocked, while its status is `Blocked`.
+    #[serde(default)]
+    pub blocked_reason: Option<String>,
+    /// ID of the task this one is nested under in TODO.md.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// IDs of tasks that must be finished before this one.
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// Date the task must be done by.
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    /// Date work on the task is planned to start.
+    #[serde(default)]
+    pub scheduled: Option<NaiveDate>,
+    /// Rule for re-creating the task once it is finished.
+    #[serde(default)]
+    pub recur: Option<Recurrence>,
+    /// ID of the copy made when this recurring task was finished.
+    #[serde(default)]
+    pub recurred: Option<String>,
+    /// Custom `@key value` fields tally does not interpret.
+    #[serde(default)]
+    pub meta: CustomMeta,
+    /// Free-form Markdown written below the task's metadata.
+    #[serde(default)]
+    pub notes: Option<String>,
 
     // Creation metadata
     pub created_at_time: DateTime<Utc>,
     pub created_at_version: Option<Version>,
     pub created_at_commit: Option<String>,
+    /// Who created the task, or wrote the source marker it came from.
+    #[serde(default)]
+    pub created_by: Option<String>,
 
     // Completion metadata
     pub completed_at_time: Option<DateTime<Utc>>,
@@ -23,17 +59,98 @@ pub struct Task {
 impl Task {
     /// Create a new incomplete task
     pub fn new(description: impl Into<String>, priority: Priority, tags: Vec<String>) -> Self {
+        let description = description.into();
+        let created_at_time = Utc::now();
         Self {
-            description: description.into(),
+            id: derive_id(&description, &created_at_time),
+            description,
             priority,
             tags,
-            completed: false,
-            created_at_time: Utc::now(),
+            status: TaskStatus::Open,
+            blocked_reason: None,
+            parent: None,
+            depends: Vec::new(),
+            due: None,
+            scheduled: None,
+            recur: None,
+            recurred: None,
+            meta: CustomMeta::default(),
+            notes: None,
+            created_at_time,
             created_at_version: None,
             created_at_commit: None,
+            created_by: None,
             completed_at_time: None,
             completed_at_version: None,
             completed_at_commit: None,
         }
     }
+
+    pub fn is_done(&self) -> bool {
+        self.status == TaskStatus::Done
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.status.is_closed()
+    }
+}
+
+/// Derive a short ID from a task's description and creation time.
+///
+/// The same inputs always produce the same ID, so tasks written before IDs
+/// existed keep a stable ID until it is persisted on the next save.
+pub fn derive_id(description: &str, created_at: &DateTime<Utc>) -> String {
+    let seed = format!(
+        "{}\x1f{}",
+        description,
+        created_at.timestamp_nanos_opt().unwrap_or_default()
+    );
+    hash_id(&seed)
+}
+
+/// Derive a replacement ID when `id` collides with an existing one.
+pub fn rehash_id(id: &str, attempt: usize) -> String {
+    hash_id(&format!("{id}\x1f{attempt}"))
+}
+
+/// Normalize user input such as `#A1B2C3` into a comparable task ID.
+pub fn normalize_id(value: &str) -> String {
+    value.trim().trim_start_matches('#').to_ascii_lowercase()
+}
+
+fn hash_id(seed: &str) -> String {
+    // FNV-1a keeps generated IDs identical across builds and platforms.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in seed.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let hex = format!("{hash:016x}");
+    hex[hex.len() - ID_LEN..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn derive_id_is_stable_and_short() {
+        let created = Utc.with_ymd_and_hms(2026, 2, 20, 8, 15, 0).unwrap();
+
+        let first = derive_id("config support", &created);
+        let second = derive_id("config support", &created);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), ID_LEN);
+        assert!(first.chars().all(|ch| ch.is_ascii_hexdigit()));
+        assert_ne!(first, derive_id("other task", &created));
+    }
+
+    #[test]
+    fn normalize_id_strips_hash_and_case() {
+        assert_eq!(normalize_id(" #A1B2C3 "), "a1b2c3");
+        assert_eq!(normalize_id("a1b2c3"), "a1b2c3");
+    }
 }
@@ -0,0 +1,111 @@
+use crate::models::tasks::Task;
+
+/// Index of a task's parent, if the parent is in `tasks`.
+pub fn parent_index(tasks: &[Task], index: usize) -> Option<usize> {
+    let parent = tasks[index].parent.as_deref()?;
+    tasks
+        .iter()
+        .position(|task| task.id == parent)
+        .filter(|&parent| parent != index)
+}
+
+/// Whether the task has no parent in `tasks`.
+pub fn is_root(tasks: &[Task], index: usize) -> bool {
+    parent_index(tasks, index).is_none()
+}
+
+/// Indices of a task's direct children, in list order.
+pub fn children(tasks: &[Task], index: usize) -> Vec<usize> {
+    (0..tasks.len())
+        .filter(|&child| child != index && parent_index(tasks, child) == Some(index))
+        .collect()
+}
+
+/// Indices of all tasks below a task, depth first.
+pub fn descendants(tasks: &[Task], index: usize) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut stack = children(tasks, index);
+    stack.reverse();
+
+    while let Some(child) = stack.pop() {
+        if found.contains(&child) || child == index {
+            continue;
+        }
+        found.push(child);
+        let mut grandchildren = children(tasks, child);
+        grandchildren.reverse();
+        stack.extend(grandchildren);
+    }
+    found
+}
+
+/// Indices of a task's parent, grandparent and so on, nearest first.
+pub fn ancestors(tasks: &[Task], index: usize) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut current = index;
+
+    while let Some(parent) = parent_index(tasks, current) {
+        if parent == index || found.contains(&parent) {
+            break;
+        }
+        found.push(parent);
+        current = parent;
+    }
+    found
+}
+
+/// Tasks in tree order: each root followed by its descendants, with their depth.
+pub fn walk(tasks: &[Task]) -> Vec<(usize, usize)> {
+    let mut order = Vec::new();
+    for root in (0..tasks.len()).filter(|&i| is_root(tasks, i)) {
+        order.push((root, 0));
+        for child in descendants(tasks, root) {
+            order.push((child, ancestors(tasks, child).len()));
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+
+    fn task(id: &str, parent: Option<&str>) -> Task {
+        let mut task = Task::new(id, Priority::Medium, vec![]);
+        task.id = id.to_string();
+        task.parent = parent.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn walk_nests_children_under_their_parents() {
+        let tasks = vec![
+            task("a", None),
+            task("b", None),
+            task("a1", Some("a")),
+            task("a1x", Some("a1")),
+            task("orphan", Some("gone")),
+            task("a2", Some("a")),
+        ];
+
+        let order: Vec<(&str, usize)> = walk(&tasks)
+            .into_iter()
+            .map(|(i, depth)| (tasks[i].id.as_str(), depth))
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![
+                ("a", 0),
+                ("a1", 1),
+                ("a1x", 2),
+                ("a2", 1),
+                ("b", 0),
+                ("orphan", 0),
+            ]
+        );
+        assert_eq!(ancestors(&tasks, 3), vec![2, 0]);
+        assert_eq!(descendants(&tasks, 0), vec![2, 3, 5]);
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+
+/// Open `text` in the user's editor and return what was saved.
+///
+/// Uses the configured editor, then `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn edit_text(configured: Option<&str>, text: &str) -> Result<String> {
+    let editor = resolve_editor(
+        configured,
+        env::var("VISUAL").ok().as_deref(),
+        env::var("EDITOR").ok().as_deref(),
+    );
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("Editor command is empty")?;
+
+    // A fresh, randomly named file that is removed when it goes out of scope,
+    // whether or not the edit succeeds.
+    let mut file = tempfile::Builder::new()
+        .prefix("tally-edit-")
+        .suffix(".md")
+        .tempfile()
+        .context("Failed to create a temporary file to edit")?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    // Read by path: editors often save by replacing the file.
+    Ok(fs::read_to_string(file.path())?)
+}
+
+fn resolve_editor(configured: Option<&str>, visual: Option<&str>, editor: Option<&str>) -> String {
+    [configured, visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .unwrap_or("vi")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_editor;
+
+    #[test]
+    fn resolve_editor_prefers_config_then_environment() {
+        assert_eq!(
+            resolve_editor(Some("hx"), Some("code -w"), Some("nano")),
+            "hx"
+        );
+        assert_eq!(
+            resolve_editor(Some(" "), Some("code -w"), Some("nano")),
+            "code -w"
+        );
+        assert_eq!(resolve_editor(None, None, Some("nano")), "nano");
+        assert_eq!(resolve_editor(None, None, None), "vi");
+    }
+}
@@ -1,7 +1,9 @@
+mod editor;
 mod json;
 pub mod pager;
 mod prompt;
 
+pub use editor::edit_text;
 pub use json::print_json;
 pub use pager::page_text;
-pub use prompt::confirm;
+pub use prompt::{confirm, is_interactive};
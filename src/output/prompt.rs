@@ -1,8 +1,13 @@
 use std::fmt;
 use std::io::{self, IsTerminal, Write};
 
+/// Whether `confirm` can ask the user.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
 pub fn confirm(prompt: impl fmt::Display, default_yes: bool) -> anyhow::Result<bool> {
-    if !io::stdin().is_terminal() {
+    if !is_interactive() {
         anyhow::bail!(
             "Confirmation required for non-interactive input. Run from a terminal or stage the files manually."
         );
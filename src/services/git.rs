@@ -1,19 +1,32 @@
 use crate::models::app_config::TrackCreatedFiles;
 use crate::output;
 use crate::services::storage::config_storage::ConfigStorage;
+use crate::services::storage::lock;
 use crate::utils::project_paths::ProjectPaths;
 use anyhow::{Result, anyhow, bail};
 use chrono::{DateTime, TimeZone, Utc};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Clone)]
 pub struct CommitEntry {
     pub hash: String,
     pub done_items: Vec<String>,
+    /// Task IDs or issue keys the commit closes, without a leading `#`.
+    pub refs: Vec<String>,
     pub date: DateTime<Utc>,
 }
 
+/// Words that close the task or issue named after them, as in `fixes #12`
+/// or a `Closes: TALLY-7` trailer.
+const CLOSING_KEYWORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+/// Keywords that may put an issue key such as `TALLY-7` in running text.
+/// Other forms also start subjects like `fix: UTF-8 handling`, so they only
+/// take `#` references there.
+const ISSUE_KEY_KEYWORDS: &[&str] = &["closes", "fixes"];
+
 pub fn commit_tally_files(message: &str) -> Result<()> {
     commit_tally_files_with_options(message, false)
 }
@@ -24,12 +37,12 @@ pub fn commit_tally_files_quiet(message: &str) -> Result<()> {
 
 fn commit_tally_files_with_options(message: &str, quiet: bool) -> Result<()> {
     let paths = ProjectPaths::get_paths()?;
-    let config_storage = ConfigStorage::new(&paths.config_file)?;
+    let config_storage = ConfigStorage::for_project(&paths)?;
     let config = config_storage.get_config();
 
-    let mut files = vec!["TODO.md"];
+    let mut files = vec![paths.files.todo.as_str()];
     if paths.changelog_file.exists() {
-        files.push("CHANGELOG.md");
+        files.push(paths.files.changelog.as_str());
     }
 
     track_untracked_tally_files(&paths.root, &files, config.git.track_created_files)?;
@@ -37,9 +50,11 @@ fn commit_tally_files_with_options(message: &str, quiet: bool) -> Result<()> {
     let mut args = vec!["commit", "-m", message, "--"];
     args.extend(files.iter().copied());
 
+    // Hooks run by the commit may call tally while this process holds the lock.
     let output = Command::new("git")
         .args(args)
         .current_dir(&paths.root)
+        .env(lock::OWNER_ENV, std::process::id().to_string())
         .output()?;
 
     if !output.status.success() {
@@ -111,25 +126,229 @@ fn untracked_files(root: &Path, files: &[&str]) -> Result<Vec<String>> {
     Ok(untracked)
 }
 
-pub fn scan_recent_commits(
+/// Set a key in the repository's `.git/config`.
+pub fn set_config(root: &Path, key: &str, value: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["config", key, value])
+        .current_dir(root)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "Failed to set git config {}: {}",
+            key,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// Line-based three-way merge of `base` and `other` into `current`, leaving
+/// conflict markers where they overlap. Returns whether it merged cleanly.
+pub fn merge_file(current: &Path, base: &Path, other: &Path) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["merge-file", "-L", "ours", "-L", "base", "-L", "theirs"])
+        .args([current, base, other])
+        .output()?;
+
+    // Positive exit codes count conflicts; anything else is a failure.
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(code) if code > 0 && code < 128 => Ok(false),
+        _ => bail!(
+            "git merge-file failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+    }
+}
+
+/// The `HEAD` commit, if its message names done items or closes tasks.
+pub fn head_commit(root: &Path, done_marker: &str) -> Result<Option<CommitEntry>> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--pretty=format:%h%x1f%ct%x1f%B%x1e", "HEAD"])
+        .current_dir(root)
+        .output()?;
+
+    if !output.status.success() {
+        bail!("failed to read the HEAD commit");
+    }
+
+    let raw = String::from_utf8(output.stdout)?;
+    Ok(parse_commits(&raw, done_marker).into_iter().next())
+}
+
+/// Full hash of the commit a revision or tag points to, if it exists.
+pub fn resolve_commit(root: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", "--end-of-options"])
+        .arg(format!("{}^{{commit}}", rev))
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `ancestor` is reachable from `descendant`.
+pub fn is_ancestor(root: &Path, ancestor: &str, descendant: &str) -> bool {
+    Command::new("git")
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .current_dir(root)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Short hash of `HEAD`, or `None` outside a repository or before the
+/// first commit.
+pub fn head_hash(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "--verify", "--quiet", "HEAD"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The top directory of the work tree containing `dir`.
+pub fn toplevel(dir: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// The configured `user.name`, if any.
+pub fn user_name(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "user.name"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+/// Short hash and author of the commit that last changed `line` of `path`,
+/// or `None` if the line is not committed yet.
+pub fn blame_line(root: &Path, path: &str, line: usize) -> Option<(String, String)> {
+    let output = Command::new("git")
+        .args(["blame", "--porcelain", "-L"])
+        .arg(format!("{},{}", line, line))
+        .args(["--", path])
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    parse_blame(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_blame(porcelain: &str) -> Option<(String, String)> {
+    let mut lines = porcelain.lines();
+    let hash = lines.next()?.split_whitespace().next()?;
+    // Uncommitted lines are attributed to an all-zero commit.
+    if hash.chars().all(|ch| ch == '0') {
+        return None;
+    }
+    let author = lines.find_map(|line| line.strip_prefix("author "))?;
+    Some((hash.chars().take(7).collect(), author.trim().to_string()))
+}
+
+/// The directory git runs hooks from, honoring `core.hooksPath`.
+pub fn hooks_dir(root: &Path) -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .current_dir(root)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "Not a git repository: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(root.join(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Commits with a `done:` block selected by extra `git log` arguments, such
+/// as a revision range or `--all`.
+pub fn scan_commits(
     root: &Path,
     done_marker: &str,
-    git_log_limit: usize,
+    log_args: &[String],
 ) -> Result<Vec<CommitEntry>> {
-    let limit = git_log_limit.to_string();
     let output = Command::new("git")
-        .args(["log", "--pretty=format:%h%x1f%ct%x1f%B%x1e", "-n", &limit])
+        .args(["log", "--pretty=format:%h%x1f%ct%x1f%B%x1e"])
+        .args(log_args)
         .current_dir(root)
         .output()?;
 
     if !output.status.success() {
-        anyhow::bail!("failed to read git log");
+        anyhow::bail!(
+            "failed to read git log: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
     let raw = String::from_utf8(output.stdout)?;
     Ok(parse_commits(&raw, done_marker))
 }
 
+/// The `origin` remote as a browsable https URL, if it can be determined.
+pub fn remote_web_url(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    web_url_from_remote(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+fn web_url_from_remote(remote: &str) -> Option<String> {
+    let remote = remote.trim().trim_end_matches('/');
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+
+    if let Some(rest) = remote.strip_prefix("git@") {
+        let (host, path) = rest.split_once(':')?;
+        return Some(format!("https://{}/{}", host, path));
+    }
+
+    if let Some(rest) = remote.strip_prefix("ssh://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, rest)| rest);
+        let (host, path) = rest.split_once('/')?;
+        let host = host.split_once(':').map_or(host, |(host, _)| host);
+        return Some(format!("https://{}/{}", host, path));
+    }
+
+    if remote.starts_with("https://") || remote.starts_with("http://") {
+        return Some(remote.to_string());
+    }
+
+    None
+}
+
 fn parse_commits(input: &str, done_marker: &str) -> Vec<CommitEntry> {
     let mut commits = Vec::new();
 
@@ -151,10 +370,12 @@ fn parse_commits(input: &str, done_marker: &str) -> Vec<CommitEntry> {
             .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap());
 
         let done_items = extract_done_items(body, done_marker);
-        if !done_items.is_empty() {
+        let refs = extract_refs(body);
+        if !done_items.is_empty() || !refs.is_empty() {
             commits.push(CommitEntry {
                 hash,
                 done_items,
+                refs,
                 date,
             });
         }
@@ -163,7 +384,22 @@ fn parse_commits(input: &str, done_marker: &str) -> Vec<CommitEntry> {
     commits
 }
 
-fn extract_done_items(message: &str, done_marker: &str) -> Vec<String> {
+/// Items listed under the `done_marker` line of a commit message, followed
+/// by the values of matching trailers such as `Done: fix parser`.
+pub fn extract_done_items(message: &str, done_marker: &str) -> Vec<String> {
+    let trailer_key = done_marker.trim().trim_end_matches(':');
+    let trailers: Vec<String> = parse_trailers(message)
+        .into_iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case(trailer_key))
+        .map(|(_, value)| value)
+        .collect();
+
+    let mut items = extract_done_block(message, done_marker);
+    items.extend(trailers);
+    items
+}
+
+fn extract_done_block(message: &str, done_marker: &str) -> Vec<String> {
     let mut items = Vec::new();
     let mut in_done = false;
 
@@ -192,10 +428,127 @@ fn extract_done_items(message: &str, done_marker: &str) -> Vec<String> {
     items
 }
 
+/// `Key: value` trailers in the last paragraph of a commit message, read
+/// the way `git interpret-trailers` does: every line of that paragraph must
+/// be a trailer or an indented continuation of one, and the subject line
+/// never counts.
+pub fn parse_trailers(message: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = message.trim_end().lines().collect();
+    let start = lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|blank| blank + 1)
+        .unwrap_or(lines.len());
+
+    let mut trailers: Vec<(String, String)> = Vec::new();
+    for line in &lines[start..] {
+        if line.starts_with([' ', '\t']) {
+            let Some((_, value)) = trailers.last_mut() else {
+                return Vec::new();
+            };
+            value.push(' ');
+            value.push_str(line.trim());
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            return Vec::new();
+        };
+        let is_token = !key.is_empty()
+            && key
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+        if !is_token || value.trim().is_empty() {
+            return Vec::new();
+        }
+        trailers.push((key.to_string(), value.trim().to_string()));
+    }
+    trailers
+}
+
+/// Task IDs and issue keys a commit message closes, from closing trailers
+/// (`Closes: #a1b2c3, #d4e5f6`) and phrases such as `fix #12` or
+/// `closes TALLY-7` anywhere in the message. Leading `#`s are removed.
+pub fn extract_refs(message: &str) -> Vec<String> {
+    let is_closing = |word: &str| {
+        let word = word
+            .trim_start_matches('(')
+            .trim_end_matches(':')
+            .to_ascii_lowercase();
+        CLOSING_KEYWORDS.contains(&word.as_str())
+    };
+
+    let mut refs: Vec<String> = parse_trailers(message)
+        .into_iter()
+        .filter(|(key, _)| is_closing(key))
+        .flat_map(|(_, value)| {
+            value
+                .split([',', ' '])
+                .map(|token| token.trim().trim_start_matches('#').to_string())
+                .filter(|token| !token.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let words: Vec<&str> = message.split_whitespace().collect();
+    for pair in words.windows(2) {
+        if !is_closing(pair[0]) {
+            continue;
+        }
+        let token = pair[1]
+            .trim_start_matches('(')
+            .trim_end_matches(['.', ',', ';', ':', ')']);
+        let keyword = pair[0].trim_start_matches('(').to_ascii_lowercase();
+        let reference = match token.strip_prefix('#') {
+            Some(reference) => hash_reference(reference),
+            None if ISSUE_KEY_KEYWORDS.contains(&keyword.as_str()) => issue_key(token),
+            None => None,
+        };
+        refs.extend(reference);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    refs.retain(|reference| seen.insert(reference.to_ascii_lowercase()));
+    refs
+}
+
+/// The `12` or `a1b2c3` of a `#12` or `#a1b2c3` reference.
+fn hash_reference(reference: &str) -> Option<String> {
+    let valid = !reference.is_empty() && reference.chars().all(|ch| ch.is_ascii_alphanumeric());
+    valid.then(|| reference.to_string())
+}
+
+/// An issue key such as `TALLY-7`.
+fn issue_key(token: &str) -> Option<String> {
+    let (project, number) = token.rsplit_once('-')?;
+    let valid = project.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && project.chars().all(|ch| ch.is_ascii_alphanumeric())
+        && project.chars().any(|ch| ch.is_ascii_uppercase())
+        && !number.is_empty()
+        && number.chars().all(|ch| ch.is_ascii_digit());
+    valid.then(|| token.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn web_url_from_remote_normalizes_common_forms() {
+        for remote in [
+            "git@github.com:acme/tally.git",
+            "ssh://git@github.com:22/acme/tally.git",
+            "https://github.com/acme/tally.git",
+            "https://github.com/acme/tally/",
+        ] {
+            assert_eq!(
+                web_url_from_remote(remote).as_deref(),
+                Some("https://github.com/acme/tally"),
+                "remote '{remote}'"
+            );
+        }
+        assert_eq!(web_url_from_remote("/srv/git/tally.git"), None);
+    }
+
     #[test]
     fn extract_done_items_parses_list_and_stops_on_blank_line() {
         let message =
@@ -230,6 +583,41 @@ mod tests {
         assert_eq!(commits[0].date.timestamp(), 1700000000);
     }
 
+    #[test]
+    fn reads_done_trailers_and_closing_references() {
+        let message = "Fix parser (fixes #12, closes TALLY-7)\n\nSee the fix for GH issue.\n\nDone: handle quoted strings\nCloses: #a1b2c3,\n  d4e5f6\nSigned-off-by: Dev <dev@example.com>";
+
+        assert_eq!(
+            extract_done_items(message, "done:"),
+            vec!["handle quoted strings"]
+        );
+        assert_eq!(
+            extract_refs(message),
+            vec!["a1b2c3", "d4e5f6", "12", "TALLY-7"]
+        );
+
+        // A paragraph with any non-trailer line is not a trailer block.
+        assert!(parse_trailers("subject\n\nDone: x\nnot a trailer").is_empty());
+        // Nor is the subject line.
+        assert!(parse_trailers("Done: fix parser").is_empty());
+        assert!(extract_refs("Fix the build").is_empty());
+    }
+
+    #[test]
+    fn hyphenated_words_are_not_issue_keys() {
+        for message in [
+            "fix: UTF-8 handling",
+            "Fix UTF-8 handling in names",
+            "fixed SHA-256 digests",
+            "Resolve ISO-8601 parsing\n\nCloses the gap in UTF-16 input.",
+            "fixes: UTF-8 in paths",
+        ] {
+            assert!(extract_refs(message).is_empty(), "message '{message}'");
+        }
+        assert_eq!(extract_refs("fix: crash (fix #12)"), vec!["12"]);
+        assert_eq!(extract_refs("Fixes TALLY-7"), vec!["TALLY-7"]);
+    }
+
     #[test]
     fn parse_commits_uses_epoch_for_invalid_timestamp() {
         let input = "abc123\x1fnot-a-number\x1fsubject\n\ndone:\n- keep\n\x1e";
@@ -239,4 +627,17 @@ mod tests {
         assert_eq!(commits.len(), 1);
         assert_eq!(commits[0].date.timestamp(), 0);
     }
+
+    #[test]
+    fn parse_blame_reads_commit_and_author() {
+        let porcelain = "3f2a9c1d0e4b5a6978877665544332211aabbccd 12 12 1\nauthor Ada Lovelace\nauthor-mail <ada@example.com>\n\t// TODO: tidy\n";
+        assert_eq!(
+            parse_blame(porcelain),
+            Some(("3f2a9c1".to_string(), "Ada Lovelace".to_string()))
+        );
+
+        let uncommitted =
+            "0000000000000000000000000000000000000000 3 3 1\nauthor Not Committed Yet\n";
+        assert_eq!(parse_blame(uncommitted), None);
+    }
 }
@@ -0,0 +1,66 @@
+use crate::models::common::Version;
+use std::fs;
+use std::path::Path;
+
+/// The version declared in the project's `Cargo.toml` (including a
+/// workspace's `[workspace.package]`) or `package.json`.
+pub fn package_version(root: &Path) -> Option<Version> {
+    cargo_version(root).or_else(|| npm_version(root))
+}
+
+fn cargo_version(root: &Path) -> Option<Version> {
+    let content = fs::read_to_string(root.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    let version = manifest
+        .get("package")
+        .and_then(|package| package.get("version"))
+        .and_then(toml::Value::as_str)
+        .or_else(|| {
+            manifest
+                .get("workspace")?
+                .get("package")?
+                .get("version")?
+                .as_str()
+        })?;
+    Version::parse(version).ok()
+}
+
+fn npm_version(root: &Path) -> Option<Version> {
+    let content = fs::read_to_string(root.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    Version::parse(manifest.get("version")?.as_str()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reads_cargo_workspace_and_npm_versions() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        assert_eq!(package_version(root), None);
+
+        fs::write(
+            root.join("package.json"),
+            r#"{"name": "web", "version": "2.1.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(package_version(root), Version::parse("2.1.0").ok());
+
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\"]\n\n[workspace.package]\nversion = \"0.4.2\"\n",
+        )
+        .unwrap();
+        assert_eq!(package_version(root), Version::parse("0.4.2").ok());
+
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"1.3.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(package_version(root), Version::parse("1.3.0").ok());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod git;
+pub mod manifest;
 pub mod serializers;
 pub mod source;
 pub mod storage;
@@ -1,39 +1,111 @@
 use crate::models::{
+    app_config::{Changelog, ChangelogGrouping, ChangelogStyle},
     changes::{Change, Log, Release},
     common::{Priority, Version},
+    tasks::CustomMeta,
 };
+use crate::utils::conventional_commit;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::BTreeMap;
 
-pub fn to_markdown(changelog: &Log) -> String {
-    let mut output = String::new();
-    output.push_str(&render_changelog_header(changelog));
-
-    for release in &changelog.releases {
-        output.push_str(&release_to_markdown(release));
-        output.push('\n');
-    }
+const KEEP_A_CHANGELOG_SECTIONS: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Conventional-commit types and their headings, in rendering order.
+const CONVENTIONAL_SECTIONS: [(&str, &str); 11] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("revert", "Reverts"),
+    ("docs", "Documentation"),
+    ("style", "Styles"),
+    ("refactor", "Code Refactoring"),
+    ("test", "Tests"),
+    ("build", "Build System"),
+    ("ci", "Continuous Integration"),
+    ("chore", "Chores"),
+];
+
+const BREAKING_SECTION: &str = "Breaking Changes";
+const OTHER_SECTION: &str = "Other Changes";
+const CANCELLED_SECTION: &str = "Cancelled";
+
+const KEEP_A_CHANGELOG_INTRO: &str =
+    "All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).";
+
+/// How the priority of bullets under a section is recovered when parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionKind {
+    /// Tally layout: the section heading is the priority.
+    Priority(Priority),
+    /// Typed sections: priority is written inline as `(high)` / `(low)`.
+    Inline,
+    /// Conventional type section: the `type(scope):` prefix is rendered as
+    /// `**scope:**`, or dropped when there is no scope.
+    Conventional(&'static str),
+    /// Breaking changes of any type, rendered with their bold prefix.
+    Breaking,
+    /// Tasks cancelled before the release, with inline priority.
+    Cancelled,
+}
 
-    output
+pub fn to_markdown(changelog: &Log, format: &Changelog) -> String {
+    render_changelog(changelog, &PreservedChangelogSections::default(), format)
 }
 
-pub fn to_markdown_preserving(changelog: &Log, previous: Option<&str>) -> String {
+pub fn to_markdown_preserving(
+    changelog: &Log,
+    previous: Option<&str>,
+    format: &Changelog,
+) -> String {
     let Some(previous) = previous else {
-        return to_markdown(changelog);
+        return to_markdown(changelog, format);
     };
 
-    let preserved = preserved_changelog_sections(previous);
+    render_changelog(changelog, &preserved_changelog_sections(previous), format)
+}
+
+fn render_changelog(
+    changelog: &Log,
+    preserved: &PreservedChangelogSections,
+    format: &Changelog,
+) -> String {
+    let keep_a_changelog = format.style == ChangelogStyle::KeepAChangelog;
     let mut output = String::new();
-    output.push_str(&render_changelog_header(changelog));
+    output.push_str(&render_changelog_header(changelog, format));
 
     if !preserved.intro.trim().is_empty() {
         output.push_str(preserved.intro.trim());
         output.push_str("\n\n");
+    } else if keep_a_changelog {
+        output.push_str(KEEP_A_CHANGELOG_INTRO);
+        output.push_str("\n\n");
+    }
+
+    let mut top_level_sections = preserved.top_level_sections.clone();
+    let unreleased = preserved.unreleased.trim();
+    if keep_a_changelog {
+        output.push_str("## [Unreleased]\n\n");
+        if !unreleased.is_empty() {
+            output.push_str(unreleased);
+            output.push_str("\n\n");
+        }
+    } else if !unreleased.is_empty() {
+        top_level_sections.insert(0, format!("## [Unreleased]\n\n{}", unreleased));
     }
 
     for release in &changelog.releases {
-        output.push_str(&release_to_markdown(release));
+        output.push_str(&release_to_markdown(release, format));
         if let Some(extra) = preserved.release_extras.get(&release.version)
             && !extra.trim().is_empty()
         {
@@ -43,20 +115,89 @@ pub fn to_markdown_preserving(changelog: &Log, previous: Option<&str>) -> String
         output.push('\n');
     }
 
-    if !preserved.top_level_sections.is_empty() {
-        output.push_str(&preserved.top_level_sections.join("\n\n"));
+    if !top_level_sections.is_empty() {
+        output.push_str(&top_level_sections.join("\n\n"));
         output.push('\n');
     }
 
+    let mut links = if keep_a_changelog {
+        render_compare_links(changelog, format, &preserved.links)
+    } else {
+        String::new()
+    };
+    if !preserved.links.is_empty() {
+        links.push_str(&preserved.links);
+        links.push('\n');
+    }
+    if !links.is_empty() {
+        if !output.ends_with("\n\n") {
+            output.push('\n');
+        }
+        output.push_str(&links);
+    }
+
     output
 }
 
-fn render_changelog_header(changelog: &Log) -> String {
-    format!(
-        "# Changelog — {}\n\n*Generated on {}*\n\n",
-        changelog.project_name,
-        changelog.generated_at.format("%Y-%m-%d")
-    )
+fn render_changelog_header(changelog: &Log, format: &Changelog) -> String {
+    match format.style {
+        ChangelogStyle::Tally => format!(
+            "# Changelog — {}\n\n*Generated on {}*\n\n",
+            changelog.project_name,
+            changelog.generated_at.format("%Y-%m-%d")
+        ),
+        ChangelogStyle::KeepAChangelog => "# Changelog\n\n".to_string(),
+    }
+}
+
+/// Compare links for each release, except for labels the user already
+/// defined in `kept_links`, since the first definition of a label wins.
+fn render_compare_links(changelog: &Log, format: &Changelog, kept_links: &str) -> String {
+    let Some(url) = format.compare_url.as_deref() else {
+        return String::new();
+    };
+    let url = url.trim_end_matches('/');
+    let tag = |version: &Version| format!("{}{}", format.tag_prefix, version);
+    let user_defined = |label: &str| {
+        kept_links
+            .lines()
+            .filter_map(link_definition)
+            .any(|(kept, _)| kept.eq_ignore_ascii_case(label))
+    };
+
+    let mut output = String::new();
+    if let Some(latest) = changelog.releases.first()
+        && !user_defined("Unreleased")
+    {
+        output.push_str(&format!(
+            "[Unreleased]: {}/compare/{}...HEAD\n",
+            url,
+            tag(&latest.version)
+        ));
+    }
+
+    for (i, release) in changelog.releases.iter().enumerate() {
+        if user_defined(&release.version.to_string()) {
+            continue;
+        }
+        match changelog.releases.get(i + 1) {
+            Some(previous) => output.push_str(&format!(
+                "[{}]: {}/compare/{}...{}\n",
+                release.version,
+                url,
+                tag(&previous.version),
+                tag(&release.version)
+            )),
+            None => output.push_str(&format!(
+                "[{}]: {}/releases/tag/{}\n",
+                release.version,
+                url,
+                tag(&release.version)
+            )),
+        }
+    }
+
+    output
 }
 
 pub fn from_markdown(content: &str) -> Result<Log> {
@@ -66,11 +207,18 @@ pub fn from_markdown(content: &str) -> Result<Log> {
 
     let mut current_version: Option<Version> = None;
     let mut current_date = Utc::now();
-    let mut current_priority = None;
+    let mut current_section = None;
     let mut current_changes: Vec<Change> = Vec::new();
+    let mut current_cancelled: Vec<Change> = Vec::new();
+    let mut blank_lines = 0;
 
     for line in content.lines() {
         let trimmed = line.trim();
+        if trimmed.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        let blank_before = std::mem::take(&mut blank_lines);
 
         if let Some(rest) = trimmed.strip_prefix("# Changelog —") {
             let name = rest.trim();
@@ -90,39 +238,72 @@ pub fn from_markdown(content: &str) -> Result<Log> {
             continue;
         }
 
-        if let Some(release) = parse_release_header(trimmed) {
+        if line.starts_with("## ") {
             if let Some(version) = current_version.take() {
                 let refs: Vec<&Change> = current_changes.iter().collect();
-                releases.push(Release::from_changes(version, current_date, refs));
+                releases.push(
+                    Release::from_changes(version, current_date, refs)
+                        .with_cancelled(std::mem::take(&mut current_cancelled)),
+                );
                 current_changes.clear();
             }
-            current_version = Some(release.0);
-            current_date = release.1;
-            current_priority = None;
+            if let Some((version, date)) = parse_release_header(trimmed) {
+                current_version = Some(version);
+                current_date = date;
+            }
+            current_section = None;
             continue;
         }
 
-        if let Some(priority) = parse_priority_header(trimmed) {
-            current_priority = Some(priority);
+        if line.starts_with("### ") {
+            current_section = parse_section_header(trimmed);
             continue;
         }
 
-        if trimmed.starts_with("### ") {
-            current_priority = None;
+        let entries = if current_section == Some(SectionKind::Cancelled) {
+            &mut current_cancelled
+        } else {
+            &mut current_changes
+        };
+
+        // Indented lines under a change are its subtask bullets, then its
+        // notes once a blank line or other text appears.
+        if line.starts_with([' ', '\t'])
+            && current_section.is_some()
+            && let Some(change) = entries.last_mut()
+        {
+            match (&mut change.notes, trimmed.strip_prefix("- ")) {
+                (None, Some(_)) if blank_before == 0 => {
+                    change.subtasks.extend(parse_bullet_change(
+                        trimmed,
+                        SectionKind::Inline,
+                        current_date,
+                    ));
+                }
+                (notes, _) => {
+                    let notes = notes.get_or_insert_default();
+                    if !notes.is_empty() {
+                        notes.push_str(&"\n".repeat(blank_before + 1));
+                    }
+                    notes.push_str(strip_note_indent(line).trim_end());
+                }
+            }
             continue;
         }
 
-        if let Some(priority) = current_priority
-            && let Some(change) = parse_bullet_change(trimmed, priority)
+        if let Some(section) = current_section
+            && let Some(change) = parse_bullet_change(trimmed, section, current_date)
             && current_version.is_some()
         {
-            current_changes.push(change);
+            entries.push(change);
         }
     }
 
     if let Some(version) = current_version.take() {
         let refs: Vec<&Change> = current_changes.iter().collect();
-        releases.push(Release::from_changes(version, current_date, refs));
+        releases.push(
+            Release::from_changes(version, current_date, refs).with_cancelled(current_cancelled),
+        );
     }
 
     Ok(Log {
@@ -135,12 +316,32 @@ pub fn from_markdown(content: &str) -> Result<Log> {
 #[derive(Debug, Default)]
 struct PreservedChangelogSections {
     intro: String,
+    unreleased: String,
     top_level_sections: Vec<String>,
     release_extras: BTreeMap<Version, String>,
+    links: String,
 }
 
 fn preserved_changelog_sections(content: &str) -> PreservedChangelogSections {
-    let lines: Vec<&str> = content.lines().collect();
+    let mut lines: Vec<&str> = content
+        .lines()
+        .filter(|line| !is_compare_link(line))
+        .collect();
+
+    // Trailing link reference definitions belong to the document, not the
+    // last release, so keep them at the bottom.
+    let mut end = lines.len();
+    while end > 0 && (lines[end - 1].trim().is_empty() || is_link_definition(lines[end - 1])) {
+        end -= 1;
+    }
+    let links = lines[end..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    lines.truncate(end);
+
     let mut i = 0;
 
     if lines
@@ -165,6 +366,7 @@ fn preserved_changelog_sections(content: &str) -> PreservedChangelogSections {
         i += 1;
     }
 
+    let mut unreleased = String::new();
     let mut top_level_sections = Vec::new();
     let mut release_extras = BTreeMap::new();
     while i < lines.len() {
@@ -180,6 +382,8 @@ fn preserved_changelog_sections(content: &str) -> PreservedChangelogSections {
             if !extra.trim().is_empty() {
                 release_extras.insert(version, extra);
             }
+        } else if is_unreleased_header(block[0].trim()) {
+            unreleased = block[1..].join("\n").trim().to_string();
         } else {
             let section = block.join("\n").trim().to_string();
             if !section.is_empty() {
@@ -190,8 +394,10 @@ fn preserved_changelog_sections(content: &str) -> PreservedChangelogSections {
 
     PreservedChangelogSections {
         intro: intro.join("\n"),
+        unreleased,
         top_level_sections,
         release_extras,
+        links,
     }
 }
 
@@ -200,7 +406,7 @@ fn preserved_release_extra(block: &[&str]) -> String {
     let mut i = 1;
 
     while i < block.len() {
-        if parse_priority_header(block[i].trim()).is_some() {
+        if parse_section_header(block[i].trim()).is_some() {
             i += 1;
             while i < block.len() && !block[i].starts_with("### ") {
                 i += 1;
@@ -212,7 +418,7 @@ fn preserved_release_extra(block: &[&str]) -> String {
         i += 1;
         while i < block.len()
             && !block[i].starts_with("### ")
-            && parse_priority_header(block[i].trim()).is_none()
+            && parse_section_header(block[i].trim()).is_none()
         {
             i += 1;
         }
@@ -226,50 +432,288 @@ fn preserved_release_extra(block: &[&str]) -> String {
     extra.join("\n\n")
 }
 
-fn release_to_markdown(release: &Release) -> String {
+fn release_to_markdown(release: &Release, format: &Changelog) -> String {
     let mut output = String::new();
 
-    output.push_str(&format!(
-        "## {} — {}\n\n",
-        release.version,
-        release.date.format("%Y-%m-%d")
-    ));
+    match format.style {
+        ChangelogStyle::Tally => output.push_str(&format!(
+            "## {} — {}\n\n",
+            release.version,
+            release.date.format("%Y-%m-%d")
+        )),
+        ChangelogStyle::KeepAChangelog => output.push_str(&format!(
+            "## [{}] - {}\n\n",
+            release.version,
+            release.date.format("%Y-%m-%d")
+        )),
+    }
 
-    for (priority, section_name) in [
-        (Priority::High, "High Priority"),
-        (Priority::Medium, "Changes"),
-        (Priority::Low, "Minor Changes"),
-    ] {
-        if let Some(changes) = release.changes_by_priority.get(&priority)
-            && !changes.is_empty()
-        {
-            output.push_str(&format!("### {}\n\n", section_name));
-
-            for change in changes {
-                let tags = if change.tags.is_empty() {
-                    String::new()
-                } else {
-                    format!(" `{}`", change.tags.join("`, `"))
-                };
-
-                let commit = change
-                    .commit
-                    .as_ref()
-                    .map(|c| format!(" ([`{}`])", &c[..7.min(c.len())]))
-                    .unwrap_or_default();
-
-                output.push_str(&format!("- {}{}{}\n", change.description, tags, commit));
-            }
+    for (section_name, kind, changes) in release_sections(release, format) {
+        if changes.is_empty() {
+            continue;
+        }
 
-            output.push('\n');
+        output.push_str(&format!("### {}\n\n", section_name));
+        for change in changes {
+            output.push_str(&change_bullet(change, kind));
         }
+        output.push('\n');
+    }
+
+    if !release.cancelled.is_empty() {
+        output.push_str(&format!("### {}\n\n", CANCELLED_SECTION));
+        for change in &release.cancelled {
+            output.push_str(&change_bullet(change, SectionKind::Cancelled));
+        }
+        output.push('\n');
     }
 
     output
 }
 
+fn release_sections<'a>(
+    release: &'a Release,
+    format: &Changelog,
+) -> Vec<(&'static str, SectionKind, Vec<&'a Change>)> {
+    let ordered = [Priority::High, Priority::Medium, Priority::Low]
+        .into_iter()
+        .filter_map(|priority| release.changes_by_priority.get(&priority))
+        .flatten();
+
+    match (format.style, format.grouping) {
+        (ChangelogStyle::Tally, ChangelogGrouping::Priority) => [
+            (Priority::High, "High Priority"),
+            (Priority::Medium, "Changes"),
+            (Priority::Low, "Minor Changes"),
+        ]
+        .into_iter()
+        .map(|(priority, section_name)| {
+            let changes = release
+                .changes_by_priority
+                .get(&priority)
+                .map(|changes| changes.iter().collect())
+                .unwrap_or_default();
+            (section_name, SectionKind::Priority(priority), changes)
+        })
+        .collect(),
+        (ChangelogStyle::Tally, ChangelogGrouping::Conventional) => {
+            let mut sections = vec![(BREAKING_SECTION, SectionKind::Breaking, Vec::new())];
+            sections.extend(
+                CONVENTIONAL_SECTIONS
+                    .into_iter()
+                    .map(|(kind, section_name)| {
+                        (section_name, SectionKind::Conventional(kind), Vec::new())
+                    }),
+            );
+            sections.push((OTHER_SECTION, SectionKind::Inline, Vec::new()));
+
+            for change in ordered {
+                let kind = conventional_section(change);
+                if let Some((_, _, changes)) = sections.iter_mut().find(|(_, k, _)| *k == kind) {
+                    changes.push(change);
+                }
+            }
+            sections
+        }
+        (ChangelogStyle::KeepAChangelog, _) => {
+            let mut sections: Vec<_> = KEEP_A_CHANGELOG_SECTIONS
+                .into_iter()
+                .map(|section_name| (section_name, SectionKind::Inline, Vec::new()))
+                .collect();
+            for change in ordered {
+                let section_name = keep_a_changelog_section(change);
+                if let Some((_, _, changes)) = sections
+                    .iter_mut()
+                    .find(|(name, _, _)| *name == section_name)
+                {
+                    changes.push(change);
+                }
+            }
+            sections
+        }
+    }
+}
+
+/// Pick the conventional section for a change; breaking changes of every
+/// type share one section and unknown types fall back to "Other Changes".
+fn conventional_section(change: &Change) -> SectionKind {
+    if conventional_commit::has_breaking_note(&change.description) {
+        return SectionKind::Breaking;
+    }
+
+    let Some(commit) = conventional_commit::parse(&change.description) else {
+        return SectionKind::Inline;
+    };
+    if commit.breaking {
+        return SectionKind::Breaking;
+    }
+
+    CONVENTIONAL_SECTIONS
+        .into_iter()
+        .find(|(kind, _)| *kind == commit.kind)
+        .map_or(SectionKind::Inline, |(kind, _)| {
+            SectionKind::Conventional(kind)
+        })
+}
+
+/// Pick the Keep a Changelog section for a change from its tags, then its
+/// conventional-commit type, falling back to "Changed".
+fn keep_a_changelog_section(change: &Change) -> &'static str {
+    let by_tag = change
+        .tags
+        .iter()
+        .find_map(|tag| match tag.to_ascii_lowercase().as_str() {
+            "added" | "feature" | "feat" => Some("Added"),
+            "changed" | "change" => Some("Changed"),
+            "deprecated" | "deprecation" => Some("Deprecated"),
+            "removed" | "removal" => Some("Removed"),
+            "fixed" | "fix" | "bug" | "bugfix" => Some("Fixed"),
+            "security" => Some("Security"),
+            _ => None,
+        });
+    if let Some(section_name) = by_tag {
+        return section_name;
+    }
+
+    match conventional_commit::parse(&change.description)
+        .map(|commit| commit.kind.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("feat") => "Added",
+        Some("fix") => "Fixed",
+        Some("security") => "Security",
+        Some("deprecate") => "Deprecated",
+        Some("remove") | Some("revert") => "Removed",
+        _ => "Changed",
+    }
+}
+
+fn change_bullet(change: &Change, section: SectionKind) -> String {
+    let mut bullet = format!("- {}\n", bullet_line(change, section));
+    // Subtasks keep their own priority inline, whatever the section.
+    for subtask in &change.subtasks {
+        bullet.push_str(&format!(
+            "  - {}\n",
+            bullet_line(subtask, SectionKind::Inline)
+        ));
+    }
+    if let Some(notes) = &change.notes {
+        bullet.push('\n');
+        for line in notes.lines() {
+            if line.trim().is_empty() {
+                bullet.push('\n');
+            } else {
+                bullet.push_str(&format!("  {}\n", line));
+            }
+        }
+    }
+    bullet
+}
+
+/// A change's description with its priority, tags, commit and metadata, as
+/// `parse_bullet_change` reads them back.
+fn bullet_line(change: &Change, section: SectionKind) -> String {
+    let inline_priority = !matches!(section, SectionKind::Priority(_));
+    let priority = match change.priority {
+        Priority::High if inline_priority => " (high)",
+        Priority::Low if inline_priority => " (low)",
+        _ => "",
+    };
+
+    let tags = if change.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" `{}`", change.tags.join("`, `"))
+    };
+
+    let commit = change
+        .commit
+        .as_ref()
+        .map(|c| format!(" ([`{}`])", &c[..7.min(c.len())]))
+        .unwrap_or_default();
+
+    let fields: Vec<String> = change
+        .id
+        .iter()
+        .map(|id| format!("id: {}", id))
+        .chain(
+            change
+                .meta
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, escape_meta(value))),
+        )
+        .collect();
+    let metadata = if fields.is_empty() {
+        String::new()
+    } else {
+        format!(" <!-- {} -->", fields.join("; "))
+    };
+
+    format!(
+        "{}{}{}{}{}",
+        bullet_text(&change.description, section),
+        priority,
+        tags,
+        commit,
+        metadata
+    )
+}
+
+/// The description as shown under `section`; `description_from_bullet`
+/// reverses it.
+fn bullet_text(description: &str, section: SectionKind) -> String {
+    let Some(commit) = conventional_commit::parse(description) else {
+        return description.to_string();
+    };
+
+    match section {
+        SectionKind::Conventional(_) => match commit.scope {
+            Some(scope) => format!("**{}:** {}", scope, commit.subject),
+            None => commit.subject.to_string(),
+        },
+        SectionKind::Breaking => {
+            let (prefix, _) = description.split_once(':').unwrap_or_default();
+            format!("**{}:** {}", prefix, commit.subject)
+        }
+        SectionKind::Priority(_) | SectionKind::Inline | SectionKind::Cancelled => {
+            description.to_string()
+        }
+    }
+}
+
+fn description_from_bullet(text: String, section: SectionKind) -> String {
+    let bold_prefix = text
+        .strip_prefix("**")
+        .and_then(|rest| rest.split_once(":** "));
+
+    match (section, bold_prefix) {
+        (SectionKind::Conventional(kind), Some((scope, subject))) => {
+            format!("{}({}): {}", kind, scope, subject)
+        }
+        (SectionKind::Conventional(kind), None) => format!("{}: {}", kind, text),
+        (SectionKind::Breaking, Some((prefix, subject))) => format!("{}: {}", prefix, subject),
+        _ => text,
+    }
+}
+
+/// A notes line without the two-space indent that nests it under its bullet.
+fn strip_note_indent(line: &str) -> &str {
+    line.strip_prefix("  ")
+        .or_else(|| line.strip_prefix('\t'))
+        .unwrap_or_else(|| line.trim_start())
+}
+
 fn parse_release_header(line: &str) -> Option<(Version, DateTime<Utc>)> {
     let body = line.strip_prefix("## ")?;
+
+    if let Some(rest) = body.strip_prefix('[') {
+        let (version_part, rest) = rest.split_once(']')?;
+        let date_part = rest.trim().trim_start_matches(['-', '—']).trim();
+        let version = Version::parse(version_part).ok()?;
+        let date = parse_date(date_part).unwrap_or_else(Utc::now);
+        return Some((version, date));
+    }
+
     let mut parts = body.split('—');
     let version_part = parts.next()?.trim();
     let date_part = parts.next().map(str::trim).unwrap_or_default();
@@ -279,6 +723,43 @@ fn parse_release_header(line: &str) -> Option<(Version, DateTime<Utc>)> {
     Some((version, date))
 }
 
+fn is_unreleased_header(line: &str) -> bool {
+    line.strip_prefix("## ")
+        .map(|rest| rest.trim().eq_ignore_ascii_case("[Unreleased]"))
+        .unwrap_or(false)
+}
+
+/// The label and target of a `[label]: target` link reference definition.
+fn link_definition(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim().strip_prefix('[')?;
+    let (label, target) = rest.split_once("]:")?;
+    let target = target.trim();
+    (!target.is_empty()).then_some((label, target))
+}
+
+fn is_link_definition(line: &str) -> bool {
+    link_definition(line).is_some()
+}
+
+/// Whether a link definition has the shape `render_compare_links` gives
+/// it, so it is regenerated on every save. Other links to a version, such
+/// as release notes, are the user's and are kept.
+fn is_compare_link(line: &str) -> bool {
+    let Some((label, target)) = link_definition(line) else {
+        return false;
+    };
+    let for_release = label.eq_ignore_ascii_case("unreleased") || Version::parse(label).is_ok();
+    let generated = match target.rsplit_once("/compare/") {
+        Some((_, range)) => range.split_once("...").is_some_and(|(from, to)| {
+            !from.is_empty() && !to.is_empty() && !range.contains(char::is_whitespace)
+        }),
+        None => target
+            .rsplit_once("/releases/tag/")
+            .is_some_and(|(_, tag)| !tag.is_empty() && !tag.contains(char::is_whitespace)),
+    };
+    for_release && generated
+}
+
 fn parse_priority_header(line: &str) -> Option<Priority> {
     match line {
         "### High Priority" => Some(Priority::High),
@@ -288,24 +769,123 @@ fn parse_priority_header(line: &str) -> Option<Priority> {
     }
 }
 
-fn parse_bullet_change(line: &str, priority: Priority) -> Option<Change> {
+fn parse_section_header(line: &str) -> Option<SectionKind> {
+    if let Some(priority) = parse_priority_header(line) {
+        return Some(SectionKind::Priority(priority));
+    }
+
+    let name = line.strip_prefix("### ")?.trim();
+    if name == BREAKING_SECTION {
+        return Some(SectionKind::Breaking);
+    }
+    if name == CANCELLED_SECTION {
+        return Some(SectionKind::Cancelled);
+    }
+    if let Some((kind, _)) = CONVENTIONAL_SECTIONS
+        .into_iter()
+        .find(|(_, section_name)| *section_name == name)
+    {
+        return Some(SectionKind::Conventional(kind));
+    }
+    (name == OTHER_SECTION || KEEP_A_CHANGELOG_SECTIONS.contains(&name))
+        .then_some(SectionKind::Inline)
+}
+
+/// A change read from its bullet. The changelog records only the release
+/// date, so that stands in for when it was completed.
+fn parse_bullet_change(
+    line: &str,
+    section: SectionKind,
+    completed_at: DateTime<Utc>,
+) -> Option<Change> {
     let body = line.strip_prefix("- ")?.trim();
     if body.is_empty() {
         return None;
     }
 
-    let (without_commit, commit) = extract_commit(body);
+    let (without_metadata, id, meta) = extract_metadata(body);
+    let (without_commit, commit) = extract_commit(without_metadata);
     let (description, tags) = extract_tags(without_commit);
+    let (description, priority) = match section {
+        SectionKind::Priority(priority) => (description, priority),
+        _ => extract_priority(&description),
+    };
+    let description = description_from_bullet(description, section);
 
     Some(Change {
+        id,
         description,
         priority,
         tags,
         commit,
-        completed_at: Utc::now(),
+        completed_at,
+        subtasks: Vec::new(),
+        notes: None,
+        meta,
     })
 }
 
+fn extract_priority(text: &str) -> (String, Priority) {
+    for (suffix, priority) in [
+        (" (high)", Priority::High),
+        (" (medium)", Priority::Medium),
+        (" (low)", Priority::Low),
+    ] {
+        if let Some(description) = text.strip_suffix(suffix) {
+            return (description.trim_end().to_string(), priority);
+        }
+    }
+    (text.to_string(), Priority::Medium)
+}
+
+/// Escape a metadata value so it cannot end its field (`;`) or the
+/// comment (`-->`).
+fn escape_meta(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace("-->", "--\\>")
+}
+
+/// Split a metadata comment into its fields, undoing `escape_meta`.
+fn meta_fields(body: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = body.chars();
+    while let Some(ch) = chars.next() {
+        let field = fields.last_mut().expect("fields is never empty");
+        match ch {
+            '\\' => field.extend(chars.next()),
+            ';' => fields.push(String::new()),
+            ch => field.push(ch),
+        }
+    }
+    fields
+}
+
+fn extract_metadata(text: &str) -> (&str, Option<String>, CustomMeta) {
+    let mut id = None;
+    let mut meta = CustomMeta::default();
+
+    // tally escapes `-->` in values, so a comment containing it was not
+    // written by tally and stays part of the text.
+    if let Some(body) = text.strip_suffix("-->")
+        && let Some(start) = body.rfind(" <!--")
+        && !body[start..].contains("-->")
+    {
+        for field in meta_fields(&body[start + 5..]) {
+            let Some((key, value)) = field.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "id" => id = Some(value.trim().to_string()),
+                key => meta.push(key, value.trim()),
+            }
+        }
+        return (text[..start].trim_end(), id, meta);
+    }
+    (text, id, meta)
+}
+
 fn extract_commit(text: &str) -> (&str, Option<String>) {
     if let Some(start) = text.rfind(" ([`")
         && let Some(end) = text[start + 4..].find("`])")
@@ -349,6 +929,7 @@ pub fn empty_log(project_name: &str) -> Log {
 
 pub fn normalize(log: &mut Log) {
     let mut merged: BTreeMap<Version, Vec<Change>> = BTreeMap::new();
+    let mut cancelled: BTreeMap<Version, Vec<Change>> = BTreeMap::new();
     let mut date_map: BTreeMap<Version, DateTime<Utc>> = BTreeMap::new();
 
     for release in &log.releases {
@@ -363,6 +944,10 @@ pub fn normalize(log: &mut Log) {
             .entry(release.version.clone())
             .or_default()
             .extend(changes);
+        cancelled
+            .entry(release.version.clone())
+            .or_default()
+            .extend(release.cancelled.iter().cloned());
 
         let entry = date_map
             .entry(release.version.clone())
@@ -381,6 +966,7 @@ pub fn normalize(log: &mut Log) {
                 *date_map.get(&version).unwrap_or(&Utc::now()),
                 refs,
             )
+            .with_cancelled(cancelled.remove(&version).unwrap_or_default())
         })
         .collect();
 
@@ -390,6 +976,7 @@ pub fn normalize(log: &mut Log) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::app_config::{Changelog, ChangelogGrouping, ChangelogStyle};
     use chrono::{TimeZone, Utc};
 
     fn change(
@@ -399,11 +986,15 @@ mod tests {
         commit: Option<&str>,
     ) -> Change {
         Change {
+            id: None,
             description: description.to_string(),
             priority,
             tags: tags.iter().map(|tag| (*tag).to_string()).collect(),
             commit: commit.map(str::to_string),
             completed_at: Utc.with_ymd_and_hms(2026, 2, 20, 10, 30, 0).unwrap(),
+            subtasks: Vec::new(),
+            notes: None,
+            meta: CustomMeta::default(),
         }
     }
 
@@ -425,10 +1016,11 @@ mod tests {
         );
 
         let release = Release {
-            version: Version::new(1, 2, 3, false),
+            version: Version::new(1, 2, 3),
             date: Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
             changes_by_priority: by_priority,
             changes_by_tag: BTreeMap::new(),
+            cancelled: Vec::new(),
         };
 
         let log = Log {
@@ -437,7 +1029,7 @@ mod tests {
             generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
         };
 
-        let markdown = to_markdown(&log);
+        let markdown = to_markdown(&log, &Changelog::default());
 
         assert!(markdown.contains("# Changelog — tally"));
         assert!(markdown.contains("*Generated on 2026-02-23*"));
@@ -449,13 +1041,232 @@ mod tests {
         assert!(markdown.contains("- Polish docs"));
     }
 
+    #[test]
+    fn from_markdown_round_trips_change_ids() {
+        let mut tracked = change("Track ids", Priority::Medium, &["core"], Some("abcdef1"));
+        tracked.id = Some("a1b2c3".to_string());
+        let release = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![&tracked],
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(markdown.contains("- Track ids `core` ([`abcdef1`]) <!-- id: a1b2c3 -->"));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let change = &parsed.releases[0].changes_by_priority[&Priority::Medium][0];
+        assert_eq!(change.id.as_deref(), Some("a1b2c3"));
+        assert_eq!(change.description, "Track ids");
+        assert_eq!(change.tags, vec!["core"]);
+        assert_eq!(change.commit.as_deref(), Some("abcdef1"));
+    }
+
+    #[test]
+    fn custom_meta_round_trips_in_the_metadata_comment() {
+        let mut tracked = change("Track owners", Priority::Medium, &[], None);
+        tracked.id = Some("a1b2c3".to_string());
+        tracked.meta.insert("owner", "alice");
+        tracked.meta.insert("issue", "GH-123");
+        let release = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![&tracked],
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(
+            markdown.contains("- Track owners <!-- id: a1b2c3; owner: alice; issue: GH-123 -->")
+        );
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let change = &parsed.releases[0].changes_by_priority[&Priority::Medium][0];
+        assert_eq!(change.id.as_deref(), Some("a1b2c3"));
+        assert_eq!(change.meta, tracked.meta);
+    }
+
+    #[test]
+    fn custom_meta_values_are_escaped_and_keep_key_case_and_repeats() {
+        let mut tracked = change("Track reviewers", Priority::Medium, &[], None);
+        tracked.meta.push("Reviewer", "bob");
+        tracked.meta.push("reviewer", "carol");
+        tracked.meta.push("note", "a; b: c --> d \\ e");
+        let release = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![&tracked],
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        let line = markdown
+            .lines()
+            .find(|line| line.starts_with("- Track reviewers"))
+            .unwrap();
+        assert_eq!(line.matches("-->").count(), 1);
+        assert!(line.contains("Reviewer: bob; reviewer: carol; "));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let change = &parsed.releases[0].changes_by_priority[&Priority::Medium][0];
+        assert_eq!(change.description, "Track reviewers");
+        assert_eq!(change.meta, tracked.meta);
+    }
+
+    #[test]
+    fn subtasks_render_as_nested_bullets_and_round_trip() {
+        let mut parent = change("Build parser", Priority::Medium, &[], None);
+        let mut tokenizer = change("Tokenizer", Priority::High, &["lexer"], None);
+        tokenizer.id = Some("b2c3d4".to_string());
+        tokenizer.meta.insert("owner", "alice");
+        parent.subtasks = vec![tokenizer, change("Grammar", Priority::Medium, &[], None)];
+        let sibling = change("Write docs", Priority::Medium, &[], None);
+        let release = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![&parent, &sibling],
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(markdown.contains(
+            "- Build parser\n  - Tokenizer (high) `lexer` <!-- id: b2c3d4; owner: alice -->\n  - Grammar\n- Write docs\n"
+        ));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let changes = &parsed.releases[0].changes_by_priority[&Priority::Medium];
+        assert_eq!(changes.len(), 2);
+        let subtasks = &changes[0].subtasks;
+        assert_eq!(subtasks.len(), 2);
+        assert_eq!(subtasks[0].description, "Tokenizer");
+        assert_eq!(subtasks[0].priority, Priority::High);
+        assert_eq!(subtasks[0].tags, vec!["lexer"]);
+        assert_eq!(subtasks[0].id.as_deref(), Some("b2c3d4"));
+        assert_eq!(subtasks[0].meta.get("owner"), Some("alice"));
+        assert_eq!(subtasks[1].description, "Grammar");
+        assert!(changes[1].subtasks.is_empty());
+    }
+
+    #[test]
+    fn notes_render_as_indented_paragraphs_and_round_trip() {
+        let mut parent = change("Build parser", Priority::Medium, &[], None);
+        parent.subtasks = vec![change("Tokenizer", Priority::Medium, &[], None)];
+        parent.notes = Some(
+            "Handles `#` in strings.\n\n```\n- [ ] not a task\n## not a release\n```".to_string(),
+        );
+        let docs = change("Write docs", Priority::Medium, &[], None);
+        let release = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![&parent, &docs],
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(markdown.contains(
+            "- Build parser\n  - Tokenizer\n\n  Handles `#` in strings.\n\n  ```\n  - [ ] not a task\n"
+        ));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let changes = &parsed.releases[0].changes_by_priority[&Priority::Medium];
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].subtasks[0].description, "Tokenizer");
+        assert_eq!(changes[0].notes, parent.notes);
+        assert_eq!(changes[1].notes, None);
+    }
+
+    #[test]
+    fn cancelled_tasks_render_in_their_own_section_and_round_trip() {
+        let shipped = change("Add export", Priority::Medium, &[], None);
+        let dropped = change("Legacy importer", Priority::High, &["import"], None);
+        let release = Release::from_changes(
+            Version::new(1, 1, 0),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap(),
+            vec![&shipped],
+        )
+        .with_cancelled(vec![dropped]);
+        let mut log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(markdown.contains(
+            "### Changes\n\n- Add export\n\n### Cancelled\n\n- Legacy importer (high) `import`\n"
+        ));
+
+        log = from_markdown(&markdown).unwrap();
+        normalize(&mut log);
+        let release = &log.releases[0];
+        assert_eq!(release.changes_by_priority[&Priority::Medium].len(), 1);
+        assert_eq!(release.cancelled.len(), 1);
+        assert_eq!(release.cancelled[0].description, "Legacy importer");
+        assert_eq!(release.cancelled[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn from_markdown_round_trips_prerelease_and_build_versions() {
+        let rc = Version::parse("1.2.3-rc.1").unwrap();
+        let build = Version::parse("1.2.3+build.5").unwrap();
+        let date = Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap();
+        let mut log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![
+                Release::from_changes(
+                    rc.clone(),
+                    date,
+                    vec![&change("Candidate", Priority::Medium, &[], None)],
+                ),
+                Release::from_changes(
+                    build.clone(),
+                    date,
+                    vec![&change("Final", Priority::Medium, &[], None)],
+                ),
+            ],
+            generated_at: date,
+        };
+        normalize(&mut log);
+
+        let markdown = to_markdown(&log, &Changelog::default());
+        assert!(markdown.contains("## 1.2.3+build.5 — 2026-02-21"));
+        assert!(markdown.contains("## 1.2.3-rc.1 — 2026-02-21"));
+        assert!(markdown.find("1.2.3+build.5") < markdown.find("1.2.3-rc.1"));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let versions: Vec<_> = parsed.releases.iter().map(|r| r.version.clone()).collect();
+        assert_eq!(versions, vec![build, rc]);
+    }
+
     #[test]
     fn serde_json_emits_valid_payload() {
         let release = Release {
-            version: Version::new(0, 5, 0, false),
+            version: Version::new(0, 5, 0),
             date: Utc.with_ymd_and_hms(2026, 2, 2, 0, 0, 0).unwrap(),
             changes_by_priority: BTreeMap::new(),
             changes_by_tag: BTreeMap::new(),
+            cancelled: Vec::new(),
         };
 
         let log = Log {
@@ -473,7 +1284,7 @@ mod tests {
 
     #[test]
     fn to_markdown_preserving_keeps_custom_release_notes_and_sections() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let release = Release::from_changes(
             version,
             Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
@@ -486,7 +1297,7 @@ mod tests {
         };
         let previous = "# Changelog — tally\n\n*Generated on 2026-02-22*\n\nIntro note.\n\n## 1.2.3 — 2026-02-21\n\n### Changes\n\n- Old generated change\n\n### Notes\n\nKeep this note.\n\n## Appendix\n\nKeep this too.\n";
 
-        let rendered = to_markdown_preserving(&log, Some(previous));
+        let rendered = to_markdown_preserving(&log, Some(previous), &Changelog::default());
 
         assert!(rendered.contains("Intro note."));
         assert!(rendered.contains("### Notes\n\nKeep this note."));
@@ -494,4 +1305,179 @@ mod tests {
         assert!(rendered.contains("- Managed change"));
         assert!(!rendered.contains("Old generated change"));
     }
+
+    fn keep_a_changelog(compare_url: Option<&str>) -> Changelog {
+        Changelog {
+            style: ChangelogStyle::KeepAChangelog,
+            compare_url: compare_url.map(str::to_string),
+            ..Changelog::default()
+        }
+    }
+
+    fn keep_a_changelog_log() -> Log {
+        let first = Release::from_changes(
+            Version::new(1, 0, 0),
+            Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap(),
+            vec![&change("Initial import", Priority::Medium, &[], None)],
+        );
+        let second = Release::from_changes(
+            Version::new(1, 1, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            vec![
+                &change("feat(cli): add export", Priority::Medium, &[], None),
+                &change("Handle empty history", Priority::High, &["bug"], None),
+                &change("Patch token leak", Priority::Low, &["security"], None),
+                &change("Tidy output", Priority::Medium, &[], None),
+            ],
+        );
+        let mut log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![first, second],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+        normalize(&mut log);
+        log
+    }
+
+    #[test]
+    fn to_markdown_keep_a_changelog_groups_by_tag_and_prefix() {
+        let markdown = to_markdown(
+            &keep_a_changelog_log(),
+            &keep_a_changelog(Some("https://github.com/acme/tally")),
+        );
+
+        assert!(markdown.starts_with("# Changelog\n\nAll notable changes"));
+        assert!(markdown.contains("## [Unreleased]\n\n## [1.1.0] - 2026-02-21"));
+        assert!(markdown.contains("### Added\n\n- feat(cli): add export\n"));
+        assert!(markdown.contains("### Changed\n\n- Tidy output\n"));
+        assert!(markdown.contains("### Fixed\n\n- Handle empty history (high) `bug`\n"));
+        assert!(markdown.contains("### Security\n\n- Patch token leak (low) `security`\n"));
+        assert!(!markdown.contains("### Removed"));
+        assert!(markdown.find("### Added") < markdown.find("### Changed"));
+        assert!(markdown.ends_with(
+            "[Unreleased]: https://github.com/acme/tally/compare/v1.1.0...HEAD\n\
+             [1.1.0]: https://github.com/acme/tally/compare/v1.0.0...v1.1.0\n\
+             [1.0.0]: https://github.com/acme/tally/releases/tag/v1.0.0\n"
+        ));
+    }
+
+    #[test]
+    fn from_markdown_parses_keep_a_changelog() {
+        let markdown = to_markdown(&keep_a_changelog_log(), &keep_a_changelog(None));
+        assert!(!markdown.contains("]: "));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let versions: Vec<_> = parsed
+            .releases
+            .iter()
+            .map(|r| r.version.to_string())
+            .collect();
+        assert_eq!(versions, vec!["1.1.0", "1.0.0"]);
+
+        let latest = &parsed.releases[0];
+        let high = &latest.changes_by_priority[&Priority::High];
+        assert_eq!(high[0].description, "Handle empty history");
+        assert_eq!(high[0].tags, vec!["bug"]);
+        assert_eq!(
+            latest.changes_by_priority[&Priority::Low][0].description,
+            "Patch token leak"
+        );
+        assert_eq!(latest.changes_by_priority[&Priority::Medium].len(), 2);
+        assert_eq!(
+            parsed.releases[1].date,
+            Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn to_markdown_preserving_keeps_unreleased_and_regenerates_links() {
+        let format = keep_a_changelog(Some("https://example.com/repo"));
+        let previous = "# Changelog\n\nOur notes.\n\n## [Unreleased]\n\n### Added\n\n- Draft entry\n\n## [1.0.0] - 2026-01-05\n\n### Changed\n\n- Old change\n\n[Unreleased]: https://old.example.com/compare/v1.0.0...HEAD\n[1.0.0]: https://old.example.com/releases/tag/v1.0.0\n[docs]: https://example.com/docs\n";
+
+        let rendered = to_markdown_preserving(&keep_a_changelog_log(), Some(previous), &format);
+
+        assert!(rendered.starts_with("# Changelog\n\nOur notes.\n\n## [Unreleased]\n\n### Added\n\n- Draft entry\n\n## [1.1.0]"));
+        assert!(rendered.contains("[docs]: https://example.com/docs"));
+        assert!(!rendered.contains("old.example.com"));
+        assert!(!rendered.contains("Old change"));
+        assert_eq!(rendered.matches("[Unreleased]: ").count(), 1);
+    }
+
+    #[test]
+    fn to_markdown_preserving_keeps_user_links_to_versions() {
+        let format = keep_a_changelog(Some("https://example.com/repo"));
+        let previous = "# Changelog\n\n## [1.0.0] - 2026-01-05\n\n### Changed\n\n- Old change\n\n[1.1.0]: https://example.com/notes/1.1.0\n[1.0.0]: https://example.com/repo/releases/tag/v1.0.0\n";
+
+        let rendered = to_markdown_preserving(&keep_a_changelog_log(), Some(previous), &format);
+
+        assert_eq!(rendered.matches("[1.1.0]: ").count(), 1);
+        assert!(rendered.contains("[1.1.0]: https://example.com/notes/1.1.0"));
+        assert_eq!(rendered.matches("[1.0.0]: ").count(), 1);
+        assert!(rendered.contains("[Unreleased]: https://example.com/repo/compare/v1.1.0...HEAD"));
+    }
+
+    #[test]
+    fn conventional_grouping_strips_prefixes_and_round_trips() {
+        let format = Changelog {
+            grouping: ChangelogGrouping::Conventional,
+            ..Changelog::default()
+        };
+        let descriptions = [
+            "feat(cli): add export",
+            "feat: support config files",
+            "fix(parser): handle empty history",
+            "feat(api)!: drop v1 routes",
+            "BREAKING CHANGE: rename binary",
+            "docs: explain IDs",
+            "Tidy output",
+        ];
+        let mut changes: Vec<Change> = descriptions
+            .iter()
+            .map(|description| change(description, Priority::Medium, &[], None))
+            .collect();
+        changes[2].priority = Priority::High;
+        changes[2].tags = vec!["bug".to_string()];
+        let release = Release::from_changes(
+            Version::new(0, 15, 0),
+            Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
+            changes.iter().collect(),
+        );
+        let log = Log {
+            project_name: "tally".to_string(),
+            releases: vec![release],
+            generated_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+        };
+
+        let markdown = to_markdown(&log, &format);
+
+        assert!(markdown.contains(
+            "### Breaking Changes\n\n- **feat(api)!:** drop v1 routes\n- BREAKING CHANGE: rename binary\n"
+        ));
+        assert!(
+            markdown.contains("### Features\n\n- **cli:** add export\n- support config files\n")
+        );
+        assert!(
+            markdown.contains("### Bug Fixes\n\n- **parser:** handle empty history (high) `bug`\n")
+        );
+        assert!(markdown.contains("### Documentation\n\n- explain IDs\n"));
+        assert!(markdown.contains("### Other Changes\n\n- Tidy output\n"));
+        assert!(markdown.find("### Breaking Changes") < markdown.find("### Features"));
+
+        let parsed = from_markdown(&markdown).unwrap();
+        let release = &parsed.releases[0];
+        let mut parsed_descriptions: Vec<_> = release
+            .changes_by_priority
+            .values()
+            .flatten()
+            .map(|change| change.description.as_str())
+            .collect();
+        parsed_descriptions.sort_unstable();
+        let mut expected = descriptions.to_vec();
+        expected.sort_unstable();
+        assert_eq!(parsed_descriptions, expected);
+        assert_eq!(
+            release.changes_by_priority[&Priority::High][0].description,
+            "fix(parser): handle empty history"
+        );
+    }
 }
@@ -1,15 +1,25 @@
 use crate::models::{
-    common::{Priority, Version},
-    tasks::{List, Task},
+    common::{Priority, TaskStatus, Version},
+    tasks::{CustomMeta, List, Recurrence, Task, task, tree},
 };
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::fmt::Write;
 
 struct TaskMetadata {
+    id: Option<String>,
     created_at_time: DateTime<Utc>,
     created_at_version: Option<Version>,
     created_at_commit: Option<String>,
+    created_by: Option<String>,
+    blocked_reason: Option<String>,
+    depends: Vec<String>,
+    due: Option<NaiveDate>,
+    scheduled: Option<NaiveDate>,
+    recur: Option<Recurrence>,
+    recurred: Option<String>,
+    meta: CustomMeta,
+    notes: Option<String>,
     completed_at_time: Option<DateTime<Utc>>,
     completed_at_version: Option<Version>,
     completed_at_commit: Option<String>,
@@ -63,23 +73,30 @@ fn render_header(list: &List) -> String {
 
 fn render_task_sections(list: &List) -> String {
     let mut output = String::new();
+    let tasks = &list.tasks;
 
-    let mut incomplete_tasks: Vec<_> = list.tasks.iter().filter(|t| !t.completed).collect();
-    let mut completed_tasks: Vec<_> = list.tasks.iter().filter(|t| t.completed).collect();
+    // Subtasks are written under their parent, whatever their own state.
+    let roots = (0..tasks.len()).filter(|&i| tree::is_root(tasks, i));
+    let (mut incomplete_tasks, mut completed_tasks): (Vec<_>, Vec<_>) =
+        roots.partition(|&i| !tasks[i].is_closed());
 
-    incomplete_tasks.sort_by_key(|t| t.created_at_time);
-    completed_tasks.sort_by_key(|t| t.completed_at_time.unwrap_or(t.created_at_time));
+    incomplete_tasks.sort_by_key(|&i| tasks[i].created_at_time);
+    completed_tasks.sort_by_key(|&i| {
+        tasks[i]
+            .completed_at_time
+            .unwrap_or(tasks[i].created_at_time)
+    });
 
     writeln!(&mut output, "\n## Tasks\n").unwrap();
-    for task in incomplete_tasks {
-        write_task(&mut output, task);
+    for index in incomplete_tasks {
+        write_task_tree(&mut output, tasks, index, 0);
         output += "\n";
     }
 
     if !completed_tasks.is_empty() {
         writeln!(&mut output, "\n## Completed\n").unwrap();
-        for task in completed_tasks {
-            write_task(&mut output, task);
+        for index in completed_tasks {
+            write_task_tree(&mut output, tasks, index, 0);
             output += "\n";
         }
     }
@@ -87,6 +104,16 @@ fn render_task_sections(list: &List) -> String {
     output
 }
 
+fn write_task_tree(output: &mut String, tasks: &[Task], index: usize, depth: usize) {
+    write_task_at(output, &tasks[index], depth);
+
+    let mut children = tree::children(tasks, index);
+    children.sort_by_key(|&i| tasks[i].created_at_time);
+    for child in children {
+        write_task_tree(output, tasks, child, depth + 1);
+    }
+}
+
 #[derive(Debug, Default)]
 struct PreservedTodoSections {
     preface: String,
@@ -170,6 +197,7 @@ pub fn deserialize(content: &str) -> Result<List> {
     let modified_at = modified_at.context("Missing @modified metadata")?;
 
     let mut tasks = Vec::new();
+    let mut parents = Vec::new();
 
     while idx < lines.len() {
         let line = lines[idx].trim();
@@ -179,23 +207,43 @@ pub fn deserialize(content: &str) -> Result<List> {
             while idx < lines.len() && !lines[idx].starts_with("## ") {
                 idx += 1;
             }
-            tasks.extend(parse_tasks(lines[start..idx].iter().copied())?);
+            parse_tasks(lines[start..idx].iter().copied(), &mut tasks, &mut parents)?;
         } else {
             idx += 1;
         }
     }
 
-    Ok(List {
+    let mut list = List {
         project_name,
-        project_version: Version::new(0, 1, 0, false),
+        project_version: Version::new(0, 1, 0),
         created_at,
         modified_at,
         tasks,
-    })
+    };
+    list.ensure_unique_ids();
+
+    // Link subtasks once IDs are final.
+    for (index, parent) in parents.into_iter().enumerate() {
+        list.tasks[index].parent = parent.map(|parent| list.tasks[parent].id.clone());
+    }
+
+    Ok(list)
+}
+
+/// Render a single task block as it appears in TODO.md.
+pub fn render_task(task: &Task) -> String {
+    let mut output = String::new();
+    write_task(&mut output, task);
+    output
 }
 
 fn write_task(output: &mut String, task: &Task) {
-    let checkbox = if task.completed { "x" } else { " " };
+    write_task_at(output, task, 0);
+}
+
+fn write_task_at(output: &mut String, task: &Task, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let checkbox = task.status.checkbox();
 
     let priority_str = match task.priority {
         Priority::High => " (high)",
@@ -218,15 +266,25 @@ fn write_task(output: &mut String, task: &Task) {
 
     writeln!(
         output,
-        "- [{}] {}{}{}",
-        checkbox, task.description, priority_str, tags_str
+        "{}- [{}] {}{}{}",
+        indent, checkbox, task.description, priority_str, tags_str
     )
     .unwrap();
 
-    write_task_metadata(output, task);
+    let mut metadata = String::new();
+    write_task_metadata(&mut metadata, task);
+    for line in metadata.lines() {
+        if line.is_empty() {
+            output.push('\n');
+        } else {
+            writeln!(output, "{}{}", indent, line).unwrap();
+        }
+    }
 }
 
 fn write_task_metadata(output: &mut String, task: &Task) {
+    writeln!(output, "      @id {}", task.id).unwrap();
+
     writeln!(
         output,
         "      @created {}",
@@ -242,7 +300,37 @@ fn write_task_metadata(output: &mut String, task: &Task) {
         writeln!(output, "      @created_commit {}", commit).unwrap();
     }
 
-    if task.completed {
+    if let Some(author) = &task.created_by {
+        writeln!(output, "      @created_by {}", author).unwrap();
+    }
+
+    if task.status == TaskStatus::Blocked
+        && let Some(reason) = &task.blocked_reason
+    {
+        writeln!(output, "      @blocked {}", reason).unwrap();
+    }
+
+    for dependency in &task.depends {
+        writeln!(output, "      @depends {}", dependency).unwrap();
+    }
+
+    if let Some(due) = &task.due {
+        writeln!(output, "      @due {}", due).unwrap();
+    }
+
+    if let Some(scheduled) = &task.scheduled {
+        writeln!(output, "      @scheduled {}", scheduled).unwrap();
+    }
+
+    if let Some(recur) = &task.recur {
+        writeln!(output, "      @recur {}", recur).unwrap();
+    }
+
+    if let Some(recurred) = &task.recurred {
+        writeln!(output, "      @recurred {}", recurred).unwrap();
+    }
+
+    if task.is_closed() {
         if let Some(completed_time) = &task.completed_at_time {
             writeln!(
                 output,
@@ -260,6 +348,22 @@ fn write_task_metadata(output: &mut String, task: &Task) {
             writeln!(output, "      @completed_commit {}", commit).unwrap();
         }
     }
+
+    for (key, value) in task.meta.iter() {
+        writeln!(output, "      @{} {}", key, value).unwrap();
+    }
+
+    // Notes follow a blank line so they are never read back as metadata.
+    if let Some(notes) = &task.notes {
+        output.push('\n');
+        for line in notes.lines() {
+            if line.trim().is_empty() {
+                output.push('\n');
+            } else {
+                writeln!(output, "      {}", line).unwrap();
+            }
+        }
+    }
 }
 
 fn format_date(dt: &DateTime<Utc>) -> String {
@@ -312,55 +416,127 @@ fn parse_date_line(line: &str) -> Result<DateTime<Utc>> {
     ))
 }
 
-fn parse_tasks<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<Task>> {
-    let mut tasks = Vec::new();
-    let mut current_task_lines = Vec::new();
+/// Parse task blocks, appending to `tasks`. Nested checkboxes become subtasks:
+/// `parents` gets the index of each task's parent in `tasks`.
+fn parse_tasks<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    tasks: &mut Vec<Task>,
+    parents: &mut Vec<Option<usize>>,
+) -> Result<()> {
+    // Open task blocks by checkbox indent, outermost first.
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut current: Option<(usize, Option<usize>, Vec<String>)> = None;
+    // Checkboxes inside a fenced code block in a task's notes are not tasks.
+    let mut in_fence = false;
+
+    let mut flush = |current: &mut Option<(usize, Option<usize>, Vec<String>)>,
+                     open: &mut Vec<(usize, usize)>|
+     -> Result<()> {
+        if let Some((indent, parent, block)) = current.take() {
+            let default_created = parent.map(|parent: usize| tasks[parent].created_at_time);
+            tasks.push(parse_task_block(&block, default_created)?);
+            parents.push(parent);
+            open.push((indent, tasks.len() - 1));
+        }
+        Ok(())
+    };
 
     for line in lines {
-        if line.starts_with("- [") {
-            if !current_task_lines.is_empty() {
-                tasks.push(parse_task(&current_task_lines)?);
-                current_task_lines.clear();
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        // Metadata and notes sit at least `NOTE_INDENT` deeper than their
+        // checkbox, so a checkbox-like line there is note text.
+        let in_notes = current
+            .as_ref()
+            .is_some_and(|(task_indent, _, _)| indent >= task_indent + NOTE_INDENT);
+        if is_checkbox_line(trimmed) && !in_fence && !in_notes {
+            flush(&mut current, &mut open)?;
+
+            while open
+                .last()
+                .is_some_and(|(open_indent, _)| *open_indent >= indent)
+            {
+                open.pop();
+            }
+            let parent = open.last().map(|(_, index)| *index);
+            current = Some((indent, parent, vec![trimmed.to_string()]));
+        } else if let Some((_, _, block)) = current.as_mut() {
+            if trimmed.starts_with("```") {
+                in_fence = !in_fence;
             }
-            current_task_lines.push(line.to_string());
-        } else if !current_task_lines.is_empty() && !line.trim().is_empty() {
-            current_task_lines.push(line.to_string());
+            block.push(line.to_string());
         }
     }
 
-    if !current_task_lines.is_empty() {
-        tasks.push(parse_task(&current_task_lines)?);
-    }
+    flush(&mut current, &mut open)
+}
+
+/// How far metadata and notes are indented past their task's checkbox.
+const NOTE_INDENT: usize = 6;
+
+/// Whether a line starts a task: `- [` followed by one mark and `]`, so
+/// Markdown links such as `- [docs](...)` are not tasks.
+fn is_checkbox_line(trimmed: &str) -> bool {
+    let mut chars = match trimmed.strip_prefix("- [") {
+        Some(rest) => rest.chars(),
+        None => return false,
+    };
+    chars.next().is_some() && chars.next() == Some(']')
+}
 
-    Ok(tasks)
+/// Parse one task block: the checkbox line followed by its metadata lines
+/// and any notes.
+pub fn parse_task(lines: &[String]) -> Result<Task> {
+    parse_task_block(lines, None)
 }
 
-fn parse_task(lines: &[String]) -> Result<Task> {
+/// Like `parse_task`, but a subtask written without `@created` takes its parent's.
+fn parse_task_block(lines: &[String], default_created: Option<DateTime<Utc>>) -> Result<Task> {
     if lines.is_empty() {
         anyhow::bail!("Empty task lines");
     }
 
     let first_line = &lines[0];
-    let completed = first_line.contains("[x]") || first_line.contains("[X]");
-
-    let content = first_line
-        .trim_start_matches("- [")
-        .trim_start_matches(['x', 'X', ' '])
-        .trim_start_matches(']')
+    let checkbox = first_line
+        .strip_prefix("- [")
+        .context("Task line must start with a checkbox")?;
+    let mut chars = checkbox.chars();
+    let mark = chars.next().context("Task checkbox is not closed")?;
+    let status = TaskStatus::from_checkbox(mark)
+        .with_context(|| format!("Unknown task checkbox '[{}]'", mark))?;
+    let content = chars
+        .as_str()
+        .strip_prefix(']')
+        .context("Task checkbox is not closed")?
         .trim();
 
     let (description, priority, tags) = parse_task_content(content)?;
 
-    let metadata = parse_task_metadata(&lines[1..])?;
+    let metadata = parse_task_metadata(&lines[1..], default_created)?;
 
     Ok(Task {
+        id: metadata
+            .id
+            .unwrap_or_else(|| task::derive_id(&description, &metadata.created_at_time)),
         description,
         priority,
         tags,
-        completed,
+        status,
+        blocked_reason: metadata
+            .blocked_reason
+            .filter(|_| status == TaskStatus::Blocked),
+        parent: None,
+        depends: metadata.depends,
+        due: metadata.due,
+        scheduled: metadata.scheduled,
+        recur: metadata.recur,
+        recurred: metadata.recurred,
+        meta: metadata.meta,
+        notes: metadata.notes,
         created_at_time: metadata.created_at_time,
         created_at_version: metadata.created_at_version,
         created_at_commit: metadata.created_at_commit,
+        created_by: metadata.created_by,
         completed_at_time: metadata.completed_at_time,
         completed_at_version: metadata.completed_at_version,
         completed_at_commit: metadata.completed_at_commit,
@@ -395,42 +571,129 @@ fn parse_task_content(content: &str) -> Result<(String, Priority, Vec<String>)>
     Ok((description, priority, tags))
 }
 
-fn parse_task_metadata(lines: &[String]) -> Result<TaskMetadata> {
+fn parse_task_metadata(
+    lines: &[String],
+    default_created: Option<DateTime<Utc>>,
+) -> Result<TaskMetadata> {
+    let mut id = None;
     let mut created_at_time = None;
     let mut created_at_version = None;
     let mut created_at_commit = None;
+    let mut created_by = None;
+    let mut blocked_reason = None;
+    let mut depends = Vec::new();
+    let mut due = None;
+    let mut scheduled = None;
+    let mut recur = None;
+    let mut recurred = None;
     let mut completed_at_time = None;
     let mut completed_at_version = None;
     let mut completed_at_commit = None;
+    let mut meta = CustomMeta::default();
 
-    for line in lines {
+    // Metadata lines come first; the first other line starts the notes.
+    let notes_start = lines
+        .iter()
+        .position(|line| !line.trim_start().starts_with('@'))
+        .unwrap_or(lines.len());
+
+    for line in &lines[..notes_start] {
         let line = line.trim();
 
-        if let Some(value) = line.strip_prefix("@created ") {
+        if let Some(value) = line.strip_prefix("@id ") {
+            id = Some(task::normalize_id(value));
+        } else if let Some(value) = line.strip_prefix("@created ") {
             created_at_time = Some(parse_datetime(value.trim())?);
         } else if let Some(value) = line.strip_prefix("@created_version ") {
             created_at_version = Some(Version::parse(value.trim())?);
         } else if let Some(value) = line.strip_prefix("@created_commit ") {
             created_at_commit = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("@created_by ") {
+            created_by = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("@blocked ") {
+            blocked_reason = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("@depends ") {
+            for id in value.split([',', ' ']).filter(|id| !id.trim().is_empty()) {
+                let id = task::normalize_id(id);
+                if !depends.contains(&id) {
+                    depends.push(id);
+                }
+            }
+        } else if let Some(value) = line.strip_prefix("@due ") {
+            due = Some(parse_date(value.trim())?);
+        } else if let Some(value) = line.strip_prefix("@scheduled ") {
+            scheduled = Some(parse_date(value.trim())?);
+        } else if let Some(value) = line.strip_prefix("@recur ") {
+            recur = Some(value.parse()?);
+        } else if let Some(value) = line.strip_prefix("@recurred ") {
+            recurred = Some(task::normalize_id(value));
         } else if let Some(value) = line.strip_prefix("@completed ") {
             completed_at_time = Some(parse_datetime(value.trim())?);
         } else if let Some(value) = line.strip_prefix("@completed_version ") {
             completed_at_version = Some(Version::parse(value.trim())?);
         } else if let Some(value) = line.strip_prefix("@completed_commit ") {
             completed_at_commit = Some(value.trim().to_string());
+        } else if let Some((key, value)) = custom_meta_line(line) {
+            // A repeated key keeps every value.
+            meta.push(key, value);
         }
     }
 
     Ok(TaskMetadata {
-        created_at_time: created_at_time.context("Task missing @created metadata")?,
+        id,
+        created_at_time: created_at_time
+            .or(default_created)
+            .context("Task missing @created metadata")?,
         created_at_version,
         created_at_commit,
+        created_by,
+        blocked_reason,
+        depends,
+        due,
+        scheduled,
+        recur,
+        recurred,
+        meta,
+        notes: parse_notes(&lines[notes_start..]),
         completed_at_time,
         completed_at_version,
         completed_at_commit,
     })
 }
 
+/// The key and value of an `@key value` line tally does not read itself.
+fn custom_meta_line(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('@')?;
+    let (key, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    CustomMeta::key(key).map(|_| (key, value.trim()))
+}
+
+/// Notes lines with blank lines trimmed from both ends and the common
+/// indentation removed.
+fn parse_notes(lines: &[String]) -> Option<String> {
+    let start = lines.iter().position(|line| !line.trim().is_empty())?;
+    let end = lines.iter().rposition(|line| !line.trim().is_empty())? + 1;
+    let lines = &lines[start..end];
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    let notes = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(notes)
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").context("Failed to parse date")
+}
+
 fn parse_datetime(value: &str) -> Result<DateTime<Utc>> {
     let naive = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
         .context("Failed to parse datetime")?;
@@ -450,35 +713,57 @@ mod tests {
     fn serialize_deserialize_round_trip_preserves_task_metadata() {
         let mut list = List {
             project_name: "tally".to_string(),
-            project_version: Version::new(0, 5, 0, false),
+            project_version: Version::new(0, 5, 0),
             created_at: Utc.with_ymd_and_hms(2026, 2, 20, 0, 0, 0).unwrap(),
             modified_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
             tasks: vec![],
         };
 
         list.tasks.push(Task {
+            id: "c0ffee".to_string(),
             description: "config support".to_string(),
             priority: Priority::Low,
             tags: vec!["feature".to_string()],
-            completed: false,
+            status: TaskStatus::Open,
+            blocked_reason: None,
+            parent: None,
+            depends: Vec::new(),
+            due: None,
+            scheduled: None,
+            recur: None,
+            recurred: None,
+            notes: None,
+            meta: CustomMeta::default(),
             created_at_time: Utc.with_ymd_and_hms(2026, 2, 20, 8, 15, 0).unwrap(),
-            created_at_version: Some(Version::new(0, 4, 0, false)),
+            created_at_version: Some(Version::new(0, 4, 0)),
             created_at_commit: Some("abc1234".to_string()),
+            created_by: None,
             completed_at_time: None,
             completed_at_version: None,
             completed_at_commit: None,
         });
 
         list.tasks.push(Task {
+            id: "b0a710".to_string(),
             description: "fix duplication in history".to_string(),
             priority: Priority::High,
             tags: vec![],
-            completed: true,
+            status: TaskStatus::Done,
+            blocked_reason: None,
+            parent: None,
+            depends: Vec::new(),
+            due: None,
+            scheduled: None,
+            recur: None,
+            recurred: None,
+            notes: None,
+            meta: CustomMeta::default(),
             created_at_time: Utc.with_ymd_and_hms(2026, 2, 19, 9, 0, 0).unwrap(),
             created_at_version: None,
             created_at_commit: None,
+            created_by: None,
             completed_at_time: Some(Utc.with_ymd_and_hms(2026, 2, 21, 9, 45, 0).unwrap()),
-            completed_at_version: Some(Version::new(0, 3, 2, false)),
+            completed_at_version: Some(Version::parse("0.3.2-rc.1").unwrap()),
             completed_at_commit: Some("a556fb5".to_string()),
         });
 
@@ -497,7 +782,8 @@ mod tests {
             .iter()
             .find(|task| task.description == "config support")
             .unwrap();
-        assert!(!config_task.completed);
+        assert_eq!(config_task.id, "c0ffee");
+        assert!(!config_task.is_done());
         assert_eq!(config_task.priority, Priority::Low);
         assert_eq!(config_task.tags, vec!["feature"]);
         assert_eq!(config_task.created_at_commit.as_deref(), Some("abc1234"));
@@ -515,7 +801,7 @@ mod tests {
             .iter()
             .find(|task| task.description == "fix duplication in history")
             .unwrap();
-        assert!(fixed_task.completed);
+        assert!(fixed_task.is_done());
         assert_eq!(fixed_task.priority, Priority::High);
         assert_eq!(fixed_task.completed_at_commit.as_deref(), Some("a556fb5"));
         assert_eq!(
@@ -524,7 +810,7 @@ mod tests {
                 .as_ref()
                 .map(ToString::to_string)
                 .as_deref(),
-            Some("0.3.2")
+            Some("0.3.2-rc.1")
         );
     }
 
@@ -539,6 +825,18 @@ mod tests {
         assert_eq!(parsed.tasks[0].description, "keep parser compatibility");
     }
 
+    #[test]
+    fn deserialize_derives_stable_ids_for_tasks_without_id_metadata() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] same text\n      @created 2026-02-20 10:00\n- [ ] same text\n      @created 2026-02-20 10:00\n";
+
+        let first = deserialize(content).unwrap();
+        let second = deserialize(content).unwrap();
+
+        assert_eq!(first.tasks[0].id, second.tasks[0].id);
+        assert_eq!(first.tasks[1].id, second.tasks[1].id);
+        assert_ne!(first.tasks[0].id, first.tasks[1].id);
+    }
+
     #[test]
     fn deserialize_requires_created_metadata() {
         let content = "# TODO — demo\n\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] missing metadata\n      @created 2026-02-20 10:00\n";
@@ -551,19 +849,30 @@ mod tests {
     fn serialize_preserving_keeps_unmanaged_sections() {
         let mut list = List {
             project_name: "demo".to_string(),
-            project_version: Version::new(0, 1, 0, false),
+            project_version: Version::new(0, 1, 0),
             created_at: Utc.with_ymd_and_hms(2026, 2, 20, 0, 0, 0).unwrap(),
             modified_at: Utc.with_ymd_and_hms(2026, 2, 21, 0, 0, 0).unwrap(),
             tasks: vec![],
         };
         list.tasks.push(Task {
+            id: "5eed01".to_string(),
             description: "managed task".to_string(),
             priority: Priority::Medium,
             tags: vec![],
-            completed: false,
+            status: TaskStatus::Open,
+            blocked_reason: None,
+            parent: None,
+            depends: Vec::new(),
+            due: None,
+            scheduled: None,
+            recur: None,
+            recurred: None,
+            notes: None,
+            meta: CustomMeta::default(),
             created_at_time: Utc.with_ymd_and_hms(2026, 2, 20, 8, 15, 0).unwrap(),
             created_at_version: None,
             created_at_commit: None,
+            created_by: None,
             completed_at_time: None,
             completed_at_version: None,
             completed_at_commit: None,
@@ -577,4 +886,189 @@ mod tests {
         assert!(rendered.contains("managed task"));
         assert!(!rendered.contains("old task"));
     }
+
+    #[test]
+    fn nested_checkboxes_round_trip_as_subtasks() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] build parser\n      @id 0a0a0a\n      @created 2026-02-20 10:00\n  - [x] tokenizer\n    - [ ] edge cases\n  - [ ] grammar\n        @id 0b0b0b\n        @created 2026-02-21 09:00\n- [ ] docs\n      @created 2026-02-20 11:00\n";
+
+        let parsed = deserialize(content).unwrap();
+        let find = |description: &str| {
+            parsed
+                .tasks
+                .iter()
+                .find(|task| task.description == description)
+                .unwrap()
+        };
+
+        assert_eq!(parsed.tasks.len(), 5);
+        assert_eq!(find("tokenizer").parent.as_deref(), Some("0a0a0a"));
+        assert!(find("tokenizer").is_done());
+        assert_eq!(
+            find("tokenizer").created_at_time,
+            find("build parser").created_at_time
+        );
+        assert_eq!(
+            find("edge cases").parent.as_deref(),
+            Some(find("tokenizer").id.as_str())
+        );
+        assert_eq!(find("grammar").parent.as_deref(), Some("0a0a0a"));
+        assert_eq!(find("docs").parent, None);
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains("  - [x] tokenizer\n"));
+        assert!(rendered.contains("    - [ ] edge cases\n"));
+        assert!(rendered.contains("  - [ ] grammar\n        @id 0b0b0b\n"));
+
+        let reparsed = deserialize(&rendered).unwrap();
+        assert_eq!(reparsed.tasks.len(), 5);
+        for task in &parsed.tasks {
+            let same = reparsed.tasks.iter().find(|t| t.id == task.id).unwrap();
+            assert_eq!(same.parent, task.parent);
+        }
+    }
+
+    #[test]
+    fn checkbox_variants_map_to_task_status() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [/] parser\n      @created 2026-02-20 10:00\n- [~] release\n      @created 2026-02-20 11:00\n      @blocked waiting on review\n\n## Completed\n\n- [-] legacy importer\n      @created 2026-02-19 10:00\n      @completed 2026-02-21 09:00\n";
+
+        let parsed = deserialize(content).unwrap();
+        let statuses: Vec<_> = parsed.tasks.iter().map(|task| task.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TaskStatus::InProgress,
+                TaskStatus::Blocked,
+                TaskStatus::Cancelled
+            ]
+        );
+        assert_eq!(
+            parsed.tasks[1].blocked_reason.as_deref(),
+            Some("waiting on review")
+        );
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains("- [/] parser\n"));
+        assert!(rendered.contains("- [~] release\n"));
+        assert!(rendered.contains("      @blocked waiting on review\n"));
+        assert!(rendered.contains("## Completed\n\n- [-] legacy importer\n"));
+        assert!(rendered.contains("      @completed 2026-02-21 09:00\n"));
+        assert!(deserialize(&content.replace("[/]", "[?]")).is_err());
+    }
+
+    #[test]
+    fn due_and_scheduled_dates_round_trip() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] ship beta\n      @id 0c0c0c\n      @created 2026-02-20 10:00\n      @due 2026-11-01\n      @scheduled 2026-10-20\n";
+
+        let parsed = deserialize(content).unwrap();
+        let task = &parsed.tasks[0];
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(task.scheduled, NaiveDate::from_ymd_opt(2026, 10, 20));
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains("      @due 2026-11-01\n      @scheduled 2026-10-20\n"));
+        assert!(deserialize(&content.replace("@scheduled", "@recur")).is_err());
+        let recurring =
+            deserialize(&content.replace("@scheduled 2026-10-20", "@recur every 2W")).unwrap();
+        assert!(serialize(&recurring).contains("      @due 2026-11-01\n      @recur every 2w\n"));
+    }
+
+    #[test]
+    fn notes_below_metadata_round_trip() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] fix parser\n      @id 0c0c0c\n      @created 2026-02-20 10:00\n      The tokenizer fails on:\n\n      ```\n      - [ ] nested\n          indented\n      ```\n  - [ ] subtask\n        @created 2026-02-20 11:00\n- [ ] docs\n      @created 2026-02-20 12:00\n";
+
+        let parsed = deserialize(content).unwrap();
+        assert_eq!(parsed.tasks.len(), 3);
+        let notes = "The tokenizer fails on:\n\n```\n- [ ] nested\n    indented\n```";
+        assert_eq!(parsed.tasks[0].notes.as_deref(), Some(notes));
+        assert_eq!(parsed.tasks[1].parent.as_deref(), Some("0c0c0c"));
+        assert_eq!(parsed.tasks[2].notes, None);
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains(
+            "      @created 2026-02-20 10:00\n\n      The tokenizer fails on:\n\n      ```\n"
+        ));
+
+        let reparsed = deserialize(&rendered).unwrap();
+        assert_eq!(reparsed.tasks.len(), 3);
+        assert_eq!(reparsed.tasks[0].notes.as_deref(), Some(notes));
+    }
+
+    #[test]
+    fn links_and_checkboxes_in_notes_stay_notes() {
+        let mut list = List {
+            project_name: "tally".to_string(),
+            project_version: Version::new(0, 5, 0),
+            created_at: Utc.with_ymd_and_hms(2026, 2, 20, 0, 0, 0).unwrap(),
+            modified_at: Utc.with_ymd_and_hms(2026, 2, 23, 0, 0, 0).unwrap(),
+            tasks: vec![],
+        };
+        let notes = "See:\n- [docs](http://example.com)\n- [ ] not a task\n- [x] nor this";
+        let mut parent = Task::new("fix parser", Priority::Medium, vec![]);
+        parent.notes = Some(notes.to_string());
+        let mut child = Task::new("add tests", Priority::Medium, vec![]);
+        child.parent = Some(parent.id.clone());
+        child.notes = Some("- [?] still a note".to_string());
+        list.tasks.push(parent);
+        list.tasks.push(child);
+
+        let parsed = deserialize(&serialize(&list)).unwrap();
+        assert_eq!(parsed.tasks.len(), 2);
+        assert_eq!(parsed.tasks[0].notes.as_deref(), Some(notes));
+        assert_eq!(parsed.tasks[1].parent, Some(parsed.tasks[0].id.clone()));
+        assert_eq!(parsed.tasks[1].notes.as_deref(), Some("- [?] still a note"));
+    }
+
+    #[test]
+    fn duplicated_ids_are_rehashed_with_their_dependents() {
+        // The second pair is a pasted copy of the first.
+        let pair = "- [ ] schema\n      @id aaaaaa\n      @created 2026-02-20 10:00\n- [ ] migrate\n      @id bbbbbb\n      @created 2026-02-20 10:00\n      @depends aaaaaa\n";
+        let content = format!(
+            "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n{pair}{pair}"
+        );
+
+        let parsed = deserialize(&content).unwrap();
+        let ids: Vec<_> = parsed.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(&ids[..2], ["aaaaaa", "bbbbbb"]);
+        assert_ne!(ids[2], "aaaaaa");
+        assert_eq!(parsed.tasks[1].depends, vec!["aaaaaa"]);
+        assert_eq!(parsed.tasks[3].depends, vec![ids[2].to_string()]);
+    }
+
+    #[test]
+    fn custom_meta_lines_round_trip_in_original_order() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] ship beta\n      @id 0c0c0c\n      @Owner alice\n      @created 2026-02-20 10:00\n      @issue GH-123\n      @estimate 3h\n      @reviewer bob\n      @reviewer carol\n";
+
+        let parsed = deserialize(content).unwrap();
+        let meta: Vec<_> = parsed.tasks[0].meta.iter().collect();
+        assert_eq!(
+            meta,
+            vec![
+                ("Owner", "alice"),
+                ("issue", "GH-123"),
+                ("estimate", "3h"),
+                ("reviewer", "bob"),
+                ("reviewer", "carol"),
+            ]
+        );
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains(
+            "      @Owner alice\n      @issue GH-123\n      @estimate 3h\n      @reviewer bob\n      @reviewer carol\n"
+        ));
+        assert_eq!(
+            deserialize(&rendered).unwrap().tasks[0].meta,
+            parsed.tasks[0].meta
+        );
+    }
+
+    #[test]
+    fn depends_lines_round_trip() {
+        let content = "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] ship beta\n      @id 0c0c0c\n      @created 2026-02-20 10:00\n      @depends #0A0A0A, 0b0b0b\n      @depends 0b0b0b\n";
+
+        let parsed = deserialize(content).unwrap();
+        assert_eq!(parsed.tasks[0].depends, vec!["0a0a0a", "0b0b0b"]);
+
+        let rendered = serialize(&parsed);
+        assert!(rendered.contains("      @depends 0a0a0a\n      @depends 0b0b0b\n"));
+    }
 }
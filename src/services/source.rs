@@ -28,8 +28,10 @@ impl SourceTodo {
     }
 }
 
+/// Scan tracked files for markers, skipping tally's own files in `excluded`.
 pub fn scan_project(
     root: &Path,
+    excluded: &[&str],
     todo_markers: &[String],
     done_markers: &[String],
 ) -> Result<Vec<SourceTodo>> {
@@ -45,7 +47,7 @@ pub fn scan_project(
     let mut todos = Vec::new();
     let files = String::from_utf8(output.stdout)?;
     for rel_path in files.lines().map(str::trim).filter(|l| !l.is_empty()) {
-        if rel_path == "TODO.md" || rel_path == "CHANGELOG.md" {
+        if excluded.contains(&rel_path) {
             continue;
         }
 
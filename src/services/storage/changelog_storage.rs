@@ -1,23 +1,66 @@
+use crate::models::app_config::{Changelog, ChangelogStyle};
 use crate::models::changes::{Change, Log, Release};
 use crate::models::common::Version;
+use crate::models::tasks::task;
+use crate::services::git;
 use crate::services::serializers::changelog_serializer;
-use crate::utils::matching::{MatchCandidate, select_unambiguous};
+use crate::services::storage::lock::{self, ProjectLock};
+use crate::services::storage::task_storage::ListStorage;
+use crate::utils::atomic_file;
+use crate::utils::matching::{MatchCandidate, select_unambiguous, tag_query_score};
+use crate::utils::project_paths::ProjectPaths;
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use fuzzy_matcher::FuzzyMatcher;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 pub struct ChangelogStorage {
     changelog: Log,
     changelog_file: PathBuf,
+    format: Changelog,
+    _lock: Option<Rc<ProjectLock>>,
 }
 
 impl ChangelogStorage {
-    pub fn new(changelog_file: &Path, project_name: &str) -> Result<Self> {
+    /// Open the project's changelog, holding the project lock until the
+    /// storage is dropped.
+    pub fn for_project(
+        paths: &ProjectPaths,
+        project_name: &str,
+        format: &Changelog,
+    ) -> Result<Self> {
+        let lock = lock::acquire(&paths.state_dir)?;
+        let mut storage = Self::new(&paths.changelog_file, project_name, format)?;
+        storage._lock = Some(lock);
+        Ok(storage)
+    }
+
+    /// Open the project's changelog for a command that only reads it, without
+    /// taking the project lock.
+    pub fn for_reading(
+        paths: &ProjectPaths,
+        project_name: &str,
+        format: &Changelog,
+    ) -> Result<Self> {
+        Self::new(&paths.changelog_file, project_name, format)
+    }
+
+    pub fn new(changelog_file: &Path, project_name: &str, format: &Changelog) -> Result<Self> {
+        let mut format = format.clone();
+        if format.style == ChangelogStyle::KeepAChangelog
+            && format.compare_url.is_none()
+            && let Some(root) = changelog_file.parent()
+        {
+            format.compare_url = git::remote_web_url(root);
+        }
+
         let mut storage = Self {
             changelog: changelog_serializer::empty_log(project_name),
             changelog_file: changelog_file.to_path_buf(),
+            format,
+            _lock: None,
         };
         storage.load()?;
         Ok(storage)
@@ -29,27 +72,52 @@ impl ChangelogStorage {
         }
 
         let content = fs::read_to_string(&self.changelog_file)?;
+        let project_name = std::mem::take(&mut self.changelog.project_name);
         self.changelog = changelog_serializer::from_markdown(&content)?;
+        if self.changelog.project_name == "Untitled" {
+            // Keep a Changelog headers don't carry the project name.
+            self.changelog.project_name = project_name;
+        }
         changelog_serializer::normalize(&mut self.changelog);
         Ok(())
     }
 
     pub fn save(&mut self) -> Result<()> {
-        if let Some(parent) = self.changelog_file.parent() {
-            fs::create_dir_all(parent)?;
-        }
+        let markdown = self.render();
+        atomic_file::write(&self.changelog_file, &markdown)
+    }
+
+    /// Save the changelog together with a TODO list, so that either both
+    /// files are updated or neither is.
+    pub fn save_with_list(&mut self, list: &mut ListStorage) -> Result<()> {
+        let markdown = self.render();
+        let todo = list.render()?;
+        atomic_file::write_all(&[
+            (list.path(), todo.as_str()),
+            (&self.changelog_file, markdown.as_str()),
+        ])?;
+        list.mark_saved(todo);
+        Ok(())
+    }
+
+    fn render(&mut self) -> String {
         self.changelog.generated_at = Utc::now();
         let previous = fs::read_to_string(&self.changelog_file).ok();
-        let markdown =
-            changelog_serializer::to_markdown_preserving(&self.changelog, previous.as_deref());
-        fs::write(&self.changelog_file, markdown)?;
-        Ok(())
+        changelog_serializer::to_markdown_preserving(
+            &self.changelog,
+            previous.as_deref(),
+            &self.format,
+        )
     }
 
     pub fn log(&self) -> &Log {
         &self.changelog
     }
 
+    pub fn log_mut(&mut self) -> &mut Log {
+        &mut self.changelog
+    }
+
     pub fn merge_changes_for_version(&mut self, version: &Version, changes: Vec<Change>) -> usize {
         let release_index = self
             .changelog
@@ -69,7 +137,9 @@ impl ChangelogStorage {
 
             for change in changes {
                 let duplicate = existing.iter().any(|e| {
-                    if let (Some(a), Some(b)) = (&e.commit, &change.commit) {
+                    if let (Some(a), Some(b)) = (&e.id, &change.id) {
+                        a == b
+                    } else if let (Some(a), Some(b)) = (&e.commit, &change.commit) {
                         a == b
                     } else {
                         e.description == change.description && e.tags == change.tags
@@ -81,8 +151,7 @@ impl ChangelogStorage {
                 }
             }
 
-            let refs: Vec<&Change> = existing.iter().collect();
-            self.changelog.releases[idx] = Release::from_changes(version.clone(), Utc::now(), refs);
+            self.set_release_changes(idx, existing, Utc::now());
         } else {
             inserted = changes.len();
             let refs: Vec<&Change> = changes.iter().collect();
@@ -104,6 +173,76 @@ impl ChangelogStorage {
         tag_filter: Option<&[String]>,
         min_score: f64,
     ) -> Result<Option<(Version, Change)>> {
+        let Some((ri, ci, version, removed)) =
+            self.select_change(query, version, tag_filter, min_score)?
+        else {
+            return Ok(None);
+        };
+
+        let mut changes: Vec<Change> = self.changelog.releases[ri]
+            .changes_by_priority
+            .values()
+            .flat_map(|v| v.iter().cloned())
+            .collect();
+        if ci >= changes.len() {
+            return Ok(None);
+        }
+        changes.remove(ci);
+        self.set_release_changes(ri, changes, Utc::now());
+
+        Ok(Some((version, removed)))
+    }
+
+    /// Find a released change by fuzzy description match without removing it
+    pub fn find_change(
+        &self,
+        query: &str,
+        version: Option<&Version>,
+        tag_filter: Option<&[String]>,
+        min_score: f64,
+    ) -> Result<Option<(Version, Change)>> {
+        Ok(self
+            .select_change(query, version, tag_filter, min_score)?
+            .map(|(_, _, version, change)| (version, change)))
+    }
+
+    /// Replace a released change in `version`, keeping the release date
+    pub fn replace_change(
+        &mut self,
+        version: &Version,
+        original: &Change,
+        updated: Change,
+    ) -> bool {
+        let Some(ri) = self
+            .changelog
+            .releases
+            .iter()
+            .position(|release| &release.version == version)
+        else {
+            return false;
+        };
+
+        let mut changes = self.changelog.releases[ri].changes();
+        let Some(ci) = changes
+            .iter()
+            .position(|change| same_change(change, original))
+        else {
+            return false;
+        };
+        changes[ci] = updated;
+
+        let date = self.changelog.releases[ri].date;
+        self.set_release_changes(ri, changes, date);
+        true
+    }
+
+    fn select_change(
+        &self,
+        query: &str,
+        version: Option<&Version>,
+        tag_filter: Option<&[String]>,
+        min_score: f64,
+    ) -> Result<Option<(usize, usize, Version, Change)>> {
         use fuzzy_matcher::skim::SkimMatcherV2;
 
         let matcher = SkimMatcherV2::default();
@@ -135,7 +274,11 @@ impl ChangelogStorage {
                 let maybe_score = if query.is_empty() {
                     version_only_query.then_some(i64::MAX)
                 } else {
-                    release_match_score(&matcher, &change.description, query)
+                    release_match_score(&matcher, &change.description, query).or_else(|| {
+                        tag_query_score(query, &change.tags, |word| {
+                            release_match_score(&matcher, &change.description, word)
+                        })
+                    })
                 };
                 let Some(score) = maybe_score else {
                     continue;
@@ -150,29 +293,45 @@ impl ChangelogStorage {
             }
         }
 
-        let Some(best) = select_unambiguous(candidates, min_score, query)? else {
-            return Ok(None);
-        };
-        let (ri, ci, version, removed) = best.value;
+        Ok(select_unambiguous(candidates, min_score, query)?.map(|best| best.value))
+    }
 
-        let mut changes: Vec<Change> = self.changelog.releases[ri]
-            .changes_by_priority
-            .values()
-            .flat_map(|v| v.iter().cloned())
-            .collect();
-        if ci >= changes.len() {
-            return Ok(None);
-        }
-        changes.remove(ci);
+    /// Find a released change by its task ID
+    pub fn find_change_by_id(&self, id: &str) -> Option<(Version, Change)> {
+        let id = task::normalize_id(id);
+        self.changelog.releases.iter().find_map(|release| {
+            release
+                .changes()
+                .into_iter()
+                .find(|change| change.id.as_deref() == Some(id.as_str()))
+                .map(|change| (release.version.clone(), change))
+        })
+    }
 
-        if changes.is_empty() {
-            self.changelog.releases.remove(ri);
-        } else {
-            let refs: Vec<&Change> = changes.iter().collect();
-            self.changelog.releases[ri] = Release::from_changes(version.clone(), Utc::now(), refs);
-        }
+    /// Remove a released change by its task ID, optionally within one version
+    pub fn remove_change_by_id(
+        &mut self,
+        id: &str,
+        version: Option<&Version>,
+    ) -> Option<(Version, Change)> {
+        let id = task::normalize_id(id);
+        let ri = self.changelog.releases.iter().position(|release| {
+            version.is_none_or(|v| &release.version == v)
+                && release
+                    .changes()
+                    .iter()
+                    .any(|change| change.id.as_deref() == Some(id.as_str()))
+        })?;
+
+        let version = self.changelog.releases[ri].version.clone();
+        let (removed, remaining): (Vec<_>, Vec<_>) = self.changelog.releases[ri]
+            .changes()
+            .into_iter()
+            .partition(|change| change.id.as_deref() == Some(id.as_str()));
 
-        Ok(Some((version, removed)))
+        self.set_release_changes(ri, remaining, Utc::now());
+
+        removed.into_iter().next().map(|change| (version, change))
     }
 
     pub fn remove_changes(
@@ -212,7 +371,7 @@ impl ChangelogStorage {
             return Vec::new();
         };
 
-        let changes = ordered_changes(&self.changelog.releases[ri]);
+        let changes = self.changelog.releases[ri].changes();
         let (removed, remaining): (Vec<_>, Vec<_>) = changes
             .into_iter()
             .partition(|change| tag_matches(change, tag_filter));
@@ -221,30 +380,72 @@ impl ChangelogStorage {
             return Vec::new();
         }
 
-        if remaining.is_empty() {
-            self.changelog.releases.remove(ri);
-        } else {
-            let refs: Vec<&Change> = remaining.iter().collect();
-            self.changelog.releases[ri] = Release::from_changes(version.clone(), Utc::now(), refs);
-        }
+        self.set_release_changes(ri, remaining, Utc::now());
 
         removed
             .into_iter()
             .map(|change| (version.clone(), change))
             .collect()
     }
+
+    /// Record cancelled tasks under `version`, creating the release if needed.
+    pub fn merge_cancelled_for_version(&mut self, version: &Version, cancelled: Vec<Change>) {
+        if cancelled.is_empty() {
+            return;
+        }
+
+        let release = match self
+            .changelog
+            .releases
+            .iter()
+            .position(|r| &r.version == version)
+        {
+            Some(idx) => &mut self.changelog.releases[idx],
+            None => {
+                self.changelog.releases.push(Release::from_changes(
+                    version.clone(),
+                    Utc::now(),
+                    Vec::new(),
+                ));
+                self.changelog
+                    .releases
+                    .sort_by(|a, b| b.version.cmp(&a.version));
+                self.changelog
+                    .releases
+                    .iter_mut()
+                    .find(|r| &r.version == version)
+                    .expect("release was just added")
+            }
+        };
+
+        for change in cancelled {
+            if !release.cancelled.iter().any(|e| same_change(e, &change)) {
+                release.cancelled.push(change);
+            }
+        }
+    }
+
+    /// Replace the changes of release `ri`, keeping its cancelled entries.
+    /// A release left with no entries at all is dropped.
+    fn set_release_changes(&mut self, ri: usize, changes: Vec<Change>, date: DateTime<Utc>) {
+        let release = &self.changelog.releases[ri];
+        if changes.is_empty() && release.cancelled.is_empty() {
+            self.changelog.releases.remove(ri);
+            return;
+        }
+
+        let cancelled = release.cancelled.clone();
+        let refs: Vec<&Change> = changes.iter().collect();
+        self.changelog.releases[ri] =
+            Release::from_changes(release.version.clone(), date, refs).with_cancelled(cancelled);
+    }
 }
 
-fn ordered_changes(release: &Release) -> Vec<Change> {
-    [
-        crate::models::common::Priority::High,
-        crate::models::common::Priority::Medium,
-        crate::models::common::Priority::Low,
-    ]
-    .into_iter()
-    .filter_map(|priority| release.changes_by_priority.get(&priority))
-    .flat_map(|changes| changes.iter().cloned())
-    .collect()
+fn same_change(a: &Change, b: &Change) -> bool {
+    match (&a.id, &b.id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.description == b.description && a.tags == b.tags && a.commit == b.commit,
+    }
 }
 
 fn tag_matches(change: &Change, tag_filter: Option<&[String]>) -> bool {
@@ -318,15 +519,20 @@ fn parse_release_tag(word: &str) -> Option<Version> {
 mod tests {
     use super::*;
     use crate::models::common::Priority;
+    use crate::models::tasks::CustomMeta;
     use chrono::{TimeZone, Utc};
 
     fn change(description: &str, tags: &[&str]) -> Change {
         Change {
+            id: None,
             description: description.to_string(),
             priority: Priority::Medium,
             tags: tags.iter().map(|tag| tag.to_string()).collect(),
             commit: None,
             completed_at: Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+            subtasks: Vec::new(),
+            notes: None,
+            meta: CustomMeta::default(),
         }
     }
 
@@ -338,6 +544,8 @@ mod tests {
                 generated_at: Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
             },
             changelog_file: PathBuf::from("CHANGELOG.md"),
+            format: Changelog::default(),
+            _lock: None,
         }
     }
 
@@ -345,7 +553,15 @@ mod tests {
     fn release_query_extracts_semver_tag_and_keeps_task_text() {
         let query = ReleaseQuery::from_query("v1.2.3 fix parser");
 
-        assert_eq!(query.version, Some(Version::new(1, 2, 3, false)));
+        assert_eq!(query.version, Some(Version::new(1, 2, 3)));
+        assert_eq!(query.text, "fix parser");
+    }
+
+    #[test]
+    fn release_query_extracts_prerelease_tag() {
+        let query = ReleaseQuery::from_query("v1.2.3-rc.1 fix parser");
+
+        assert_eq!(query.version, Some(Version::parse("1.2.3-rc.1").unwrap()));
         assert_eq!(query.text, "fix parser");
     }
 
@@ -359,8 +575,8 @@ mod tests {
 
     #[test]
     fn remove_change_uses_semver_tag_as_release_filter() {
-        let v1 = Version::new(1, 0, 0, false);
-        let v2 = Version::new(2, 0, 0, false);
+        let v1 = Version::new(1, 0, 0);
+        let v2 = Version::new(2, 0, 0);
         let mut storage = storage_with_releases(vec![
             Release::from_changes(v1.clone(), Utc::now(), vec![&change("fix parser", &[])]),
             Release::from_changes(v2.clone(), Utc::now(), vec![&change("fix parser", &[])]),
@@ -379,7 +595,7 @@ mod tests {
 
     #[test]
     fn remove_change_rejects_ambiguous_literal_prefix() {
-        let version = Version::new(1, 14, 0, false);
+        let version = Version::new(1, 14, 0);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version.clone(),
             Utc::now(),
@@ -422,6 +638,8 @@ mod tests {
         let mut storage = ChangelogStorage {
             changelog: log,
             changelog_file: PathBuf::from("CHANGELOG.md"),
+            format: Changelog::default(),
+            _lock: None,
         };
 
         let err = storage
@@ -433,7 +651,7 @@ mod tests {
 
     #[test]
     fn remove_change_rejects_ambiguous_version_only_query() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version.clone(),
             Utc::now(),
@@ -453,7 +671,7 @@ mod tests {
 
     #[test]
     fn remove_changes_with_semver_tag_removes_whole_release() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version.clone(),
             Utc::now(),
@@ -474,7 +692,7 @@ mod tests {
 
     #[test]
     fn remove_changes_with_semver_tag_respects_tag_filter() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version.clone(),
             Utc::now(),
@@ -493,14 +711,38 @@ mod tests {
         assert_eq!(removed[0].0, version);
         assert_eq!(removed[0].1.description, "first released task");
 
-        let remaining = ordered_changes(&storage.changelog.releases[0]);
+        let remaining = storage.changelog.releases[0].changes();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].description, "second released task");
+    }
+
+    #[test]
+    fn remove_change_by_id_removes_only_that_change() {
+        let version = Version::new(1, 2, 3);
+        let mut first = change("first released task", &[]);
+        first.id = Some("a1b2c3".to_string());
+        let mut storage = storage_with_releases(vec![Release::from_changes(
+            version.clone(),
+            Utc::now(),
+            vec![&first, &change("second released task", &[])],
+        )]);
+
+        assert!(storage.find_change_by_id("#A1B2C3").is_some());
+        let (removed_version, removed) = storage
+            .remove_change_by_id("a1b2c3", None)
+            .expect("change with id");
+
+        assert_eq!(removed_version, version);
+        assert_eq!(removed.description, "first released task");
+        let remaining = storage.changelog.releases[0].changes();
         assert_eq!(remaining.len(), 1);
         assert_eq!(remaining[0].description, "second released task");
+        assert!(storage.remove_change_by_id("a1b2c3", None).is_none());
     }
 
     #[test]
     fn empty_query_without_release_filter_does_not_match() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version,
             Utc::now(),
@@ -518,7 +760,7 @@ mod tests {
 
     #[test]
     fn remove_change_respects_released_min_score() {
-        let version = Version::new(1, 2, 3, false);
+        let version = Version::new(1, 2, 3);
         let mut storage = storage_with_releases(vec![Release::from_changes(
             version,
             Utc::now(),
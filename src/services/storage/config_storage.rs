@@ -1,40 +1,88 @@
 use anyhow::Result;
-#[cfg(test)]
+use serde::Serialize;
 use serde::de::DeserializeOwned;
-#[cfg(test)]
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 use toml;
 
 use crate::models::AppConfig;
+use crate::utils::project_paths::ProjectPaths;
+
+/// Which layer an effective configuration value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigOrigin {
+    Default,
+    Global,
+    Project,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::Global => write!(f, "global"),
+            ConfigOrigin::Project => write!(f, "project"),
+        }
+    }
+}
 
 pub struct ConfigStorage {
     config: AppConfig,
     config_file: PathBuf,
+    project_config_file: Option<PathBuf>,
+    origins: BTreeMap<String, ConfigOrigin>,
 }
 
 impl ConfigStorage {
     pub fn new(config_file: &Path) -> Result<Self> {
+        Self::layered(config_file, None)
+    }
+
+    /// Load the global config with the project's `.tally.toml` layered over it.
+    pub fn for_project(paths: &ProjectPaths) -> Result<Self> {
+        Self::layered(&paths.config_file, Some(&paths.project_config_file))
+    }
+
+    fn layered(config_file: &Path, project_config_file: Option<&Path>) -> Result<Self> {
         let mut storage = Self {
             config: AppConfig::default(),
             config_file: config_file.to_path_buf(),
+            project_config_file: project_config_file.map(Path::to_path_buf),
+            origins: BTreeMap::new(),
         };
 
         storage.load_config()?;
         Ok(storage)
     }
 
-    /// Loads configuration from config.toml, or creates default if it doesn't exist.
+    /// Loads configuration from config.toml, or creates default if it doesn't exist,
+    /// then overrides it key by key with the project config when there is one.
     pub fn load_config(&mut self) -> Result<()> {
+        self.origins = flatten(&toml::Value::try_from(AppConfig::default())?)
+            .into_keys()
+            .map(|key| (key, ConfigOrigin::Default))
+            .collect();
+
         if !self.config_file.exists() {
-            return self.save_config();
+            self.config = AppConfig::default();
+            self.save_config()?;
         }
 
-        let toml_str = fs::read_to_string(&self.config_file)
-            .map_err(|e| io::Error::other(format!("Failed to load config: {}", e)))?;
+        let mut merged = read_layer(&self.config_file)?;
+        self.record_origins(&merged, ConfigOrigin::Global);
+
+        if let Some(project_file) = self.project_config_file.as_deref()
+            && project_file.exists()
+        {
+            let project = read_layer(project_file)?;
+            self.record_origins(&project, ConfigOrigin::Project);
+            merge_values(&mut merged, project);
+        }
 
-        self.config = toml::from_str(&toml_str).map_err(|e| {
+        self.config = merged.try_into().map_err(|e| {
             io::Error::other(format!(
                 "Failed to parse config {}: {}",
                 self.config_file.display(),
@@ -44,6 +92,37 @@ impl ConfigStorage {
         Ok(())
     }
 
+    fn record_origins(&mut self, layer: &toml::Value, origin: ConfigOrigin) {
+        for key in flatten(layer).into_keys() {
+            self.origins.insert(key, origin);
+        }
+    }
+
+    /// The file a non-default origin refers to.
+    pub fn origin_file(&self, origin: ConfigOrigin) -> Option<&Path> {
+        match origin {
+            ConfigOrigin::Default => None,
+            ConfigOrigin::Global => Some(&self.config_file),
+            ConfigOrigin::Project => self.project_config_file.as_deref(),
+        }
+    }
+
+    /// Every effective value as a dotted key, with the layer it came from.
+    pub fn effective_values(&self) -> Result<BTreeMap<String, (toml::Value, ConfigOrigin)>> {
+        let values = flatten(&toml::Value::try_from(&self.config)?);
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| {
+                let origin = self
+                    .origins
+                    .get(&key)
+                    .copied()
+                    .unwrap_or(ConfigOrigin::Default);
+                (key, (value, origin))
+            })
+            .collect())
+    }
+
     /// Saves the current configuration to config.toml.
     pub fn save_config(&self) -> Result<()> {
         let toml = toml::to_string_pretty(&self.config)
@@ -65,133 +144,277 @@ impl ConfigStorage {
         &self.config
     }
 
-    /// Sets a configuration value at the given key path (e.g., "github.api_token").
-    #[cfg(test)]
+    /// Sets a configuration value in the global config (e.g., "git.done_prefix").
     pub fn try_set_value(&mut self, key_path: &str, value: &str) -> Result<(), String> {
-        if key_path.trim().is_empty() {
-            return Err("Key path cannot be empty".into());
-        }
-
-        let mut root = toml::Value::try_from(&self.config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-        let keys: Vec<&str> = key_path.split('.').collect();
-        let (path, final_key) = keys.split_at(keys.len() - 1);
-
-        let mut current = root.as_table_mut().ok_or("Config root is not a table")?;
+        self.set_value(key_path, value, ConfigTarget::Global)
+    }
 
-        for key in path {
-            current = current
-                .get_mut(*key)
-                .and_then(toml::Value::as_table_mut)
-                .ok_or_else(|| format!("Key path not found: {}", key_path))?;
+    /// Sets a value in the target file after checking it against the `AppConfig` schema.
+    pub fn set_value(
+        &mut self,
+        key_path: &str,
+        value: &str,
+        target: ConfigTarget,
+    ) -> Result<(), String> {
+        check_key(key_path)?;
+        let path = self.target_file(target)?.to_path_buf();
+        let raw = read_raw(&path)?;
+
+        let mut first_error = None;
+        for candidate in convert_value(value) {
+            let mut updated = raw.clone();
+            insert_value(&mut updated, key_path, candidate)?;
+            match updated.clone().try_into::<AppConfig>() {
+                Ok(_) => {
+                    write_raw(&path, &updated)?;
+                    return self
+                        .load_config()
+                        .map_err(|e| format!("Failed to reload config: {}", e));
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e.to_string());
+                }
+            }
         }
 
-        let parsed_value = self.convert_value(value)?;
-        current.insert(final_key[0].to_string(), parsed_value);
+        Err(format!(
+            "Invalid value for {}: {}",
+            key_path,
+            first_error.unwrap_or_default().trim()
+        ))
+    }
 
-        self.config = root
-            .try_into()
-            .map_err(|e| format!("Failed to update config: {}", e))?;
+    /// Removes a value from the target file so the next layer applies again.
+    /// Returns whether the file had the key.
+    pub fn unset_value(&mut self, key_path: &str, target: ConfigTarget) -> Result<bool, String> {
+        check_key(key_path)?;
+        let path = self.target_file(target)?.to_path_buf();
+        let mut raw = read_raw(&path)?;
 
-        self.save_config()
-            .map_err(|e| format!("Failed to save config: {}", e))
+        if !remove_value(&mut raw, key_path) {
+            return Ok(false);
+        }
+        write_raw(&path, &raw)?;
+        self.load_config()
+            .map_err(|e| format!("Failed to reload config: {}", e))?;
+        Ok(true)
     }
 
-    /// Gets a configuration value at the given key path.
-    #[cfg(test)]
+    /// Gets an effective configuration value at the given key path.
     pub fn try_get_value<T>(&self, key_path: &str) -> Result<T, String>
     where
         T: DeserializeOwned,
     {
         let value = self.get_value(key_path)?;
         value
-            .clone()
             .try_into()
             .map_err(|e| format!("Failed to deserialize '{}': {}", key_path, e))
     }
 
-    #[cfg(test)]
-    fn get_value(&self, key_path: &str) -> Result<toml::Value, String> {
+    /// Gets an effective configuration value; known optional keys may be unset.
+    pub fn get_value(&self, key_path: &str) -> Result<toml::Value, String> {
+        check_key(key_path)?;
         let root = toml::Value::try_from(&self.config)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-        let mut current = &root;
-        for key in key_path.split('.') {
-            current = current
-                .get(key)
-                .ok_or_else(|| format!("Key path not found: {}", key_path))?;
-        }
+        lookup(&root, key_path)
+            .cloned()
+            .ok_or_else(|| format!("{} is not set", key_path))
+    }
 
-        Ok(current.clone())
+    /// Values written in the target file itself, as dotted keys.
+    pub fn target_values(
+        &self,
+        target: ConfigTarget,
+    ) -> Result<BTreeMap<String, toml::Value>, String> {
+        Ok(flatten(&read_raw(self.target_file(target)?)?))
     }
 
-    /// Gets all configuration keys and values as flattened dot-notation paths.
-    #[cfg(test)]
-    #[allow(dead_code)]
-    pub fn get_flattened_config(&self) -> HashMap<String, String> {
-        let root =
-            toml::Value::try_from(&self.config).unwrap_or(toml::Value::Table(Default::default()));
-        Self::flatten_value(&root, "", 10, 0)
+    fn target_file(&self, target: ConfigTarget) -> Result<&Path, String> {
+        match target {
+            ConfigTarget::Global => Ok(&self.config_file),
+            ConfigTarget::Project => self
+                .project_config_file
+                .as_deref()
+                .ok_or_else(|| "No project config file for this storage".to_string()),
+        }
     }
+}
 
-    #[cfg(test)]
-    fn flatten_value(
-        value: &toml::Value,
-        prefix: &str,
-        max_depth: usize,
-        current_depth: usize,
-    ) -> HashMap<String, String> {
-        let mut result = HashMap::new();
+/// Which file `set` and `unset` write to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigTarget {
+    Global,
+    Project,
+}
 
-        if current_depth >= max_depth {
-            return result;
+/// Keys without a default value, so they are absent from a serialized default config.
+const OPTIONAL_KEYS: [&str; 2] = ["preferences.editor", "changelog.compare_url"];
+
+/// Every key the `AppConfig` schema accepts.
+pub fn known_keys() -> Vec<String> {
+    let mut keys: Vec<String> = toml::Value::try_from(AppConfig::default())
+        .map(|defaults| flatten(&defaults).into_keys().collect())
+        .unwrap_or_default();
+    keys.extend(OPTIONAL_KEYS.iter().map(|key| key.to_string()));
+    keys.sort();
+    keys
+}
+
+fn check_key(key_path: &str) -> Result<(), String> {
+    if key_path.trim().is_empty() {
+        return Err("Key path cannot be empty".into());
+    }
+    if !known_keys().iter().any(|key| key == key_path) {
+        return Err(format!("Key path not found: {}", key_path));
+    }
+    Ok(())
+}
+
+/// Interpretations of a command-line value, tried in order against the schema.
+fn convert_value(value: &str) -> Vec<toml::Value> {
+    let mut candidates = Vec::new();
+
+    // Try TOML literal first
+    if let Ok(parsed) = value.parse::<toml::Value>() {
+        if let toml::Value::Integer(i) = parsed {
+            candidates.push(parsed.clone());
+            candidates.push(toml::Value::Float(i as f64));
+        } else {
+            candidates.push(parsed);
         }
+    }
 
-        match value {
-            toml::Value::String(s) => {
-                result.insert(prefix.to_string(), s.clone());
-            }
-            toml::Value::Integer(i) => {
-                result.insert(prefix.to_string(), i.to_string());
-            }
-            toml::Value::Float(f) => {
-                result.insert(prefix.to_string(), f.to_string());
+    // Then a plain string, or a comma-separated list of strings
+    candidates.push(toml::Value::String(value.to_string()));
+    candidates.push(toml::Value::Array(
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| toml::Value::String(item.to_string()))
+            .collect(),
+    ));
+    candidates
+}
+
+fn read_raw(path: &Path) -> Result<toml::Value, String> {
+    if !path.exists() {
+        return Ok(toml::Value::Table(Default::default()));
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to load config: {}", e))?;
+    toml::from_str(&content)
+        .map_err(|e| format!("Failed to parse config {}: {}", path.display(), e))
+}
+
+fn write_raw(path: &Path, value: &toml::Value) -> Result<(), String> {
+    let toml =
+        toml::to_string_pretty(value).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    fs::write(path, toml).map_err(|e| format!("Failed to save config: {}", e))
+}
+
+fn lookup<'a>(root: &'a toml::Value, key_path: &str) -> Option<&'a toml::Value> {
+    key_path
+        .split('.')
+        .try_fold(root, |current, key| current.get(key))
+}
+
+fn insert_value(root: &mut toml::Value, key_path: &str, value: toml::Value) -> Result<(), String> {
+    let keys: Vec<&str> = key_path.split('.').collect();
+    let (path, final_key) = keys.split_at(keys.len() - 1);
+
+    let mut current = root.as_table_mut().ok_or("Config root is not a table")?;
+    for key in path {
+        current = current
+            .entry(key.to_string())
+            .or_insert_with(|| toml::Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| format!("Key path not found: {}", key_path))?;
+    }
+    current.insert(final_key[0].to_string(), value);
+    Ok(())
+}
+
+/// Remove a dotted key, dropping tables it leaves empty.
+fn remove_value(root: &mut toml::Value, key_path: &str) -> bool {
+    let Some(table) = root.as_table_mut() else {
+        return false;
+    };
+    match key_path.split_once('.') {
+        None => table.remove(key_path).is_some(),
+        Some((head, rest)) => {
+            let Some(child) = table.get_mut(head) else {
+                return false;
+            };
+            let removed = remove_value(child, rest);
+            if child.as_table().is_some_and(|t| t.is_empty()) {
+                table.remove(head);
             }
-            toml::Value::Boolean(b) => {
-                result.insert(prefix.to_string(), b.to_string());
+            removed
+        }
+    }
+}
+
+/// Read one config file, checking it on its own so errors name the file.
+fn read_layer(path: &Path) -> Result<toml::Value> {
+    let toml_str = fs::read_to_string(path)
+        .map_err(|e| io::Error::other(format!("Failed to load config: {}", e)))?;
+    let parse_error = |e: &dyn fmt::Display| {
+        io::Error::other(format!("Failed to parse config {}: {}", path.display(), e))
+    };
+
+    let value: toml::Value = toml::from_str(&toml_str).map_err(|e| parse_error(&e))?;
+    value
+        .clone()
+        .try_into::<AppConfig>()
+        .map_err(|e| parse_error(&e))?;
+    Ok(value)
+}
+
+/// Merge `overlay` into `base` table by table; any other value replaces the base.
+fn merge_values(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
             }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Flatten nested tables into dotted keys; arrays are kept as single values.
+fn flatten(value: &toml::Value) -> BTreeMap<String, toml::Value> {
+    fn walk(value: &toml::Value, prefix: &str, out: &mut BTreeMap<String, toml::Value>) {
+        match value {
             toml::Value::Table(table) => {
-                for (key, val) in table {
-                    let new_prefix = if prefix.is_empty() {
+                for (key, value) in table {
+                    let key = if prefix.is_empty() {
                         key.clone()
                     } else {
                         format!("{}.{}", prefix, key)
                     };
-                    result.extend(Self::flatten_value(
-                        val,
-                        &new_prefix,
-                        max_depth,
-                        current_depth + 1,
-                    ));
+                    walk(value, &key, out);
                 }
             }
-            _ => {}
+            _ => {
+                out.insert(prefix.to_string(), value.clone());
+            }
         }
-
-        result
     }
 
-    #[cfg(test)]
-    fn convert_value(&self, value: &str) -> Result<toml::Value, String> {
-        // Try TOML literal first
-        if let Ok(parsed) = value.parse::<toml::Value>() {
-            return Ok(parsed);
-        }
-
-        // Fallback to string
-        Ok(toml::Value::String(value.to_string()))
-    }
+    let mut out = BTreeMap::new();
+    walk(value, "", &mut out);
+    out
 }
 
 #[cfg(test)]
@@ -347,4 +570,156 @@ done_prefix = "DONE:"
 
         cleanup(&path);
     }
+
+    #[test]
+    fn project_config_overrides_global_key_by_key() {
+        let path = temp_config_path("layered");
+        let project_path = path.with_file_name("project.toml");
+        fs::write(
+            &path,
+            r#"
+[matching]
+task_min_score = 60.0
+released_min_score = 65.0
+
+[scan]
+todo_markers = ["TODO:", "FIXME:"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            &project_path,
+            r#"
+[matching]
+task_min_score = 80.0
+
+[scan]
+todo_markers = ["HACK:"]
+"#,
+        )
+        .unwrap();
+
+        let storage = ConfigStorage::layered(&path, Some(&project_path)).unwrap();
+        let config = storage.get_config();
+
+        assert_eq!(config.matching.task_min_score, 80.0);
+        assert_eq!(config.matching.released_min_score, 65.0);
+        assert_eq!(config.matching.source_done_min_score, 50.0);
+        assert_eq!(config.scan.todo_markers, vec!["HACK:"]);
+
+        let values = storage.effective_values().unwrap();
+        assert_eq!(values["matching.task_min_score"].1, ConfigOrigin::Project);
+        assert_eq!(
+            values["matching.released_min_score"].1,
+            ConfigOrigin::Global
+        );
+        assert_eq!(
+            values["matching.source_done_min_score"].1,
+            ConfigOrigin::Default
+        );
+        assert_eq!(values["scan.todo_markers"].1, ConfigOrigin::Project);
+        assert_eq!(
+            storage.origin_file(ConfigOrigin::Project),
+            Some(project_path.as_path())
+        );
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn project_config_errors_name_the_project_file() {
+        let path = temp_config_path("layered-invalid");
+        let project_path = path.with_file_name(".tally.toml");
+        fs::write(&project_path, "[matching]\ntask_min_score = \"high\"\n").unwrap();
+
+        let err = match ConfigStorage::layered(&path, Some(&project_path)) {
+            Ok(_) => panic!("invalid project config should fail"),
+            Err(err) => err,
+        };
+
+        assert!(err.to_string().contains(".tally.toml"));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn set_and_unset_write_the_project_file() {
+        let path = temp_config_path("project-set");
+        let project_path = path.with_file_name(".tally.toml");
+
+        let mut storage = ConfigStorage::layered(&path, Some(&project_path)).unwrap();
+        storage
+            .set_value("matching.task_min_score", "70", ConfigTarget::Project)
+            .unwrap();
+        storage
+            .set_value("scan.todo_markers", "TODO:, FIXME:", ConfigTarget::Project)
+            .unwrap();
+        storage
+            .set_value("preferences.editor", "nano", ConfigTarget::Project)
+            .unwrap();
+
+        assert_eq!(storage.get_config().matching.task_min_score, 70.0);
+        assert_eq!(
+            storage.get_config().scan.todo_markers,
+            vec!["TODO:", "FIXME:"]
+        );
+        assert_eq!(
+            storage.get_config().preferences.editor.as_deref(),
+            Some("nano")
+        );
+        let project = storage.target_values(ConfigTarget::Project).unwrap();
+        assert_eq!(project.len(), 3);
+        assert!(
+            !storage
+                .target_values(ConfigTarget::Global)
+                .unwrap()
+                .contains_key("preferences.editor")
+        );
+
+        assert!(
+            storage
+                .unset_value("matching.task_min_score", ConfigTarget::Project)
+                .unwrap()
+        );
+        assert!(
+            !storage
+                .unset_value("matching.task_min_score", ConfigTarget::Project)
+                .unwrap()
+        );
+        assert_eq!(storage.get_config().matching.task_min_score, 50.0);
+        let content = fs::read_to_string(&project_path).unwrap();
+        assert!(!content.contains("[matching]"));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn set_rejects_values_that_do_not_fit_the_schema() {
+        let path = temp_config_path("invalid-values");
+
+        let mut storage = ConfigStorage::new(&path).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        let err = storage
+            .set_value("scan.git_log_limit", "many", ConfigTarget::Global)
+            .unwrap_err();
+        assert!(err.contains("Invalid value for scan.git_log_limit"));
+        let err = storage
+            .set_value("changelog.style", "fancy", ConfigTarget::Global)
+            .unwrap_err();
+        assert!(err.contains("Invalid value for changelog.style"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+        storage
+            .set_value("changelog.style", "keep_a_changelog", ConfigTarget::Global)
+            .unwrap();
+        assert_eq!(
+            storage.get_value("changelog.style").unwrap().as_str(),
+            Some("keep_a_changelog")
+        );
+        assert!(storage.get_value("changelog.compare_url").is_err());
+        assert!(known_keys().contains(&"changelog.compare_url".to_string()));
+
+        cleanup(&path);
+    }
 }
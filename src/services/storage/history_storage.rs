@@ -0,0 +1,279 @@
+use crate::models::history::{FileChange, Journal, JournalEntry};
+use crate::utils::atomic_file;
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of commands kept in the journal.
+const MAX_ENTRIES: usize = 100;
+
+pub struct HistoryStorage {
+    journal: Journal,
+    journal_file: PathBuf,
+    state_dir: PathBuf,
+}
+
+impl HistoryStorage {
+    pub fn new(state_dir: &Path) -> Result<Self> {
+        let mut storage = Self {
+            journal: Journal::default(),
+            journal_file: state_dir.join("history").join("journal.json"),
+            state_dir: state_dir.to_path_buf(),
+        };
+        storage.load()?;
+        Ok(storage)
+    }
+
+    fn load(&mut self) -> Result<()> {
+        if !self.journal_file.exists() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&self.journal_file)?;
+        self.journal = serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse history journal {}",
+                self.journal_file.display()
+            )
+        })?;
+        self.journal.position = self.journal.position.min(self.journal.entries.len());
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        super::ensure_state_dir(&self.state_dir)?;
+        atomic_file::write(&self.journal_file, &serde_json::to_string(&self.journal)?)?;
+        Ok(())
+    }
+
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    /// Record a command's file changes. Files that did not change are dropped,
+    /// and nothing is recorded when no file changed.
+    pub fn record(&mut self, command: &str, files: Vec<FileChange>) -> Result<Option<u64>> {
+        let files: Vec<FileChange> = files
+            .into_iter()
+            .filter(|file| file.before != file.after)
+            .collect();
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let id = self.journal.next_id();
+        self.journal.push(
+            JournalEntry {
+                id,
+                command: command.to_string(),
+                recorded_at: Utc::now(),
+                files,
+            },
+            MAX_ENTRIES,
+        );
+        self.save()?;
+        Ok(Some(id))
+    }
+
+    /// Restore the files of the latest applied entry to their state before it.
+    pub fn undo(&mut self, root: &Path, dry_run: bool) -> Result<Option<JournalEntry>> {
+        let Some(entry) = self.journal.undo_target().cloned() else {
+            return Ok(None);
+        };
+
+        ensure_unchanged(root, &entry, |file| &file.after, "undo")?;
+        if !dry_run {
+            restore(root, &entry, |file| &file.before)?;
+            self.journal.position -= 1;
+            self.save()?;
+        }
+        Ok(Some(entry))
+    }
+
+    /// Reapply the earliest undone entry.
+    pub fn redo(&mut self, root: &Path, dry_run: bool) -> Result<Option<JournalEntry>> {
+        let Some(entry) = self.journal.redo_target().cloned() else {
+            return Ok(None);
+        };
+
+        ensure_unchanged(root, &entry, |file| &file.before, "redo")?;
+        if !dry_run {
+            restore(root, &entry, |file| &file.after)?;
+            self.journal.position += 1;
+            self.save()?;
+        }
+        Ok(Some(entry))
+    }
+}
+
+/// Read tracked files relative to `root`, for recording around a command.
+pub fn snapshot(root: &Path, files: &[&str]) -> Vec<(String, Option<String>)> {
+    files
+        .iter()
+        .map(|file| (file.to_string(), fs::read_to_string(root.join(file)).ok()))
+        .collect()
+}
+
+/// Pair up snapshots taken before and after a command.
+pub fn file_changes(
+    before: Vec<(String, Option<String>)>,
+    after: Vec<(String, Option<String>)>,
+) -> Vec<FileChange> {
+    before
+        .into_iter()
+        .zip(after)
+        .map(|((path, before), (_, after))| FileChange {
+            path,
+            before,
+            after,
+        })
+        .collect()
+}
+
+fn ensure_unchanged(
+    root: &Path,
+    entry: &JournalEntry,
+    expected: impl Fn(&FileChange) -> &Option<String>,
+    action: &str,
+) -> Result<()> {
+    for file in &entry.files {
+        let current = fs::read_to_string(root.join(&file.path)).ok();
+        if &current != expected(file) {
+            bail!(
+                "Cannot {} #{} ({}): {} was changed outside tally since it was recorded",
+                action,
+                entry.id,
+                entry.command,
+                file.path
+            );
+        }
+    }
+    Ok(())
+}
+
+fn restore(
+    root: &Path,
+    entry: &JournalEntry,
+    contents: impl Fn(&FileChange) -> &Option<String>,
+) -> Result<()> {
+    let paths: Vec<PathBuf> = entry
+        .files
+        .iter()
+        .map(|file| root.join(&file.path))
+        .collect();
+    let writes: Vec<(&Path, &str)> = entry
+        .files
+        .iter()
+        .zip(&paths)
+        .filter_map(|(file, path)| Some((path.as_path(), contents(file).as_deref()?)))
+        .collect();
+    atomic_file::write_all(&writes)?;
+
+    for (file, path) in entry.files.iter().zip(&paths) {
+        if contents(file).is_none() && path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn change(path: &str, before: Option<&str>, after: Option<&str>) -> FileChange {
+        FileChange {
+            path: path.to_string(),
+            before: before.map(str::to_string),
+            after: after.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_restore_recorded_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let state_dir = root.join(".tally");
+        fs::write(root.join("TODO.md"), "after").unwrap();
+        fs::write(root.join("CHANGELOG.md"), "log").unwrap();
+
+        let mut history = HistoryStorage::new(&state_dir).unwrap();
+        let id = history
+            .record(
+                "done parser",
+                vec![
+                    change("TODO.md", Some("before"), Some("after")),
+                    change("CHANGELOG.md", Some("log"), Some("log")),
+                ],
+            )
+            .unwrap();
+        assert_eq!(id, Some(1));
+        assert_eq!(history.journal().entries[0].files.len(), 1);
+        assert_eq!(
+            fs::read_to_string(state_dir.join(".gitignore")).unwrap(),
+            "*\n"
+        );
+
+        let mut reloaded = HistoryStorage::new(&state_dir).unwrap();
+        reloaded.undo(root, false).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(root.join("TODO.md")).unwrap(), "before");
+        assert!(reloaded.undo(root, false).unwrap().is_none());
+
+        reloaded.redo(root, false).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(root.join("TODO.md")).unwrap(), "after");
+        assert!(reloaded.redo(root, false).unwrap().is_none());
+    }
+
+    #[test]
+    fn undo_refuses_when_file_changed_outside_tally() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let mut history = HistoryStorage::new(&root.join(".tally")).unwrap();
+        history
+            .record("add task", vec![change("TODO.md", None, Some("added"))])
+            .unwrap();
+        fs::write(root.join("TODO.md"), "hand edited").unwrap();
+
+        let err = history.undo(root, false).unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("TODO.md was changed outside tally")
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("TODO.md")).unwrap(),
+            "hand edited"
+        );
+        assert_eq!(history.journal().position, 1);
+    }
+
+    #[test]
+    fn recording_after_undo_discards_redo_entries() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let mut history = HistoryStorage::new(&root.join(".tally")).unwrap();
+        fs::write(root.join("TODO.md"), "two").unwrap();
+        history
+            .record("first", vec![change("TODO.md", Some("zero"), Some("one"))])
+            .unwrap();
+        history
+            .record("second", vec![change("TODO.md", Some("one"), Some("two"))])
+            .unwrap();
+
+        history.undo(root, false).unwrap();
+        history
+            .record("third", vec![change("TODO.md", Some("one"), Some("three"))])
+            .unwrap();
+
+        let commands: Vec<_> = history
+            .journal()
+            .entries
+            .iter()
+            .map(|entry| entry.command.as_str())
+            .collect();
+        assert_eq!(commands, vec!["first", "third"]);
+        assert!(history.journal().redo_target().is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for another tally process to finish.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Where it cannot be checked whether the holder is still running, a lock
+/// older than this is treated as left behind by a crashed process.
+const STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+/// A lock file that cannot be read is taken to be held for this long after
+/// it was last written, since its writer may still be running.
+const UNREADABLE_GRACE: Duration = Duration::from_secs(5);
+const RETRY_EVERY: Duration = Duration::from_millis(50);
+
+/// Set on processes tally starts (such as `git commit` and its hooks) to the
+/// pid that holds the lock, so a nested tally does not wait on its parent.
+pub const OWNER_ENV: &str = "TALLY_LOCK_OWNER";
+
+thread_local! {
+    static HELD: RefCell<Vec<(PathBuf, Weak<ProjectLock>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The project's `.tally/lock`, held while a command reads, modifies and
+/// writes TODO.md or CHANGELOG.md. Released when the last handle is dropped.
+#[derive(Debug)]
+pub struct ProjectLock {
+    path: PathBuf,
+    /// False when the lock belongs to a parent tally process.
+    owned: bool,
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        if self.owned {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Take the project lock, waiting for another tally process if needed.
+/// Taking it again in the same process returns the lock already held.
+pub fn acquire(state_dir: &Path) -> Result<Rc<ProjectLock>> {
+    acquire_with_timeout(state_dir, TIMEOUT)
+}
+
+fn acquire_with_timeout(state_dir: &Path, timeout: Duration) -> Result<Rc<ProjectLock>> {
+    let path = state_dir.join("lock");
+    let held = HELD.with(|held| {
+        let mut held = held.borrow_mut();
+        held.retain(|(_, lock)| lock.strong_count() > 0);
+        held.iter()
+            .find(|(held_path, _)| held_path == &path)
+            .and_then(|(_, lock)| lock.upgrade())
+    });
+    if let Some(lock) = held {
+        return Ok(lock);
+    }
+
+    super::ensure_state_dir(state_dir)?;
+    let started = Utc::now();
+    let owned = loop {
+        match create(&path) {
+            Ok(()) => break true,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", path.display()));
+            }
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            // Released since we tried to create it.
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        let holder = Holder::parse(&content);
+        if holder.as_ref().is_some_and(Holder::is_parent) {
+            break false;
+        }
+        let stale = match &holder {
+            Some(holder) => holder.is_stale(),
+            None => unchanged_for(&path, UNREADABLE_GRACE),
+        };
+        // Another process may have replaced the stale lock with its own
+        // since it was read; only the lock that was judged is removed.
+        if stale && fs::read_to_string(&path).is_ok_and(|current| current == content) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+
+        if (Utc::now() - started).to_std().unwrap_or_default() >= timeout {
+            let Some(holder) = holder else {
+                bail!(
+                    "Timed out after {}s waiting for {}, which could not be read. \
+                     If no other tally command is running, delete the file and try again.",
+                    timeout.as_secs(),
+                    path.display()
+                );
+            };
+            bail!(
+                "Timed out after {}s waiting for {}: tally process {} has held it since {}. \
+                 If no other tally command is running, delete the file and try again.",
+                timeout.as_secs(),
+                path.display(),
+                holder.pid,
+                holder.since.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+        }
+        thread::sleep(RETRY_EVERY);
+    };
+
+    let lock = Rc::new(ProjectLock { path, owned });
+    HELD.with(|held| {
+        held.borrow_mut()
+            .push((lock.path.clone(), Rc::downgrade(&lock)));
+    });
+    Ok(lock)
+}
+
+/// Write the holder to a staging file and move it under the lock's name only
+/// if no lock exists, so other processes never see a half-written lock.
+fn create(path: &Path) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut file = tempfile::Builder::new().prefix("lock-").tempfile_in(dir)?;
+    writeln!(file, "{}", std::process::id())?;
+    writeln!(file, "{}", Utc::now().to_rfc3339())?;
+    file.persist_noclobber(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Whether the file was last written more than `grace` ago.
+fn unchanged_for(path: &Path, grace: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > grace)
+}
+
+/// Who holds a lock file, as written by `create`.
+struct Holder {
+    pid: u32,
+    since: DateTime<Utc>,
+}
+
+impl Holder {
+    /// `None` if the content is incomplete, e.g. left half-written by a crash.
+    fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let since = DateTime::parse_from_rfc3339(lines.next()?.trim()).ok()?;
+        Some(Self {
+            pid,
+            since: since.with_timezone(&Utc),
+        })
+    }
+
+    fn is_parent(&self) -> bool {
+        std::env::var(OWNER_ENV).is_ok_and(|owner| owner == self.pid.to_string())
+    }
+
+    /// A lock is stale once its process has exited. A live holder keeps it
+    /// however long it runs, e.g. while an editor is open.
+    fn is_stale(&self) -> bool {
+        match is_running(self.pid) {
+            Some(running) => !running,
+            None => (Utc::now() - self.since)
+                .to_std()
+                .is_ok_and(|age| age > STALE_AFTER),
+        }
+    }
+}
+
+/// Whether a process is running, or `None` where that cannot be told.
+fn is_running(pid: u32) -> Option<bool> {
+    if Path::new("/proc/self").exists() {
+        return Some(Path::new("/proc").join(pid.to_string()).exists());
+    }
+    if cfg!(unix) {
+        let output = std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+            .ok()?;
+        if output.status.success() {
+            return Some(true);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("No such process") {
+            return Some(false);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_holder(state_dir: &Path, pid: u32, since: DateTime<Utc>) {
+        fs::create_dir_all(state_dir).unwrap();
+        fs::write(
+            state_dir.join("lock"),
+            format!("{}\n{}\n", pid, since.to_rfc3339()),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn reuses_held_lock_and_releases_it_on_drop() {
+        let root = TempDir::new().unwrap();
+        let state_dir = root.path().join(".tally");
+        let first = acquire(&state_dir).unwrap();
+        let second = acquire(&state_dir).unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+        assert!(state_dir.join("lock").exists());
+
+        drop(first);
+        assert!(state_dir.join("lock").exists());
+        drop(second);
+        assert!(!state_dir.join("lock").exists());
+    }
+
+    #[test]
+    fn waits_for_live_holders_and_replaces_locks_of_exited_processes() {
+        let root = TempDir::new().unwrap();
+        let state_dir = root.path().join(".tally");
+
+        // The test process itself stands in for another running tally.
+        write_holder(&state_dir, std::process::id(), Utc::now());
+        let err = acquire_with_timeout(&state_dir, Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+        assert!(err.to_string().contains(&std::process::id().to_string()));
+
+        // However old, a live holder's lock is not taken over.
+        let long_ago = Utc::now() - chrono::Duration::hours(1);
+        write_holder(&state_dir, std::process::id(), long_ago);
+        assert!(acquire_with_timeout(&state_dir, Duration::from_millis(100)).is_err());
+
+        // A lock whose process has exited was left behind.
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        exited.wait().unwrap();
+        write_holder(&state_dir, exited.id(), Utc::now());
+        let lock = acquire_with_timeout(&state_dir, Duration::from_millis(100)).unwrap();
+        assert!(lock.owned);
+    }
+
+    #[test]
+    fn unreadable_locks_are_held_until_the_grace_period_passes() {
+        let root = TempDir::new().unwrap();
+        let state_dir = root.path().join(".tally");
+        fs::create_dir_all(&state_dir).unwrap();
+        let path = state_dir.join("lock");
+
+        for content in ["", "4242\n"] {
+            fs::write(&path, content).unwrap();
+            let err = acquire_with_timeout(&state_dir, Duration::from_millis(100)).unwrap_err();
+            assert!(err.to_string().contains("could not be read"));
+            assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        }
+
+        let written = std::time::SystemTime::now() - UNREADABLE_GRACE * 2;
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(written)
+            .unwrap();
+        let lock = acquire_with_timeout(&state_dir, Duration::from_millis(100)).unwrap();
+        assert!(lock.owned);
+        let holder = Holder::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+    }
+}
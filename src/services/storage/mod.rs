@@ -1,3 +1,21 @@
 pub mod changelog_storage;
 pub mod config_storage;
+pub mod history_storage;
+pub mod lock;
+pub mod scan_state;
 pub mod task_storage;
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// Create tally's private state directory, keeping it out of git without
+/// touching the repo's .gitignore.
+pub fn ensure_state_dir(state_dir: &Path) -> Result<()> {
+    fs::create_dir_all(state_dir)?;
+    let gitignore = state_dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, "*\n")?;
+    }
+    Ok(())
+}
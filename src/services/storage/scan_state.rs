@@ -0,0 +1,36 @@
+use crate::utils::atomic_file;
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+const SCAN_HEAD_FILE: &str = "scan-head";
+
+/// The commit `HEAD` pointed to after the last `scan --git`, if any.
+pub fn last_scanned_commit(state_dir: &Path) -> Option<String> {
+    let hash = fs::read_to_string(state_dir.join(SCAN_HEAD_FILE)).ok()?;
+    let hash = hash.trim();
+    (!hash.is_empty()).then(|| hash.to_string())
+}
+
+/// Remember `hash` so the next `scan --git` starts after it.
+pub fn record_scanned_commit(state_dir: &Path, hash: &str) -> Result<()> {
+    super::ensure_state_dir(state_dir)?;
+    atomic_file::write(&state_dir.join(SCAN_HEAD_FILE), &format!("{}\n", hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn records_and_reads_the_last_scanned_commit() {
+        let root = TempDir::new().unwrap();
+        let state_dir = root.path().join(".tally");
+
+        assert_eq!(last_scanned_commit(&state_dir), None);
+        record_scanned_commit(&state_dir, "abc123").unwrap();
+        assert_eq!(last_scanned_commit(&state_dir).as_deref(), Some("abc123"));
+        assert!(state_dir.join(".gitignore").exists());
+    }
+}
@@ -1,20 +1,60 @@
-use crate::models::common::Version;
-use crate::models::tasks::{List, Task};
+use crate::models::common::{TaskStatus, Version};
+use crate::models::merge;
+use crate::models::tasks::{List, Recurrence, Task, dependencies, recurrence};
 use crate::services::serializers::todo_serializer;
-use anyhow::{Result, anyhow};
+use crate::services::storage::lock::{self, ProjectLock};
+use crate::utils::project_paths::ProjectPaths;
+use crate::utils::{atomic_file, dates};
+use anyhow::{Result, anyhow, bail};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 pub struct ListStorage {
     todo_list: List,
     list_file: PathBuf,
+    /// Name given to a new list when the file does not exist yet.
+    default_name: String,
+    /// File content as last loaded or saved, to notice edits made meanwhile.
+    loaded: Option<String>,
+    _lock: Option<Rc<ProjectLock>>,
 }
 
 impl ListStorage {
     pub fn new(list_file: &Path) -> Result<Self> {
+        Self::named(list_file, directory_name(list_file.parent()), None)
+    }
+
+    /// Open the project's TODO file, naming a new list after the project root
+    /// rather than the directory the file lives in. The project lock is held
+    /// until the storage is dropped.
+    pub fn for_project(paths: &ProjectPaths) -> Result<Self> {
+        let lock = lock::acquire(&paths.state_dir)?;
+        Self::named(
+            &paths.todo_file,
+            directory_name(Some(&paths.root)),
+            Some(lock),
+        )
+    }
+
+    /// Open the project's TODO file for a command that only reads it. No lock
+    /// is taken, as writes replace the file whole, so reading never waits on
+    /// another tally process and leaves no `.tally/` behind.
+    pub fn for_reading(paths: &ProjectPaths) -> Result<Self> {
+        Self::named(&paths.todo_file, directory_name(Some(&paths.root)), None)
+    }
+
+    fn named(
+        list_file: &Path,
+        default_name: String,
+        lock: Option<Rc<ProjectLock>>,
+    ) -> Result<Self> {
         let mut storage = Self {
-            todo_list: List::new("", Version::new(0, 1, 0, false)),
+            todo_list: List::new("", Version::new(0, 1, 0)),
             list_file: list_file.to_path_buf(),
+            default_name,
+            loaded: None,
+            _lock: lock,
         };
         storage.load_list()?;
         Ok(storage)
@@ -24,13 +64,8 @@ impl ListStorage {
     pub fn load_list(&mut self) -> Result<()> {
         if !self.list_file.exists() {
             // Create a default list if file doesn't exist
-            let project_name = self
-                .list_file
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("Untitled");
-            self.todo_list = List::new(project_name, Version::new(0, 1, 0, false));
+            self.todo_list = List::new(&self.default_name, Version::new(0, 1, 0));
+            self.loaded = None;
             return Ok(());
         }
 
@@ -38,28 +73,101 @@ impl ListStorage {
             Ok(content) => {
                 self.todo_list = todo_serializer::deserialize(&content)
                     .map_err(|e| anyhow!("Failed to parse TODO file: {}", e))?;
+                self.loaded = Some(content);
+                self.warn_cycles();
                 Ok(())
             }
             Err(e) => Err(anyhow!("Failed to read TODO file: {}", e)),
         }
     }
 
+    /// A cycle written by hand or by a merge is reported but does not stop
+    /// commands, so `tally edit --remove-depends` can break it.
+    fn warn_cycles(&self) {
+        let tasks = &self.todo_list.tasks;
+        if let Some(cycle) = dependencies::find_cycle(tasks) {
+            eprintln!(
+                "Warning: dependency cycle in {}: {}; break it with `tally edit --remove-depends`",
+                self.list_file.display(),
+                dependencies::describe_cycle(tasks, &cycle)
+            );
+        }
+    }
+
     /// Save list to the TODO.md file
-    pub fn save_list(&self) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = self.list_file.parent() {
-            fs::create_dir_all(parent).map_err(|e| anyhow!("Failed to create directory: {}", e))?;
+    pub fn save_list(&mut self) -> Result<()> {
+        let content = self.render()?;
+        atomic_file::write(&self.list_file, &content)
+            .map_err(|e| anyhow!("Failed to write TODO file: {}", e))?;
+        self.mark_saved(content);
+        Ok(())
+    }
+
+    /// The TODO.md content `save_list` would write. If the file was changed
+    /// since it was loaded, those task changes are merged in first.
+    pub fn render(&mut self) -> Result<String> {
+        let current = fs::read_to_string(&self.list_file).ok();
+        if let Some(content) = &current
+            && current != self.loaded
+        {
+            self.merge_external(content)?;
         }
+        Ok(todo_serializer::serialize_preserving(
+            &self.todo_list,
+            current.as_deref(),
+        ))
+    }
 
-        let previous = fs::read_to_string(&self.list_file).ok();
-        let content = todo_serializer::serialize_preserving(&self.todo_list, previous.as_deref());
+    /// Record content written by `render`'s caller as the new baseline.
+    pub fn mark_saved(&mut self, content: String) {
+        self.loaded = Some(content);
+    }
 
-        fs::write(&self.list_file, content)
-            .map_err(|e| anyhow!("Failed to write TODO file: {}", e))?;
+    fn merge_external(&mut self, content: &str) -> Result<()> {
+        let file = self.list_file.display();
+        let theirs = todo_serializer::deserialize(content).map_err(|e| {
+            anyhow!(
+                "{} was changed while tally was running and can no longer be parsed: {}",
+                file,
+                e
+            )
+        })?;
+        let base = match &self.loaded {
+            Some(loaded) => todo_serializer::deserialize(loaded)?.tasks,
+            None => Vec::new(),
+        };
+
+        let tasks = merge::merge_tasks(&base, &self.todo_list.tasks, &theirs.tasks).map_err(|conflicts| {
+            let conflicts: Vec<String> = conflicts.iter().map(|c| format!("  {}", c)).collect();
+            anyhow!(
+                "{} was changed while tally was running, and the changes conflict \
+                 (ours: this command, theirs: the file on disk):\n{}\nNothing was written; run the command again.",
+                file,
+                conflicts.join("\n")
+            )
+        })?;
+
+        // Each side's dependencies may be fine while their union is not.
+        let had_cycle = dependencies::find_cycle(&self.todo_list.tasks).is_some()
+            || dependencies::find_cycle(&theirs.tasks).is_some();
+        if !had_cycle && let Some(cycle) = dependencies::find_cycle(&tasks) {
+            bail!(
+                "{} was changed while tally was running, and together the changes form a \
+                 dependency cycle: {}\nNothing was written; run the command again.",
+                file,
+                dependencies::describe_cycle(&tasks, &cycle)
+            );
+        }
 
+        self.todo_list.tasks = tasks;
+        self.loaded = Some(content.to_string());
         Ok(())
     }
 
+    pub fn path(&self) -> &Path {
+        &self.list_file
+    }
+
     /// Add a new task and save
     pub fn add_task(&mut self, task: Task) -> Result<()> {
         self.todo_list.add_task(task);
@@ -84,36 +192,167 @@ impl ListStorage {
         &mut self.todo_list.tasks
     }
 
-    /// Remove a task by index and save
+    /// Find the index of a task by its ID
+    pub fn find_task_by_id(&self, id: &str) -> Option<usize> {
+        self.todo_list.find_task_by_id(id)
+    }
+
+    /// Remove a task by index and save. Its subtasks move up to its parent.
     pub fn remove_task(&mut self, index: usize) -> Result<Option<Task>> {
-        if index < self.todo_list.tasks.len() {
-            let task = self.todo_list.tasks.remove(index);
-            self.todo_list.modified_at = chrono::Utc::now();
+        let task = self.todo_list.remove_task(index);
+        if task.is_some() {
             self.save_list()?;
-            Ok(Some(task))
-        } else {
-            Ok(None)
         }
+        Ok(task)
     }
 
-    /// Mark a task as completed and save
-    pub fn complete_task(&mut self, index: usize, version: Option<Version>) -> Result<()> {
+    /// Get mutable access to the list for several changes saved at once
+    pub fn list_mut(&mut self) -> &mut List {
+        &mut self.todo_list
+    }
+
+    /// Mark a task as completed without saving, so several changes can be
+    /// written at once. A task with a time-based `@recur` rule gets a fresh
+    /// open copy, which is returned.
+    pub fn mark_done(
+        &mut self,
+        index: usize,
+        version: Option<Version>,
+        commit: Option<String>,
+    ) -> Result<Option<Task>> {
         if let Some(task) = self.todo_list.tasks.get_mut(index) {
-            task.completed = true;
+            task.status = TaskStatus::Done;
+            task.blocked_reason = None;
             task.completed_at_time = Some(chrono::Utc::now());
             if let Some(v) = version {
                 task.completed_at_version = Some(v);
             }
+            if let Some(commit) = commit {
+                task.completed_at_commit = Some(commit);
+            }
+
+            let task = &self.todo_list.tasks[index];
+            let recurs = task.recur.as_ref().is_some_and(Recurrence::is_time_based)
+                && !recurrence::has_recurred(&self.todo_list.tasks, task);
+            let next = recurs
+                .then(|| recurrence::next_instance(task, dates::today()))
+                .flatten()
+                .map(|next| {
+                    self.todo_list.add_task(next);
+                    let next = self.todo_list.tasks[self.todo_list.tasks.len() - 1].clone();
+                    self.todo_list.tasks[index].recurred = Some(next.id.clone());
+                    next
+                });
+
             self.todo_list.modified_at = chrono::Utc::now();
-            self.save_list()?;
-            Ok(())
+            Ok(next)
         } else {
             Err(anyhow!("Task index {} out of bounds", index))
         }
     }
 
+    /// Move a task to a state other than done and save. Reopening a
+    /// cancelled task clears its completion metadata.
+    pub fn set_status(
+        &mut self,
+        index: usize,
+        status: TaskStatus,
+        blocked_reason: Option<String>,
+    ) -> Result<()> {
+        let Some(task) = self.todo_list.tasks.get_mut(index) else {
+            return Err(anyhow!("Task index {} out of bounds", index));
+        };
+
+        task.status = status;
+        task.blocked_reason = blocked_reason.filter(|_| status == TaskStatus::Blocked);
+        if status == TaskStatus::Cancelled {
+            task.completed_at_time = Some(chrono::Utc::now());
+        } else if !status.is_closed() {
+            task.completed_at_time = None;
+            task.completed_at_version = None;
+            task.completed_at_commit = None;
+        }
+        self.todo_list.modified_at = chrono::Utc::now();
+        self.save_list()
+    }
+
     /// Get the project name
     pub fn project_name(&self) -> &str {
         &self.todo_list.project_name
     }
 }
+
+fn directory_name(dir: Option<&Path>) -> String {
+    dir.and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("Untitled")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Priority;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_merges_tasks_written_by_someone_else() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("TODO.md");
+        let mut setup = ListStorage::new(&path).unwrap();
+        setup
+            .add_task(Task::new("Shared", Priority::Medium, vec![]))
+            .unwrap();
+
+        let mut ours = ListStorage::new(&path).unwrap();
+        let mut theirs = ListStorage::new(&path).unwrap();
+        theirs
+            .add_task(Task::new("Theirs", Priority::Low, vec![]))
+            .unwrap();
+        theirs.tasks_mut()[0].priority = Priority::High;
+        theirs.save_list().unwrap();
+
+        ours.add_task(Task::new("Ours", Priority::Low, vec![]))
+            .unwrap();
+        let reloaded = ListStorage::new(&path).unwrap();
+        let names: Vec<_> = reloaded
+            .tasks()
+            .iter()
+            .map(|t| t.description.as_str())
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"Theirs") && names.contains(&"Ours"));
+        assert_eq!(reloaded.tasks()[0].priority, Priority::High);
+
+        // Both sides changing the same field aborts without writing.
+        let before = fs::read_to_string(&path).unwrap();
+        let mut ours = ListStorage::new(&path).unwrap();
+        let mut theirs = ListStorage::new(&path).unwrap();
+        theirs.tasks_mut()[0].priority = Priority::Low;
+        theirs.save_list().unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert_ne!(before, written);
+
+        ours.tasks_mut()[0].priority = Priority::Medium;
+        let err = ours.save_list().unwrap_err();
+        assert!(err.to_string().contains("conflict"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn cycles_on_disk_load_so_they_can_be_fixed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("TODO.md");
+        fs::write(
+            &path,
+            "# TODO — demo\n\n@created: 2026-02-20\n@modified: 2026-02-21\n\n## Tasks\n\n- [ ] a\n      @id aaaaaa\n      @created 2026-02-20 10:00\n      @depends bbbbbb\n- [ ] b\n      @id bbbbbb\n      @created 2026-02-20 10:00\n      @depends aaaaaa\n",
+        )
+        .unwrap();
+
+        let mut storage = ListStorage::new(&path).unwrap();
+        storage.tasks_mut()[0].depends.clear();
+        storage.save_list().unwrap();
+        let reloaded = ListStorage::new(&path).unwrap();
+        assert!(dependencies::find_cycle(reloaded.tasks()).is_none());
+    }
+}
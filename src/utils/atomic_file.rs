@@ -0,0 +1,157 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempPath;
+
+/// Replace `path` with `contents` without ever leaving a half-written file:
+/// the data goes to a temporary file next to it, which is synced and then
+/// renamed over the original. The original's permissions are kept.
+pub fn write(path: &Path, contents: &str) -> Result<()> {
+    write_all(&[(path, contents)])
+}
+
+/// Replace several files as one change. Every file is staged before any is
+/// renamed into place; if a rename fails, the files already replaced get
+/// their previous contents back (or are removed if they were new).
+pub fn write_all(files: &[(&Path, &str)]) -> Result<()> {
+    let targets: Vec<PathBuf> = files.iter().map(|(path, _)| resolve(path)).collect();
+    let originals: Vec<Option<Vec<u8>>> = targets.iter().map(|path| fs::read(path).ok()).collect();
+
+    // A staged file is removed when dropped, so any early return cleans up.
+    let staged = targets
+        .iter()
+        .zip(files)
+        .map(|(target, (_, contents))| stage(target, contents.as_bytes()))
+        .collect::<Result<Vec<TempPath>>>()?;
+    sync_dirs(&targets);
+
+    for (index, (target, temp)) in targets.iter().zip(staged).enumerate() {
+        if let Err(e) = temp.persist(target) {
+            for (target, original) in targets[..index].iter().zip(&originals) {
+                let _ = match original {
+                    Some(bytes) => stage(target, bytes)
+                        .and_then(|temp| temp.persist(target).map_err(|e| anyhow!(e.error))),
+                    None => fs::remove_file(target).map_err(|e| anyhow!(e)),
+                };
+            }
+            return Err(anyhow!(
+                "Failed to replace {}: {}",
+                target.display(),
+                e.error
+            ));
+        }
+    }
+
+    sync_dirs(&targets);
+    Ok(())
+}
+
+/// Follow a symlinked target so the link itself is not replaced.
+fn resolve(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Write `contents` to a new, randomly named file next to `target` and sync
+/// it to disk.
+fn stage(target: &Path, contents: &[u8]) -> Result<TempPath> {
+    let dir = parent_dir(target);
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+
+    let name = target
+        .file_name()
+        .with_context(|| format!("Invalid file path {}", target.display()))?;
+    // Opened like `File::create`, so a new file gets the usual permissions,
+    // but never over an existing file.
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!(".{}.", name.to_string_lossy()))
+        .suffix(".tmp")
+        .make_in(dir, |path| {
+            OpenOptions::new().write(true).create_new(true).open(path)
+        })
+        .with_context(|| format!("Failed to stage {}", target.display()))?;
+
+    let written = (|| -> std::io::Result<()> {
+        file.write_all(contents)?;
+        if let Ok(metadata) = fs::metadata(target) {
+            file.as_file().set_permissions(metadata.permissions())?;
+        }
+        file.as_file().sync_all()
+    })();
+    written.map_err(|e| anyhow!("Failed to write {}: {}", target.display(), e))?;
+    Ok(file.into_temp_path())
+}
+
+fn parent_dir(target: &Path) -> &Path {
+    match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Persist directory entries; not every platform can open a directory.
+fn sync_dirs(targets: &[PathBuf]) {
+    for dir in targets.iter().map(|target| parent_dir(target)) {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_all_rolls_back_when_a_file_cannot_be_replaced() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let todo = root.join("TODO.md");
+        let changelog = root.join("CHANGELOG.md");
+        fs::write(&todo, "before").unwrap();
+        // A directory in the way makes the second rename fail.
+        fs::create_dir_all(&changelog).unwrap();
+
+        let result = write_all(&[(&todo, "after"), (&changelog, "log")]);
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&todo).unwrap(), "before");
+
+        let leftovers: Vec<_> = fs::read_dir(root)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        fs::remove_dir(&changelog).unwrap();
+        write_all(&[(&todo, "after"), (&changelog, "log")]).unwrap();
+        assert_eq!(fs::read_to_string(&todo).unwrap(), "after");
+        assert_eq!(fs::read_to_string(&changelog).unwrap(), "log");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let todo = root.join("TODO.md");
+        fs::write(&todo, "before").unwrap();
+        fs::set_permissions(&todo, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write(&todo, "after").unwrap();
+        assert_eq!(fs::read_to_string(&todo).unwrap(), "after");
+        let mode = fs::metadata(&todo).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A new file is created like any other, not with a temp file's 0600.
+        let changelog = root.join("CHANGELOG.md");
+        write(&changelog, "log").unwrap();
+        fs::write(root.join("plain.md"), "log").unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&changelog), mode(&root.join("plain.md")));
+    }
+}
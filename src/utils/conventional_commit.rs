@@ -0,0 +1,89 @@
+/// A task description written as a conventional commit header, e.g. `feat(parser)!: add recovery`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit<'a> {
+    pub kind: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub subject: &'a str,
+}
+
+pub fn parse(description: &str) -> Option<ConventionalCommit<'_>> {
+    let (prefix, subject) = description.split_once(':')?;
+    let subject = subject.trim_start();
+    if subject.is_empty() || !description[prefix.len() + 1..].starts_with(' ') {
+        return None;
+    }
+
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, rest)) => {
+            let scope = rest.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (kind, Some(scope))
+        }
+        None => (prefix, None),
+    };
+
+    if kind.is_empty() || !kind.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some(ConventionalCommit {
+        kind,
+        scope,
+        breaking,
+        subject,
+    })
+}
+
+/// Whether a description announces a breaking change without the `!` marker.
+pub fn has_breaking_note(description: &str) -> bool {
+    description.starts_with("BREAKING CHANGE") || description.starts_with("BREAKING:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_type_scope_and_breaking_marker() {
+        let parsed = parse("feat(parser)!: add recovery").unwrap();
+
+        assert_eq!(parsed.kind, "feat");
+        assert_eq!(parsed.scope, Some("parser"));
+        assert!(parsed.breaking);
+        assert_eq!(parsed.subject, "add recovery");
+
+        let plain = parse("fix: handle empty history").unwrap();
+        assert_eq!(plain.kind, "fix");
+        assert_eq!(plain.scope, None);
+        assert!(!plain.breaking);
+    }
+
+    #[test]
+    fn rejects_plain_descriptions() {
+        for description in [
+            "make scan able to init tally",
+            "feat:no space",
+            "feat: ",
+            "two words: not a type",
+            "feat(): empty scope",
+            "https://example.com",
+        ] {
+            assert!(parse(description).is_none(), "parsed '{description}'");
+        }
+    }
+
+    #[test]
+    fn detects_breaking_notes() {
+        assert!(has_breaking_note("BREAKING CHANGE: drop v1 format"));
+        assert!(has_breaking_note("BREAKING: drop v1 format"));
+        assert!(!has_breaking_note("fix breaking parser"));
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::{Result, anyhow, bail};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+
+/// Today's date in the local timezone; due and scheduled dates are calendar days.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Parse a date given as `2026-11-01`, `today`, `tomorrow`, a weekday such as
+/// `friday` (the next one after today), or an offset such as `3d`, `2w` or `1m`.
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let value = input.trim().to_ascii_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match value.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return add_span("1d", today),
+        "yesterday" => {
+            return today
+                .checked_sub_days(Days::new(1))
+                .ok_or_else(|| anyhow!("Invalid date '{}'", input));
+        }
+        _ => {}
+    }
+
+    if let Ok(weekday) = value.parse::<Weekday>() {
+        let ahead =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return add_span(&format!("{ahead}d"), today);
+    }
+
+    add_span(value.trim_start_matches('+'), today).map_err(|_| {
+        anyhow!(
+            "Invalid date '{}': use YYYY-MM-DD, today, tomorrow, a weekday, or an offset like 3d, 2w or 1m",
+            input
+        )
+    })
+}
+
+/// The date a span such as `7d`, `2w` or `1m` after `from`.
+pub fn add_span(span: &str, from: NaiveDate) -> Result<NaiveDate> {
+    let span = span.trim().to_ascii_lowercase();
+    let Some(unit) = span.chars().last() else {
+        bail!("Empty time span");
+    };
+    let count: u32 = span[..span.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| anyhow!("Invalid time span '{}': use a number of d, w or m", span))?;
+    if count == 0 {
+        bail!("Invalid time span '{}': the count must be at least 1", span);
+    }
+
+    let date = match unit {
+        'd' => from.checked_add_days(Days::new(count.into())),
+        'w' => from.checked_add_days(Days::new(u64::from(count) * 7)),
+        'm' => from.checked_add_months(Months::new(count)),
+        _ => bail!("Invalid time span '{}': use a number of d, w or m", span),
+    };
+    date.ok_or_else(|| anyhow!("Time span '{}' is out of range", span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_absolute_and_named_dates() {
+        // 2026-10-14 is a Wednesday.
+        let today = day(2026, 10, 14);
+
+        assert_eq!(parse_date("2026-11-01", today).unwrap(), day(2026, 11, 1));
+        assert_eq!(parse_date("today", today).unwrap(), today);
+        assert_eq!(parse_date("Tomorrow", today).unwrap(), day(2026, 10, 15));
+        assert_eq!(parse_date("friday", today).unwrap(), day(2026, 10, 16));
+        assert_eq!(parse_date("wed", today).unwrap(), day(2026, 10, 21));
+    }
+
+    #[test]
+    fn parses_relative_offsets() {
+        let today = day(2026, 1, 31);
+
+        assert_eq!(parse_date("3d", today).unwrap(), day(2026, 2, 3));
+        assert_eq!(parse_date("+2w", today).unwrap(), day(2026, 2, 14));
+        assert_eq!(parse_date("1m", today).unwrap(), day(2026, 2, 28));
+        assert!(parse_date("soon", today).is_err());
+        assert!(add_span("7x", today).is_err());
+        assert!(add_span("0d", today).is_err());
+    }
+}
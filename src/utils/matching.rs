@@ -1,3 +1,4 @@
+use crate::models::tasks::task::normalize_id;
 use anyhow::{Result, anyhow};
 
 const AMBIGUOUS_SCORE_DELTA: f64 = 5.0;
@@ -18,6 +19,53 @@ pub struct MatchCandidate<T> {
     pub exact: bool,
 }
 
+/// Resolve the task ID a command targets: `--id`, or a lone `#id` query
+/// naming an item that `exists`. Any other `#word` is left to the
+/// description search, since it may be a tag.
+pub fn requested_id(
+    query: &str,
+    id: Option<&str>,
+    exists: impl Fn(&str) -> bool,
+) -> Option<String> {
+    if let Some(id) = id {
+        return Some(normalize_id(id));
+    }
+    let id = normalize_id(query_word(query)?);
+    exists(&id).then_some(id)
+}
+
+/// The word of a lone `#word` query.
+fn query_word(query: &str) -> Option<&str> {
+    query
+        .trim()
+        .strip_prefix('#')
+        .filter(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+}
+
+/// Score a lone `#word` query that named no ID: an item tagged `word`
+/// matches fully, otherwise `word` is scored against the description.
+pub fn tag_query_score(
+    query: &str,
+    tags: &[String],
+    description_score: impl FnOnce(&str) -> Option<i64>,
+) -> Option<i64> {
+    let word = query_word(query)?;
+    if tags.iter().any(|tag| tag.eq_ignore_ascii_case(word)) {
+        return Some(100);
+    }
+    description_score(word)
+}
+
+/// Build a candidate for a task addressed directly by ID.
+pub fn id_match<T>(value: T, label: impl Into<String>) -> MatchCandidate<T> {
+    MatchCandidate {
+        value,
+        score: i64::MAX,
+        label: label.into(),
+        exact: true,
+    }
+}
+
 pub fn select_unambiguous<T>(
     mut candidates: Vec<MatchCandidate<T>>,
     min_score: f64,
@@ -73,7 +121,10 @@ fn ambiguous<T>(best: &MatchCandidate<T>, second: &MatchCandidate<T>) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{MatchCandidate, score_passes, score_percent, select_unambiguous};
+    use super::{
+        MatchCandidate, requested_id, score_passes, score_percent, select_unambiguous,
+        tag_query_score,
+    };
 
     #[test]
     fn score_percent_caps_raw_matcher_scores() {
@@ -87,6 +138,33 @@ mod tests {
         assert!(!score_passes(25, 50.0));
     }
 
+    #[test]
+    fn requested_id_prefers_flag_and_accepts_hash_queries() {
+        let exists = |id: &str| id == "a1b2c3";
+        assert_eq!(
+            requested_id("#A1B2C3", None, exists).as_deref(),
+            Some("a1b2c3")
+        );
+        assert_eq!(
+            requested_id("ignored", Some("#ff00aa"), exists).as_deref(),
+            Some("ff00aa")
+        );
+        assert_eq!(requested_id("#parser", None, exists), None);
+        assert_eq!(requested_id("#tag and words", None, exists), None);
+        assert_eq!(requested_id("plain text", None, exists), None);
+    }
+
+    #[test]
+    fn tag_query_score_matches_tags_then_the_bare_word() {
+        let tags = vec!["Parser".to_string()];
+        assert_eq!(tag_query_score("#parser", &tags, |_| None), Some(100));
+        assert_eq!(
+            tag_query_score("#lexer", &tags, |word| (word == "lexer").then_some(70)),
+            Some(70)
+        );
+        assert_eq!(tag_query_score("lexer", &tags, |_| Some(70)), None);
+    }
+
     #[test]
     fn select_unambiguous_rejects_close_matches() {
         let err = select_unambiguous(
@@ -1,3 +1,6 @@
+pub mod atomic_file;
+pub mod conventional_commit;
+pub mod dates;
 pub mod matching;
 pub mod project_paths;
 pub mod task_input;
@@ -1,6 +1,10 @@
-use anyhow::{Result, anyhow};
+use crate::models::app_config::Files;
+use anyhow::{Context, Result, anyhow};
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROJECT_CONFIG_FILE: &str = ".tally.toml";
 
 pub fn global_config_dir() -> Result<PathBuf> {
     dirs::config_dir()
@@ -9,53 +13,162 @@ pub fn global_config_dir() -> Result<PathBuf> {
 }
 
 pub fn find_project_root() -> Result<PathBuf> {
-    let mut current = env::current_dir()?;
+    let global_config = global_config_dir()?.join("config.toml");
+    find_root_from(&env::current_dir()?, &global_config)
+}
+
+/// Walk up from `start` to the first directory holding its configured TODO file.
+///
+/// A `.tally.toml` that cannot be read is an error only in the directory
+/// that becomes the root; elsewhere it is reported and skipped.
+fn find_root_from(start: &Path, global_config: &Path) -> Result<PathBuf> {
+    let global_files = configured_files(&[global_config])?;
+    let mut current = start.to_path_buf();
 
     loop {
-        if current.join("TODO.md").exists() {
-            return Ok(current);
+        match configured_files(&[global_config, &current.join(PROJECT_CONFIG_FILE)]) {
+            Ok(files) if current.join(&files.todo).exists() => return Ok(current),
+            Ok(_) => {}
+            // Without its own config, the directory is the root if it holds
+            // a TODO file under the global name.
+            Err(e) if current.join(&global_files.todo).exists() => return Err(e),
+            Err(e) => eprintln!("Warning: {:#}; looking for the project further up", e),
         }
 
         match current.parent() {
             Some(parent) => current = parent.to_path_buf(),
             None => {
                 return Err(anyhow!(
-                    "No TODO.md found. run a write command like 'tally add' to initialize this project"
+                    "No {} found. run a write command like 'tally add' to initialize this project",
+                    global_files.todo
                 ));
             }
         }
     }
 }
 
+/// Read the `[files]` section from each config file in turn, later files winning.
+///
+/// Only this section is read here; the full config is checked by `ConfigStorage`.
+fn configured_files(config_files: &[&Path]) -> Result<Files> {
+    let mut files = Files::default();
+
+    for path in config_files.iter().filter(|path| path.exists()) {
+        let content = fs::read_to_string(path)?;
+        let value: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config {}", path.display()))?;
+        let Some(section) = value.get("files") else {
+            continue;
+        };
+
+        if let Some(todo) = section.get("todo").and_then(toml::Value::as_str) {
+            files.todo = normalize(todo);
+        }
+        if let Some(changelog) = section.get("changelog").and_then(toml::Value::as_str) {
+            files.changelog = normalize(changelog);
+        }
+    }
+
+    Ok(files)
+}
+
+fn normalize(path: &str) -> String {
+    path.trim().trim_start_matches("./").to_string()
+}
+
 pub struct ProjectPaths {
     pub todo_file: PathBuf,
     pub changelog_file: PathBuf,
+    /// TODO and CHANGELOG paths relative to `root`, as configured under `[files]`.
+    pub files: Files,
     pub config_file: PathBuf,
+    /// Shared per-project settings layered over `config_file`.
+    pub project_config_file: PathBuf,
+    /// Tally's private, git-ignored state such as the undo journal.
+    pub state_dir: PathBuf,
     pub root: PathBuf,
 }
 
 impl ProjectPaths {
     pub fn get_paths() -> Result<Self> {
-        let root = find_project_root()?;
-        let config_dir = global_config_dir()?;
-
-        Ok(Self {
-            todo_file: root.join("TODO.md"),
-            changelog_file: root.join("CHANGELOG.md"),
-            config_file: config_dir.join("config.toml"),
-            root,
-        })
+        Self::at(find_project_root()?)
     }
 
     pub fn for_current_dir() -> Result<Self> {
-        let root = env::current_dir()?;
-        let config_dir = global_config_dir()?;
+        Self::at(env::current_dir()?)
+    }
+
+    /// The project found by walking up from `dir` rather than the current
+    /// directory.
+    pub fn containing(dir: &Path) -> Result<Self> {
+        let global_config = global_config_dir()?.join("config.toml");
+        Self::at(find_root_from(dir, &global_config)?)
+    }
+
+    /// The project rooted at `root`, whether or not its files exist yet.
+    pub fn at(root: PathBuf) -> Result<Self> {
+        let config_file = global_config_dir()?.join("config.toml");
+        let project_config_file = root.join(PROJECT_CONFIG_FILE);
+        let files = configured_files(&[&config_file, &project_config_file])?;
 
         Ok(Self {
-            todo_file: root.join("TODO.md"),
-            changelog_file: root.join("CHANGELOG.md"),
-            config_file: config_dir.join("config.toml"),
+            todo_file: root.join(&files.todo),
+            changelog_file: root.join(&files.changelog),
+            files,
+            config_file,
+            project_config_file,
+            state_dir: root.join(".tally"),
             root,
         })
     }
+
+    /// The TODO and CHANGELOG paths relative to the project root.
+    pub fn tally_files(&self) -> [&str; 2] {
+        [&self.files.todo, &self.files.changelog]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn root_discovery_uses_project_file_names() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_path_buf();
+        let nested = root.join("src").join("parser");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(
+            root.join(PROJECT_CONFIG_FILE),
+            "[files]\ntodo = \"./docs/TASKS.md\"\n",
+        )
+        .unwrap();
+        let global = root.join("global.toml");
+
+        fs::write(root.join("docs").join("TASKS.md"), "# Tasks\n").unwrap();
+        assert_eq!(find_root_from(&nested, &global).unwrap(), root);
+
+        let files = configured_files(&[&global, &root.join(PROJECT_CONFIG_FILE)]).unwrap();
+        assert_eq!(files.todo, "docs/TASKS.md");
+        assert_eq!(files.changelog, "CHANGELOG.md");
+    }
+
+    #[test]
+    fn malformed_config_fails_only_in_the_chosen_root() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_path_buf();
+        let nested = root.join("vendor").join("lib");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("TODO.md"), "# TODO\n").unwrap();
+        fs::write(root.join("vendor").join(PROJECT_CONFIG_FILE), "[files\n").unwrap();
+        let global = root.join("global.toml");
+
+        assert_eq!(find_root_from(&nested, &global).unwrap(), root);
+
+        fs::write(root.join(PROJECT_CONFIG_FILE), "[files\n").unwrap();
+        let err = find_root_from(&nested, &global).unwrap_err();
+        assert!(err.to_string().contains("Failed to parse config"));
+    }
 }
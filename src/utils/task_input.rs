@@ -1,10 +1,16 @@
 use crate::models::common::Priority;
+use crate::utils::dates;
 use anyhow::Result;
+use chrono::NaiveDate;
 
 pub struct ParsedTaskInput {
     pub description: String,
     pub priority: Priority,
     pub tags: Vec<String>,
+    /// From an inline `due:<date>`.
+    pub due: Option<NaiveDate>,
+    /// From an inline `scheduled:<date>`.
+    pub scheduled: Option<NaiveDate>,
 }
 
 pub fn parse_task_input(
@@ -15,6 +21,8 @@ pub fn parse_task_input(
     let mut description_parts = Vec::new();
     let mut parsed_priority = None;
     let mut parsed_tags = Vec::new();
+    let mut due = None;
+    let mut scheduled = None;
 
     for part in description.as_ref().split_whitespace() {
         if let Some(tag) = part.strip_prefix('#') {
@@ -24,6 +32,16 @@ pub fn parse_task_input(
             continue;
         }
 
+        // A word like `due:process` that is not a date is description text.
+        if let Some(date) = inline_date(part, "due:") {
+            due = Some(date);
+            continue;
+        }
+        if let Some(date) = inline_date(part, "scheduled:") {
+            scheduled = Some(date);
+            continue;
+        }
+
         match parse_priority_marker(part) {
             Some(priority) => parsed_priority = Some(priority),
             None => description_parts.push(part),
@@ -42,9 +60,19 @@ pub fn parse_task_input(
             .or(parsed_priority)
             .unwrap_or(Priority::Medium),
         tags: tags_override.unwrap_or(parsed_tags),
+        due,
+        scheduled,
     })
 }
 
+fn inline_date(part: &str, key: &str) -> Option<NaiveDate> {
+    let prefix = part.get(..key.len())?;
+    if !prefix.eq_ignore_ascii_case(key) {
+        return None;
+    }
+    dates::parse_date(&part[key.len()..], dates::today()).ok()
+}
+
 fn parse_priority_marker(value: &str) -> Option<Priority> {
     match value.to_ascii_lowercase().as_str() {
         "(high)" => Some(Priority::High),
@@ -94,4 +122,36 @@ mod tests {
         assert_eq!(input.priority, Priority::Medium);
         assert_eq!(input.tags, vec!["docs"]);
     }
+
+    #[test]
+    fn parses_inline_dates() {
+        let input = parse_task_input(
+            "Ship beta due:2026-11-01 Scheduled:2026-10-20 #release",
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(input.description, "Ship beta");
+        assert_eq!(input.due, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(input.scheduled, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(input.tags, vec!["release"]);
+    }
+
+    #[test]
+    fn keeps_words_that_are_not_dates_in_the_description() {
+        let input = parse_task_input(
+            "Document the due:process and scheduled: jobs due:2026-11-01",
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            input.description,
+            "Document the due:process and scheduled: jobs"
+        );
+        assert_eq!(input.due, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(input.scheduled, None);
+    }
 }